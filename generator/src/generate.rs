@@ -134,7 +134,7 @@ fn generate_array(context: &mut Context, count: &u32, node: &Node) -> NodeResult
 fn generate_scalar(context: &mut Context, _scalar: &Scalar) -> NodeResult {
 	// TODO: handle subtypes i.e. references
 
-	let (field_index, column) = match context.columns.get(0) {
+	let (field_index, column) = match context.columns.first() {
 		Some(column) => column,
 		None => {
 			// Definitions include columns that do not exist - represent them as impossible options.
@@ -54,7 +54,7 @@ fn main() -> Result<()> {
 	};
 	let ironworks =
 		Ironworks::new().with_resource(Box::new(SqPack::new(fs_resource)) as Box<dyn Resource>);
-	let excel = Excel::new(std::sync::Arc::new(ironworks));
+	let excel = Excel::new(ironworks);
 
 	let (provider, version, schemas) = saint_coinach()?;
 	let src_dir = build_scaffold(provider, version, excel.version()?, &out_dir)?;
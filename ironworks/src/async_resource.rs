@@ -0,0 +1,125 @@
+use std::{future::Future, io::Read, pin::Pin, sync::Arc};
+
+use crate::{
+	error::{Error, Result},
+	ironworks::Resource,
+};
+
+/// Boxed, sendable future returned by async resources.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Resource layer that can provide data to an ironworks instance without
+/// blocking the calling task. Synchronous resources can be lifted into an
+/// async resource with [`Blocking`].
+pub trait AsyncResource: Send + Sync + 'static {
+	/// Get the version string for the file at `path`. A return value of
+	/// `Err(Error::NotFound(ErrorValue::Path(_)))` will result in lookups
+	/// continuing to the next resource.
+	fn version<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<String>>;
+
+	/// Get the contents of the file at `path`. A return value of
+	/// `Err(Error::NotFound(ErrorValue::Path(_)))` will result in lookups
+	/// continuing to the next resource.
+	fn file<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<Vec<u8>>>;
+
+	/// Check whether the file at `path` exists. The default implementation
+	/// falls back to attempting a full `file` lookup.
+	fn exists<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<bool>> {
+		Box::pin(async move {
+			match self.file(path).await {
+				Ok(_) => Ok(true),
+				Err(Error::NotFound(_)) => Ok(false),
+				Err(error) => Err(error),
+			}
+		})
+	}
+}
+
+impl<R: AsyncResource + ?Sized> AsyncResource for Box<R> {
+	fn version<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<String>> {
+		self.as_ref().version(path)
+	}
+
+	fn file<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<Vec<u8>>> {
+		self.as_ref().file(path)
+	}
+
+	fn exists<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<bool>> {
+		self.as_ref().exists(path)
+	}
+}
+
+impl<R: AsyncResource + ?Sized> AsyncResource for Arc<R> {
+	fn version<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<String>> {
+		self.as_ref().version(path)
+	}
+
+	fn file<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<Vec<u8>>> {
+		self.as_ref().file(path)
+	}
+
+	fn exists<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<bool>> {
+		self.as_ref().exists(path)
+	}
+}
+
+/// Adapter lifting a synchronous [`Resource`] into an [`AsyncResource`]. Reads
+/// are performed on tokio's blocking thread pool, and as such must be awaited
+/// from within a tokio runtime.
+#[derive(Debug)]
+pub struct Blocking<R> {
+	resource: Arc<R>,
+}
+
+impl<R> Clone for Blocking<R> {
+	fn clone(&self) -> Self {
+		Self {
+			resource: self.resource.clone(),
+		}
+	}
+}
+
+impl<R: Resource + Send + Sync> Blocking<R> {
+	/// Wrap a synchronous resource for use in async contexts.
+	pub fn new(resource: impl Into<Arc<R>>) -> Self {
+		Self {
+			resource: resource.into(),
+		}
+	}
+
+	fn spawn<'a, T, F>(&self, path: &str, f: F) -> BoxFuture<'a, Result<T>>
+	where
+		T: Send + 'static,
+		F: FnOnce(&R, &str) -> Result<T> + Send + 'static,
+	{
+		let resource = self.resource.clone();
+		let path = path.to_string();
+
+		// Spawned on first poll, such that futures can be built outside a runtime,
+		// and no work is started for futures dropped before they're awaited.
+		Box::pin(async move {
+			tokio::task::spawn_blocking(move || f(&resource, &path))
+				.await
+				.map_err(|error| Error::Resource(error.into()))?
+		})
+	}
+}
+
+impl<R: Resource + Send + Sync> AsyncResource for Blocking<R> {
+	fn version<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<String>> {
+		self.spawn(path, |resource, path| resource.version(path))
+	}
+
+	fn file<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<Vec<u8>>> {
+		// Streams aren't required to be Send, so they're drained before leaving the blocking task.
+		self.spawn(path, |resource, path| {
+			let mut data = Vec::new();
+			resource.file(path)?.read_to_end(&mut data)?;
+			Ok(data)
+		})
+	}
+
+	fn exists<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<bool>> {
+		self.spawn(path, |resource, path| resource.exists(path))
+	}
+}
@@ -0,0 +1,140 @@
+use std::{
+	collections::{BTreeSet, HashSet, VecDeque},
+	io::Read,
+};
+
+use crate::{
+	error::{Error, Result},
+	sqpack::{self, SqPack},
+};
+
+use super::references::references;
+
+/// Walk the files at `seeds`, and every file they reference in turn, until no
+/// further paths are found. Referenced paths are only followed if they exist
+/// within `sqpack`. Returns the paths discovered, excluding the seeds.
+///
+/// Files that fail to parse are skipped, rather than failing the walk.
+pub fn discover<R: sqpack::Resource>(
+	sqpack: &SqPack<R>,
+	seeds: impl IntoIterator<Item = impl AsRef<str>>,
+) -> Result<BTreeSet<String>> {
+	let mut seen = HashSet::new();
+	let mut queue = VecDeque::new();
+	for seed in seeds {
+		let seed = seed.as_ref().to_lowercase();
+		if seen.insert(seed.clone()) {
+			queue.push_back(seed);
+		}
+	}
+
+	let mut discovered = BTreeSet::new();
+	while let Some(path) = queue.pop_front() {
+		let mut data = Vec::new();
+		match sqpack.file(&path) {
+			Ok(mut file) => file.read_to_end(&mut data)?,
+			Err(Error::NotFound(_)) => continue,
+			Err(error) => return Err(error),
+		};
+
+		let Ok(found) = references(&path, &data) else {
+			continue;
+		};
+
+		for reference in found {
+			if !seen.insert(reference.clone()) || !sqpack.exists(&reference)? {
+				continue;
+			}
+
+			discovered.insert(reference.clone());
+			queue.push_back(reference);
+		}
+	}
+
+	Ok(discovered)
+}
+
+#[cfg(test)]
+mod test {
+	use crate::sqpack::{FileKind, Package, SqPack, VInstall, Writer};
+
+	use super::{
+		super::fixture::{layer_group, material, model, shared_group},
+		discover,
+	};
+
+	const SHARED_GROUP: &str = "bg/ffxiv/zone/asset/prefab.sgb";
+	const LAYER_GROUP: &str = "bg/ffxiv/zone/level/bg.lgb";
+	const MODEL: &str = "bg/ffxiv/zone/bgparts/rock.mdl";
+	const MATERIAL: &str = "bg/ffxiv/zone/material/rock.mtrl";
+	const TEXTURE: &str = "bg/ffxiv/zone/texture/rock_d.tex";
+
+	/// A chain of files each referencing the next, alongside references to
+	/// files that are not in the package.
+	fn package(writer: &mut Writer) {
+		let files = [
+			(
+				SHARED_GROUP,
+				shared_group(
+					LAYER_GROUP,
+					"bg/ffxiv/zone/level/sky.svb",
+					"bg/ffxiv/zone/level/culling.lcb",
+				),
+			),
+			(
+				LAYER_GROUP,
+				layer_group(MODEL, "bg/ffxiv/zone/collision/rock.pcb"),
+			),
+			(MODEL, model(&[MATERIAL])),
+			(
+				MATERIAL,
+				material(&[TEXTURE, "bg/ffxiv/zone/texture/rock_n.tex"]),
+			),
+		];
+		for (path, data) in files {
+			writer.add_file(path, &data).unwrap();
+		}
+		writer
+			.add_file_as(TEXTURE, FileKind::Standard, &[0; 16])
+			.unwrap();
+	}
+
+	fn sqpack(writer: Writer) -> SqPack<VInstall<Package>> {
+		SqPack::new(VInstall::at_sqpack(writer.build().unwrap()))
+	}
+
+	#[test]
+	fn follows_references_to_a_fixpoint() {
+		let mut writer = Writer::new();
+		package(&mut writer);
+		let sqpack = sqpack(writer);
+
+		assert_eq!(
+			discover(&sqpack, [SHARED_GROUP]).unwrap(),
+			[LAYER_GROUP, MATERIAL, MODEL, TEXTURE]
+				.map(String::from)
+				.into()
+		);
+
+		// Seeds are matched case-insensitively, and never reported themselves.
+		assert_eq!(
+			discover(&sqpack, ["BG/FFXIV/ZONE/BGPARTS/ROCK.MDL", MATERIAL]).unwrap(),
+			[TEXTURE].map(String::from).into()
+		);
+	}
+
+	#[test]
+	fn skips_missing_and_malformed_files() {
+		let mut writer = Writer::new();
+		package(&mut writer);
+		writer
+			.add_file_as(MODEL, FileKind::Standard, &[1; 8])
+			.unwrap();
+		let sqpack = sqpack(writer);
+
+		assert_eq!(
+			discover(&sqpack, ["bg/ffxiv/zone/asset/missing.sgb", SHARED_GROUP]).unwrap(),
+			[LAYER_GROUP, MODEL].map(String::from).into()
+		);
+	}
+}
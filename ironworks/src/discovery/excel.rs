@@ -0,0 +1,52 @@
+use crate::{
+	error::Result,
+	excel::{Excel, Field},
+	ironworks::Resource,
+};
+
+/// Collect the paths referenced by a column of an Excel sheet, mapping each
+/// field to its paths with `map`. Model columns, whose paths depend on the
+/// kind of model, are expected to be mapped by the caller.
+pub fn column_paths<R: Resource>(
+	excel: &Excel<R>,
+	sheet: &str,
+	column: usize,
+	map: impl Fn(&Field) -> Vec<String>,
+) -> Result<Vec<String>> {
+	let mut paths = Vec::new();
+	for row in excel.sheet(sheet)? {
+		paths.extend(map(&row.field(column)?));
+	}
+
+	paths.sort();
+	paths.dedup();
+	Ok(paths)
+}
+
+/// Paths of the standard and high resolution textures for an icon ID, as found
+/// in `Icon` columns. An ID of 0 represents no icon.
+pub fn icon_paths(icon: u32) -> Vec<String> {
+	if icon == 0 {
+		return vec![];
+	}
+
+	let folder = icon / 1000 * 1000;
+	vec![
+		format!("ui/icon/{folder:06}/{icon:06}.tex"),
+		format!("ui/icon/{folder:06}/{icon:06}_hr1.tex"),
+	]
+}
+
+#[cfg(test)]
+mod test {
+	use super::icon_paths;
+
+	#[test]
+	fn icon_paths_are_grouped_by_thousand() {
+		assert_eq!(
+			icon_paths(60_123),
+			["ui/icon/060000/060123.tex", "ui/icon/060000/060123_hr1.tex"]
+		);
+		assert!(icon_paths(0).is_empty());
+	}
+}
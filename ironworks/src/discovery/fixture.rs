@@ -0,0 +1,244 @@
+//! Minimal files of each kind discovery reads, referencing the paths they are
+//! built with.
+
+fn string(path: &str) -> Vec<u8> {
+	[path.as_bytes(), b"\0"].concat()
+}
+
+/// A model with one mesh per material, each drawn at the highest LOD.
+pub fn model(materials: &[&str]) -> Vec<u8> {
+	let count = u16::try_from(materials.len()).unwrap();
+	let mut strings = Vec::new();
+	let mut offsets = Vec::new();
+	for material in materials {
+		offsets.push(u32::try_from(strings.len()).unwrap());
+		strings.extend(string(material));
+	}
+
+	let mut body = Vec::new();
+	// One vertex declaration, every element past the stream sentinel unused.
+	body.extend([255u8, 0, 0, 0, 0, 0, 0, 0].repeat(17));
+	body.extend(count.to_le_bytes());
+	body.extend([0; 2]);
+	body.extend(u32::try_from(strings.len()).unwrap().to_le_bytes());
+	body.extend(strings);
+
+	// Model header, counting only the meshes and their materials.
+	body.extend(1.0f32.to_le_bytes());
+	for count in [count, 0, 0, count, 0, 0, 0, 0, 0] {
+		body.extend(count.to_le_bytes());
+	}
+	body.extend([1, 0]);
+	body.extend([0; 32]);
+
+	let mut lod = [0; 60];
+	lod[2..4].copy_from_slice(&count.to_le_bytes());
+	body.extend(lod);
+	body.extend([0; 2 * 60]);
+
+	for index in 0..count {
+		let mut mesh = [0; 36];
+		mesh[8..10].copy_from_slice(&index.to_le_bytes());
+		body.extend(mesh);
+	}
+	body.extend(offsets.iter().flat_map(|offset| offset.to_le_bytes()));
+	// Submesh bone map, empty, then no padding ahead of the bounding boxes.
+	body.extend(0u32.to_le_bytes());
+	body.push(0);
+	body.extend([0; 4 * 32]);
+
+	let mut bytes = Vec::new();
+	bytes.extend(0x0100_0006u32.to_le_bytes());
+	bytes.extend(136u32.to_le_bytes());
+	bytes.extend(u32::try_from(body.len() - 136).unwrap().to_le_bytes());
+	bytes.extend(1u16.to_le_bytes());
+	bytes.extend(count.to_le_bytes());
+	bytes.extend(u32::try_from(0x44 + body.len()).unwrap().to_le_bytes());
+	bytes.extend([0; 8 + 12 + 12 + 12]);
+	bytes.extend([1, 0, 0, 0]);
+	bytes.extend(body);
+	bytes
+}
+
+/// A material sampling `textures`, with no colour table or shader values.
+pub fn material(textures: &[&str]) -> Vec<u8> {
+	let mut strings = Vec::new();
+	let mut offsets = Vec::new();
+	for texture in textures {
+		offsets.push(u16::try_from(strings.len()).unwrap());
+		strings.extend(string(texture));
+	}
+	let shader = u16::try_from(strings.len()).unwrap();
+	strings.extend(string("character.shpk"));
+
+	let mut bytes = Vec::new();
+	bytes.extend(0x0103_0000u32.to_le_bytes());
+	bytes.extend(0u16.to_le_bytes());
+	bytes.extend(0u16.to_le_bytes());
+	bytes.extend(u16::try_from(strings.len()).unwrap().to_le_bytes());
+	bytes.extend(shader.to_le_bytes());
+	bytes.extend([u8::try_from(textures.len()).unwrap(), 0, 0, 0]);
+	for offset in offsets {
+		bytes.extend(offset.to_le_bytes());
+		bytes.extend(0u16.to_le_bytes());
+	}
+	bytes.extend(strings);
+	// Material header, declaring no keys, constants, samplers or values.
+	bytes.extend([0; 12]);
+	bytes
+}
+
+/// An effect holding nothing but `textures`.
+pub fn effect(textures: &[&str]) -> Vec<u8> {
+	/// One block: the tag back to front, then its length, then its payload padded out to four.
+	fn block(tag: &str, payload: &[u8]) -> Vec<u8> {
+		let mut bytes = tag.bytes().rev().collect::<Vec<_>>();
+		bytes.resize(4, 0);
+		bytes.extend(u32::try_from(payload.len()).unwrap().to_le_bytes());
+		bytes.extend(payload);
+		bytes.resize(8 + payload.len().next_multiple_of(4), 0);
+		bytes
+	}
+
+	let mut blocks = block("Ver", &0x2011_0913u32.to_le_bytes());
+	for texture in textures {
+		blocks.extend(block("Tex", &string(texture)));
+	}
+	block("AVFX", &blocks)
+}
+
+/// A timeline whose only item names the face library at `library`.
+pub fn timeline(library: &str) -> Vec<u8> {
+	let library = string(library);
+
+	let mut bytes = Vec::from(*b"TMLB");
+	bytes.extend(u32::try_from(24 + library.len()).unwrap().to_le_bytes());
+	bytes.extend(1u32.to_le_bytes());
+	// Offsets are measured from past the item's magic and size, so the path
+	// follows directly.
+	bytes.extend(*b"TMPP");
+	bytes.extend(12u32.to_le_bytes());
+	bytes.extend(4i32.to_le_bytes());
+	bytes.extend(library);
+	bytes
+}
+
+/// An animation pack with a single animation, driven by `timeline`, and no
+/// Havok data.
+pub fn pack(timeline: &[u8]) -> Vec<u8> {
+	let mut bytes = Vec::from(*b"pap ");
+	bytes.extend(0x0002_0001u32.to_le_bytes());
+	bytes.extend(1u16.to_le_bytes());
+	bytes.extend(101u16.to_le_bytes());
+	bytes.extend([0, 3]);
+	bytes.extend(26u32.to_le_bytes());
+	bytes.extend(66u32.to_le_bytes());
+	bytes.extend(66u32.to_le_bytes());
+
+	let mut name = [0; 32];
+	name[..4].copy_from_slice(b"idle");
+	bytes.extend(name);
+	bytes.extend(0u16.to_le_bytes());
+	bytes.extend(0i16.to_le_bytes());
+	bytes.extend(0i32.to_le_bytes());
+
+	bytes.extend(timeline);
+	bytes
+}
+
+/// A layer group with one layer, holding a background model placed from
+/// `asset`, colliding with `collision`.
+pub fn layer_group(asset: &str, collision: &str) -> Vec<u8> {
+	const LAYER_HEADER: usize = 52;
+	const INSTANCE: usize = 0x30 + 44;
+
+	let mut bytes = Vec::from(*b"LGB1");
+	bytes.extend(0u32.to_le_bytes());
+	bytes.extend(1u32.to_le_bytes());
+	bytes.extend(*b"LGP1");
+	bytes.extend(24u32.to_le_bytes());
+
+	// Offsets inside the section are measured from the four fields that end its header.
+	let heap = bytes.len();
+	let table = heap + 16;
+	let layer = table + 4;
+	let instance = layer + LAYER_HEADER + 4;
+	let asset = string(asset);
+	let collision = string(collision);
+	let names = instance + INSTANCE + asset.len() + collision.len();
+
+	bytes.extend(256u32.to_le_bytes());
+	bytes.extend(i32::try_from(names - heap).unwrap().to_le_bytes());
+	bytes.extend(16u32.to_le_bytes());
+	bytes.extend(1u32.to_le_bytes());
+	bytes.extend(i32::try_from(layer - table).unwrap().to_le_bytes());
+
+	bytes.extend(7u32.to_le_bytes());
+	bytes.extend(i32::try_from(names + 6 - layer).unwrap().to_le_bytes());
+	bytes.extend(u32::try_from(LAYER_HEADER).unwrap().to_le_bytes());
+	bytes.extend(1u32.to_le_bytes());
+	bytes.extend([1, 0, 0, 1]);
+	bytes.resize(layer + LAYER_HEADER, 0);
+	bytes.extend(4i32.to_le_bytes());
+
+	// The model's paths follow its fields, measured from the instance.
+	bytes.extend(1i32.to_le_bytes());
+	bytes.resize(instance + 0x30, 0);
+	bytes.extend(i32::try_from(INSTANCE).unwrap().to_le_bytes());
+	bytes.extend(i32::try_from(INSTANCE + asset.len()).unwrap().to_le_bytes());
+	bytes.resize(instance + INSTANCE, 0);
+	bytes.extend(asset);
+	bytes.extend(collision);
+
+	bytes.extend(b"group\0");
+	bytes.extend(b"layer\0");
+	bytes
+}
+
+/// A shared group placing no layers of its own, instead naming the layer group
+/// at `layer_group` and the sky visibility and light culling files at `sky`
+/// and `culling`.
+pub fn shared_group(layer_group: &str, sky: &str, culling: &str) -> Vec<u8> {
+	const GENERAL: usize = 92;
+
+	let mut bytes = Vec::from(*b"SGB1");
+	bytes.extend(0u32.to_le_bytes());
+	bytes.extend(1u32.to_le_bytes());
+	bytes.extend(*b"SCN1");
+	bytes.extend(0u32.to_le_bytes());
+
+	let body = bytes.len();
+	let general = body + 64;
+	let filters = general + GENERAL;
+	let resources = filters + 8;
+	let strings = resources + 4;
+	let sky_at = strings;
+	let culling_at = sky_at + sky.len() + 1;
+	let layer_group_at = culling_at + culling.len() + 1;
+
+	let mut offsets = [0i32; 16];
+	offsets[0] = i32::try_from(strings - body).unwrap();
+	offsets[2] = i32::try_from(general - body).unwrap();
+	offsets[3] = i32::try_from(filters - body).unwrap();
+	offsets[5] = i32::try_from(resources - body).unwrap();
+	offsets[6] = 1;
+	bytes.extend(offsets.map(i32::to_le_bytes).concat());
+
+	let mut block = [0i32; GENERAL / 4];
+	block[5] = i32::try_from(sky_at - general).unwrap();
+	block[13] = i32::try_from(culling_at - general).unwrap();
+	bytes.extend(block.map(i32::to_le_bytes).concat());
+
+	bytes.extend(8i32.to_le_bytes());
+	bytes.extend(0i32.to_le_bytes());
+	bytes.extend(
+		i32::try_from(layer_group_at - resources)
+			.unwrap()
+			.to_le_bytes(),
+	);
+
+	bytes.extend(string(sky));
+	bytes.extend(string(culling));
+	bytes.extend(string(layer_group));
+	bytes
+}
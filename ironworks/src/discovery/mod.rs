@@ -0,0 +1,16 @@
+//! Discovery of game paths referenced by the contents of other files.
+//!
+//! Paths found this way can be fed into a [`PathDatabase`](crate::sqpack::PathDatabase)
+//! to name index entries that no path list covers.
+
+mod discover;
+#[cfg(feature = "excel")]
+mod excel;
+#[cfg(test)]
+mod fixture;
+mod references;
+
+pub use {discover::discover, references::references};
+
+#[cfg(feature = "excel")]
+pub use excel::{column_paths, icon_paths};
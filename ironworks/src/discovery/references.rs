@@ -0,0 +1,274 @@
+use std::io::Cursor;
+
+use crate::{
+	error::Result,
+	file::{
+		File,
+		avfx::Avfx,
+		layer::{Instance, InstanceData, LayerGroup, Scene},
+		lgb::LayerGroupFile,
+		mdl::{Lod, ModelContainer},
+		mtrl::Material,
+		pap::AnimationPack,
+		sgb::SharedGroupFile,
+		tmb::{CommandKind, Item, Timeline},
+	},
+};
+
+/// Extract the game paths referenced by the file at `path`, given its contents.
+/// Files of a kind that is not known to reference other files return no paths.
+pub fn references(path: &str, data: &[u8]) -> Result<Vec<String>> {
+	let extension = path.rsplit_once('.').map_or("", |(_, extension)| extension);
+
+	let mut paths = match extension {
+		"mdl" => model(path, read(data)?)?,
+		"mtrl" => read::<Material>(data)?
+			.textures()
+			.iter()
+			.map(|texture| texture.path().to_string())
+			.collect(),
+		"avfx" => read::<Avfx>(data)?.textures().to_vec(),
+		"sgb" => scene(read::<SharedGroupFile>(data)?.scene()),
+		"lgb" => layer_group(read::<LayerGroupFile>(data)?.group()),
+		"pap" => {
+			let pack = read::<AnimationPack>(data)?;
+			let mut paths = Vec::new();
+			for timeline in pack.timelines() {
+				paths.extend(timeline_paths(&read(timeline)?));
+			}
+			paths
+		}
+		"tmb" => timeline_paths(&read(data)?),
+		_ => vec![],
+	};
+
+	paths.retain(|path| !path.is_empty());
+	for path in paths.iter_mut() {
+		*path = path.to_lowercase();
+	}
+	paths.sort();
+	paths.dedup();
+
+	Ok(paths)
+}
+
+fn read<F: File>(data: &[u8]) -> Result<F> {
+	F::read(Cursor::new(data.to_vec()))
+}
+
+fn model(path: &str, container: ModelContainer) -> Result<Vec<String>> {
+	// Character materials are named relative to the model's root, i.e.
+	// `/mt_c0101e0001_top_a.mtrl`, and stored under a variant directory. Only
+	// the first variant can be derived without further context.
+	let root = path.split_once("/model/").map(|(root, _)| root);
+
+	let mut paths = Vec::new();
+	for mesh in container.model(Lod::High).meshes() {
+		let material = mesh.material()?;
+		match (material.strip_prefix('/'), root) {
+			(Some(name), Some(root)) => paths.push(format!("{root}/material/v0001/{name}")),
+			(Some(_), None) => {}
+			(None, _) => paths.push(material),
+		}
+	}
+
+	Ok(paths)
+}
+
+fn scene(scene: &Scene) -> Vec<String> {
+	let mut paths = scene.layer_group_paths().clone();
+	paths.push(scene.sky_visibility_path().clone());
+	paths.push(scene.light_culling_path().clone());
+
+	for environment in scene.environments() {
+		paths.push(environment.asset_path().clone());
+		paths.push(environment.sound_asset_path().clone());
+	}
+
+	for group in scene.layer_groups() {
+		paths.extend(layer_group(group));
+	}
+
+	paths
+}
+
+fn layer_group(group: &LayerGroup) -> Vec<String> {
+	group
+		.layers()
+		.iter()
+		.flat_map(|layer| layer.instances())
+		.flat_map(instance)
+		.collect()
+}
+
+fn instance(instance: &Instance) -> Vec<String> {
+	let paths = match instance.data() {
+		InstanceData::BgPart(part) => vec![part.asset_path(), part.collision_asset_path()],
+		InstanceData::Light(light) => vec![light.texture_path()],
+		InstanceData::Vfx(vfx) => vec![vfx.asset_path()],
+		InstanceData::SharedGroup(group) => vec![group.asset_path()],
+		InstanceData::Sound(sound) => vec![sound.asset_path()],
+		InstanceData::EnvSpace(space) => vec![space.asset_path(), space.sound_asset_path()],
+		InstanceData::EnvLocation(location) => vec![
+			location.ambient_light_asset_path(),
+			location.env_map_asset_path(),
+		],
+		InstanceData::Decal(decal) => vec![
+			decal.diffuse_path(),
+			decal.normal_path(),
+			decal.specular_path(),
+		],
+		_ => vec![],
+	};
+
+	paths.into_iter().cloned().collect()
+}
+
+fn timeline_paths(timeline: &Timeline) -> Vec<String> {
+	timeline
+		.items()
+		.iter()
+		.filter_map(|item| match item {
+			Item::FaceLibrary(library) => library.path(),
+			Item::Command(command) => match command.kind() {
+				CommandKind::C002(command) => command.path(),
+				CommandKind::C009(command) => command.path(),
+				CommandKind::C010(command) => command.path(),
+				CommandKind::C012(command) => command.path(),
+				CommandKind::C063(command) => command.path(),
+				CommandKind::C173(command) => command.path(),
+				_ => None,
+			},
+			_ => None,
+		})
+		.map(String::from)
+		.collect()
+}
+
+#[cfg(test)]
+mod test {
+	use super::{
+		super::fixture::{effect, layer_group, material, model, pack, shared_group, timeline},
+		references,
+	};
+
+	#[test]
+	fn unknown_kinds_reference_nothing() {
+		assert!(
+			references("exd/root.exl", b"EXLT,2\r\n")
+				.unwrap()
+				.is_empty()
+		);
+		assert!(references("common/font/font1.tex", &[]).unwrap().is_empty());
+	}
+
+	#[test]
+	fn malformed_files_fail() {
+		assert!(references("chara/xls/file.mtrl", &[0; 4]).is_err());
+	}
+
+	#[test]
+	fn models_reference_materials() {
+		let data = model(&[
+			"/mt_c0101e0001_top_a.mtrl",
+			"chara/common/material/Shared.mtrl",
+		]);
+		assert_eq!(
+			references("chara/equipment/e0001/model/c0101e0001_top.mdl", &data).unwrap(),
+			[
+				"chara/common/material/shared.mtrl",
+				"chara/equipment/e0001/material/v0001/mt_c0101e0001_top_a.mtrl",
+			]
+		);
+
+		// Relative materials cannot be placed without a model root to hang off.
+		assert_eq!(
+			references("bg/ffxiv/zone/rock.mdl", &data).unwrap(),
+			["chara/common/material/shared.mtrl"]
+		);
+	}
+
+	#[test]
+	fn materials_reference_textures() {
+		let data = material(&[
+			"chara/common/texture/a_d.tex",
+			"",
+			"chara/common/texture/a_d.tex",
+		]);
+		assert_eq!(
+			references("chara/common/material/a.mtrl", &data).unwrap(),
+			["chara/common/texture/a_d.tex"]
+		);
+	}
+
+	#[test]
+	fn effects_reference_textures() {
+		let data = effect(&[
+			"vfx/common/texture/uv_r.atex",
+			"vfx/common/texture/fire.atex",
+		]);
+		assert_eq!(
+			references("vfx/common/eff/fire.avfx", &data).unwrap(),
+			[
+				"vfx/common/texture/fire.atex",
+				"vfx/common/texture/uv_r.atex"
+			]
+		);
+	}
+
+	#[test]
+	fn timelines_reference_libraries() {
+		let data = timeline("chara/human/c0101/animation/f0001/resident/face.pap");
+		assert_eq!(
+			references("chara/action/emote/wave.tmb", &data).unwrap(),
+			["chara/human/c0101/animation/f0001/resident/face.pap"]
+		);
+	}
+
+	#[test]
+	fn packs_reference_through_their_timelines() {
+		let data = pack(&timeline(
+			"chara/human/c0101/animation/f0001/resident/face.pap",
+		));
+		assert_eq!(
+			references(
+				"chara/human/c0101/animation/a0001/bt_common/idle.pap",
+				&data
+			)
+			.unwrap(),
+			["chara/human/c0101/animation/f0001/resident/face.pap"]
+		);
+	}
+
+	#[test]
+	fn layer_groups_reference_instance_assets() {
+		let data = layer_group(
+			"bg/ffxiv/zone/bgparts/rock.mdl",
+			"bg/ffxiv/zone/collision/rock.pcb",
+		);
+		assert_eq!(
+			references("bg/ffxiv/zone/level/bg.lgb", &data).unwrap(),
+			[
+				"bg/ffxiv/zone/bgparts/rock.mdl",
+				"bg/ffxiv/zone/collision/rock.pcb",
+			]
+		);
+	}
+
+	#[test]
+	fn shared_groups_reference_scene_assets() {
+		let data = shared_group(
+			"bg/ffxiv/zone/level/bg.lgb",
+			"bg/ffxiv/zone/level/sky.svb",
+			"bg/ffxiv/zone/level/culling.lcb",
+		);
+		assert_eq!(
+			references("bg/ffxiv/zone/asset/prefab.sgb", &data).unwrap(),
+			[
+				"bg/ffxiv/zone/level/bg.lgb",
+				"bg/ffxiv/zone/level/culling.lcb",
+				"bg/ffxiv/zone/level/sky.svb",
+			]
+		);
+	}
+}
@@ -55,6 +55,10 @@ pub enum ErrorValue {
 		sheet: Option<String>,
 	},
 
+	/// An Excel language.
+	#[cfg(feature = "excel")]
+	Language(crate::excel::Language),
+
 	/// A SeString rich text value.
 	#[cfg(feature = "sestring")]
 	SeString,
@@ -79,6 +83,9 @@ impl fmt::Display for ErrorValue {
 			#[cfg(feature = "excel")]
 			Self::Sheet(sheet) => write!(formatter, "Excel sheet {sheet:?}"),
 
+			#[cfg(feature = "excel")]
+			Self::Language(language) => write!(formatter, "Excel language {language}"),
+
 			#[cfg(feature = "exd")]
 			Self::Row { row, subrow, sheet } => write!(
 				formatter,
@@ -1,19 +1,19 @@
-use std::{
-	convert::Infallible,
-	sync::{Arc, OnceLock},
-};
+use std::sync::{Arc, OnceLock};
 
 use derivative::Derivative;
 
 use crate::{
 	error::{Error, ErrorValue, Result},
 	file::exl,
-	ironworks::Ironworks,
-	utility::{HashMapCache, HashMapCacheExt},
+	ironworks::{Ironworks, Resource},
+	utility::HashMapCache,
 };
 
+#[cfg(feature = "async")]
+use crate::async_resource::AsyncResource;
+
 use super::{
-	language::Language,
+	language::{Language, LanguagePolicy},
 	metadata::SheetMetadata,
 	path,
 	sheet::{Sheet, SheetCache},
@@ -22,11 +22,12 @@ use super::{
 /// An Excel database.
 #[derive(Derivative)]
 #[derivative(Debug)]
-pub struct Excel {
+pub struct Excel<R = Box<dyn Resource>> {
 	#[derivative(Debug = "ignore")]
-	ironworks: Arc<Ironworks>,
+	ironworks: Arc<Ironworks<R>>,
 
 	default_language: Language,
+	language_policy: LanguagePolicy,
 
 	#[derivative(Debug = "ignore")]
 	list: OnceLock<exl::ExcelList>,
@@ -34,13 +35,14 @@ pub struct Excel {
 	sheets: HashMapCache<String, SheetCache>,
 }
 
-impl Excel {
+impl<R> Excel<R> {
 	/// Build an view into the Excel database for a given ironworks instance.
-	pub fn new(ironworks: impl Into<Arc<Ironworks>>) -> Self {
+	pub fn new(ironworks: impl Into<Arc<Ironworks<R>>>) -> Self {
 		Self {
 			ironworks: ironworks.into(),
 
 			default_language: Language::None,
+			language_policy: LanguagePolicy::default(),
 
 			list: Default::default(),
 			sheets: Default::default(),
@@ -58,6 +60,48 @@ impl Excel {
 		self.default_language = language;
 	}
 
+	/// Set the policy used to select a language when a sheet does not support
+	/// the requested language.
+	pub fn with_language_policy(mut self, language_policy: LanguagePolicy) -> Self {
+		self.set_language_policy(language_policy);
+		self
+	}
+
+	/// Set the policy used to select a language when a sheet does not support
+	/// the requested language.
+	pub fn set_language_policy(&mut self, language_policy: LanguagePolicy) {
+		self.language_policy = language_policy;
+	}
+
+	fn sheet_in_list<S: SheetMetadata>(
+		&self,
+		metadata: S,
+		list: &exl::ExcelList,
+	) -> Result<Sheet<S, R>> {
+		let name = metadata.name();
+		if !list.has(&name) {
+			return Err(Error::NotFound(ErrorValue::Sheet(name)));
+		}
+
+		let cache = self
+			.sheets
+			.lock()
+			.expect("poisoned")
+			.entry(name)
+			.or_default()
+			.clone();
+
+		Ok(Sheet::new(
+			self.ironworks.clone(),
+			metadata,
+			self.default_language,
+			self.language_policy.clone(),
+			cache,
+		))
+	}
+}
+
+impl<R: Resource> Excel<R> {
 	/// Get the version string of the database.
 	pub fn version(&self) -> Result<String> {
 		self.ironworks.version(path::exl())
@@ -78,24 +122,76 @@ impl Excel {
 	}
 
 	/// Fetch a sheet from the database.
-	pub fn sheet<S: SheetMetadata>(&self, metadata: S) -> Result<Sheet<S>> {
-		let name = metadata.name();
-
+	///
+	/// Sheets read from data cached for the version of the sheet at the time
+	/// they are first read from. Fetch the sheet again to observe a newer version.
+	pub fn sheet<S: SheetMetadata>(&self, metadata: S) -> Result<Sheet<S, R>> {
 		let list = self.list()?;
-		if !list.has(&name) {
-			return Err(Error::NotFound(ErrorValue::Sheet(name)));
+		self.sheet_in_list(metadata, list)
+	}
+}
+
+#[cfg(feature = "async")]
+impl<R: AsyncResource> Excel<R> {
+	/// Get the version string of the database.
+	pub async fn version_async(&self) -> Result<String> {
+		self.ironworks.version_async(path::exl()).await
+	}
+
+	/// Fetch the authoritative list of sheets in the database.
+	pub async fn list_async(&self) -> Result<&exl::ExcelList> {
+		if let Some(list) = self.list.get() {
+			return Ok(list);
 		}
 
-		let cache = self
-			.sheets
-			.try_get_or_insert(name, || -> Result<_, Infallible> { Ok(Default::default()) })
+		let list = self
+			.ironworks
+			.file_async::<exl::ExcelList>(path::exl())
+			.await?;
+
+		Ok(self.list.get_or_init(|| list))
+	}
+
+	/// Fetch a sheet from the database.
+	///
+	/// Sheets read from data cached for the version of the sheet at the time
+	/// they are first read from. Fetch the sheet again to observe a newer version.
+	pub async fn sheet_async<S: SheetMetadata>(&self, metadata: S) -> Result<Sheet<S, R>> {
+		let list = self.list_async().await?;
+		self.sheet_in_list(metadata, list)
+	}
+}
+
+#[cfg(all(test, feature = "sqpack"))]
+mod test {
+	use crate::{
+		Ironworks,
+		sqpack::{SqPack, VInstall, Writer},
+	};
+
+	use super::{
+		super::{Language, fixture::item_writer},
+		Excel,
+	};
+
+	#[test]
+	fn reads_unversioned_sheets() {
+		let mut writer = Writer::new();
+		for (path, data) in item_writer().write().unwrap() {
+			writer.add_file(&path, &data).unwrap();
+		}
+		writer
+			.add_file("exd/root.exl", b"EXLT\r\nItem,-1\r\n")
 			.unwrap();
+		let sqpack = SqPack::new(VInstall::at_sqpack(writer.build().unwrap()));
 
-		Ok(Sheet::new(
-			self.ironworks.clone(),
-			metadata,
-			self.default_language,
-			cache,
-		))
+		let excel = Excel::new(Ironworks::new().with_resource(sqpack));
+		let sheet = excel.sheet("Item").unwrap();
+		let row = sheet.row_with_options(2, Language::English).unwrap();
+		let name = row.field(0).unwrap().into_string().unwrap();
+		assert_eq!(name.as_bytes(), b"Potion 2");
+
+		let index = sheet.index_with_options(0, Language::English).unwrap();
+		assert_eq!(index.get("Potion 5"), [(5, 0)]);
 	}
 }
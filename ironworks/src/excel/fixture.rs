@@ -0,0 +1,105 @@
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	io::Cursor,
+	sync::{Arc, RwLock},
+};
+
+use crate::{
+	FileStream, Ironworks, Resource,
+	error::{Error, ErrorValue, Result},
+	file::exh::{ColumnDefinition, ColumnKind, SheetKind},
+	sestring::SeString,
+};
+
+use super::{Excel, Field, Language, SheetWriter};
+
+/// In-memory resource, serving files by path. Clones share the same files.
+#[derive(Clone, Default)]
+pub struct Files {
+	files: Arc<RwLock<BTreeMap<String, Vec<u8>>>>,
+	sheets: Arc<RwLock<BTreeSet<String>>>,
+	version: Arc<RwLock<Option<String>>>,
+}
+
+impl Files {
+	/// Add the files built by a writer, replacing any existing sheet of the
+	/// same name. Sheets added by earlier writers are kept.
+	pub fn insert(&self, writer: &SheetWriter) {
+		let mut sheets = self.sheets.write().unwrap();
+		sheets.insert(writer.name().into());
+
+		let mut list = String::from("EXLT\r\n");
+		for sheet in sheets.iter() {
+			list.push_str(&format!("{sheet},-1\r\n"));
+		}
+
+		let mut files = self.files.write().unwrap();
+		files.extend(writer.write().unwrap());
+		files.insert("exd/root.exl".into(), list.into_bytes());
+	}
+
+	/// Set the version reported for every file.
+	pub fn set_version(&self, version: &str) {
+		*self.version.write().unwrap() = Some(version.into());
+	}
+}
+
+impl Resource for Files {
+	fn version(&self, path: &str) -> Result<String> {
+		self.version
+			.read()
+			.unwrap()
+			.clone()
+			.ok_or_else(|| Error::NotFound(ErrorValue::Path(path.into())))
+	}
+
+	fn file(&self, path: &str) -> Result<Box<dyn FileStream>> {
+		let files = self.files.read().unwrap();
+		let data = files
+			.get(path)
+			.ok_or_else(|| Error::NotFound(ErrorValue::Path(path.into())))?;
+		Ok(Box::new(Cursor::new(data.clone())))
+	}
+}
+
+/// Database containing the sheet built by a writer.
+pub fn excel(writer: &SheetWriter) -> Excel<Files> {
+	let files = Files::default();
+	files.insert(writer);
+	Excel::new(Ironworks::new().with_resource(files))
+}
+
+pub fn string(value: &str) -> Field {
+	Field::String(SeString::from(value.as_bytes().to_vec()))
+}
+
+/// Sheet of items in English and German, split over two pages.
+pub fn item_writer() -> SheetWriter {
+	let mut writer = SheetWriter::new(
+		"Item",
+		SheetKind::Default,
+		vec![
+			ColumnDefinition::new(ColumnKind::String, 0),
+			ColumnDefinition::new(ColumnKind::UInt16, 4),
+			ColumnDefinition::new(ColumnKind::PackedBool0, 6),
+			ColumnDefinition::new(ColumnKind::PackedBool1, 6),
+			ColumnDefinition::new(ColumnKind::Int32, 8),
+		],
+	)
+	.with_page_size(2);
+
+	for (language, name) in [(Language::English, "Potion"), (Language::German, "Trank")] {
+		for row_id in [1, 2, 5] {
+			let fields = vec![
+				string(&format!("{name} {row_id}")),
+				Field::U16(row_id as u16 * 10),
+				Field::Bool(row_id % 2 == 1),
+				Field::Bool(true),
+				Field::I32(-(row_id as i32)),
+			];
+			writer.set_row(language, row_id, 0, fields).unwrap();
+		}
+	}
+
+	writer
+}
@@ -0,0 +1,169 @@
+use std::{collections::BTreeMap, ops::RangeBounds};
+
+use crate::error::{Error, ErrorValue, Result};
+
+use super::{field::Field, query::QueryRow};
+
+/// Key of a value within a column index. Integer and boolean fields are keyed
+/// by their numeric value, and strings by their plain text.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum IndexKey {
+	Integer(i128),
+	String(String),
+}
+
+macro_rules! integer_key {
+	($($type:ty),+) => {
+		$(impl From<$type> for IndexKey {
+			fn from(value: $type) -> Self {
+				Self::Integer(value.into())
+			}
+		})+
+	};
+}
+
+integer_key!(bool, i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl From<&str> for IndexKey {
+	fn from(value: &str) -> Self {
+		Self::String(value.into())
+	}
+}
+
+impl From<String> for IndexKey {
+	fn from(value: String) -> Self {
+		Self::String(value)
+	}
+}
+
+impl TryFrom<&Field> for IndexKey {
+	type Error = Error;
+
+	fn try_from(field: &Field) -> Result<Self> {
+		use Field as F;
+		let key = match field {
+			F::String(string) => string.to_string().into(),
+			F::Bool(value) => (*value).into(),
+			F::I8(value) => (*value).into(),
+			F::I16(value) => (*value).into(),
+			F::I32(value) => (*value).into(),
+			F::I64(value) => (*value).into(),
+			F::U8(value) => (*value).into(),
+			F::U16(value) => (*value).into(),
+			F::U32(value) => (*value).into(),
+			F::U64(value) => (*value).into(),
+			F::F32(_) => {
+				return Err(Error::Invalid(
+					ErrorValue::Other(format!("field {field:?}")),
+					"floating point fields cannot be indexed".into(),
+				));
+			}
+		};
+		Ok(key)
+	}
+}
+
+/// Index of the (sub)rows of a sheet by the value of a column.
+#[derive(Debug)]
+pub struct ColumnIndex {
+	rows: BTreeMap<IndexKey, Vec<(u32, u16)>>,
+}
+
+impl ColumnIndex {
+	pub(super) fn build(rows: Vec<QueryRow>) -> Result<Self> {
+		let mut index = BTreeMap::<_, Vec<_>>::new();
+		for row in rows {
+			let key = IndexKey::try_from(&row.fields()[0])?;
+			index
+				.entry(key)
+				.or_default()
+				.push((row.row_id(), row.subrow_id()));
+		}
+
+		Ok(Self { rows: index })
+	}
+
+	/// Get the (row ID, subrow ID) pairs of the rows with the specified value,
+	/// in sheet order.
+	pub fn get(&self, key: impl Into<IndexKey>) -> &[(u32, u16)] {
+		self.rows.get(&key.into()).map_or(&[], Vec::as_slice)
+	}
+
+	/// Iterate over the values within a range, along with the rows containing
+	/// them, in key order.
+	pub fn range(
+		&self,
+		range: impl RangeBounds<IndexKey>,
+	) -> impl Iterator<Item = (&IndexKey, &[(u32, u16)])> {
+		self.rows
+			.range(range)
+			.map(|(key, rows)| (key, rows.as_slice()))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		Ironworks,
+		excel::{
+			Excel, Language,
+			fixture::{Files, item_writer, string},
+		},
+	};
+
+	use super::IndexKey;
+
+	#[test]
+	fn indexes_columns() {
+		let files = Files::default();
+		files.insert(&item_writer());
+		let excel = Excel::new(Ironworks::new().with_resource(files));
+		let sheet = excel.sheet("Item").unwrap();
+
+		let names = sheet.index_with_options(0, Language::German).unwrap();
+		assert_eq!(names.get("Trank 2"), [(2, 0)]);
+		assert!(names.get("Potion 2").is_empty());
+
+		let flags = sheet.index_with_options(2, Language::English).unwrap();
+		assert_eq!(flags.get(true), [(1, 0), (5, 0)]);
+
+		let levels = sheet.index_with_options(1, Language::English).unwrap();
+		let above = levels
+			.range(IndexKey::from(20u16)..)
+			.flat_map(|(_, rows)| rows)
+			.collect::<Vec<_>>();
+		assert_eq!(above, [&(2, 0), &(5, 0)]);
+	}
+
+	#[test]
+	fn refetches_on_version_change() {
+		let files = Files::default();
+		files.set_version("1");
+		let mut writer = item_writer();
+		files.insert(&writer);
+
+		let excel = Excel::new(Ironworks::new().with_resource(files.clone()));
+		let sheet = excel.sheet("Item").unwrap();
+		let index = || sheet.index_with_options(0, Language::English).unwrap();
+		assert_eq!(index().get("Potion 1"), [(1, 0)]);
+
+		writer
+			.set_field(Language::English, 1, 0, 0, string("Ether"))
+			.unwrap();
+		files.insert(&writer);
+		assert_eq!(index().get("Potion 1"), [(1, 0)]);
+
+		files.set_version("2");
+		assert_eq!(index().get("Potion 1"), [(1, 0)]);
+
+		let sheet = excel.sheet("Item").unwrap();
+		let index = sheet.index_with_options(0, Language::English).unwrap();
+		assert!(index.get("Potion 1").is_empty());
+		assert_eq!(index.get("Ether"), [(1, 0)]);
+
+		let row = sheet.row_with_options(1, Language::English).unwrap();
+		let name = row.field(0).unwrap().into_string().unwrap();
+		assert_eq!(name.as_bytes(), b"Ether");
+	}
+}
@@ -3,14 +3,15 @@ use std::sync::Arc;
 use crate::{
 	error::{Error, ErrorValue, Result},
 	file::{exd, exh},
+	ironworks::Resource,
 };
 
 use super::{metadata::SheetMetadata, sheet::Sheet};
 
 /// Iterator over the rows in a sheet.
 #[derive(Debug)]
-pub struct SheetIterator<S> {
-	sheet: Sheet<S>,
+pub struct SheetIterator<S, R = Box<dyn Resource>> {
+	sheet: Sheet<S, R>,
 
 	page_index: usize,
 	row_index: usize,
@@ -18,10 +19,12 @@ pub struct SheetIterator<S> {
 
 	page: Option<Arc<exd::ExcelData>>,
 	subrow_max: Option<u16>,
+
+	cache_pages: bool,
 }
 
-impl<S: SheetMetadata> SheetIterator<S> {
-	pub(super) fn new(sheet: Sheet<S>) -> Self {
+impl<S: SheetMetadata, R: Resource> SheetIterator<S, R> {
+	pub(super) fn new(sheet: Sheet<S, R>) -> Self {
 		Self {
 			sheet,
 
@@ -31,11 +34,28 @@ impl<S: SheetMetadata> SheetIterator<S> {
 
 			page: None,
 			subrow_max: None,
+
+			cache_pages: true,
 		}
 	}
+
+	/// Set whether pages read while iterating are retained in the sheet's cache.
+	/// Disabling this allows iterating large sheets while holding only a single
+	/// page in memory.
+	pub fn with_page_cache(mut self, cache_pages: bool) -> Self {
+		self.set_page_cache(cache_pages);
+		self
+	}
+
+	/// Set whether pages read while iterating are retained in the sheet's cache.
+	/// Disabling this allows iterating large sheets while holding only a single
+	/// page in memory.
+	pub fn set_page_cache(&mut self, cache_pages: bool) {
+		self.cache_pages = cache_pages;
+	}
 }
 
-impl<S: SheetMetadata> Iterator for SheetIterator<S> {
+impl<S: SheetMetadata, R: Resource> Iterator for SheetIterator<S, R> {
 	type Item = S::Row;
 
 	fn next(&mut self) -> Option<Self::Item> {
@@ -53,7 +73,10 @@ impl<S: SheetMetadata> Iterator for SheetIterator<S> {
 
 		while let Err(Error::NotFound(ErrorValue::Row { .. })) = row {
 			let row_id = self.row_id().ok()?;
-			row = self.sheet.subrow(row_id, self.subrow_id);
+			row = match self.cache_pages {
+				true => self.sheet.subrow(row_id, self.subrow_id),
+				false => self.page_subrow(row_id),
+			};
 			self.step().ok()?;
 		}
 
@@ -61,7 +84,7 @@ impl<S: SheetMetadata> Iterator for SheetIterator<S> {
 	}
 }
 
-impl<S: SheetMetadata> SheetIterator<S> {
+impl<S: SheetMetadata, R: Resource> SheetIterator<S, R> {
 	fn step(&mut self) -> Result<()> {
 		self.subrow_id += 1;
 
@@ -118,14 +141,30 @@ impl<S: SheetMetadata> SheetIterator<S> {
 		Ok(id)
 	}
 
+	fn page_subrow(&mut self, row_id: u32) -> Result<S::Row> {
+		let header = self.sheet.header()?;
+		if header.kind() != exh::SheetKind::Subrows && self.subrow_id > 0 {
+			return Err(Error::NotFound(ErrorValue::Row {
+				row: row_id,
+				subrow: self.subrow_id,
+				sheet: None,
+			}));
+		}
+
+		let page = self.page()?;
+		self.sheet.read_row(header, &page, row_id, self.subrow_id)
+	}
+
 	fn page(&mut self) -> Result<Arc<exd::ExcelData>> {
 		let page = match &self.page {
 			Some(value) => value,
 			None => {
-				let page = self.sheet.page(
-					self.page_definition()?.start_id(),
-					self.sheet.resolve_language(self.sheet.default_language)?,
-				)?;
+				let start_id = self.page_definition()?.start_id();
+				let language = self.sheet.resolve_language(self.sheet.default_language)?;
+				let page = match self.cache_pages {
+					true => self.sheet.page(start_id, language),
+					false => self.sheet.read_page(start_id, language),
+				}?;
 
 				self.page.insert(page)
 			}
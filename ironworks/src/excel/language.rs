@@ -12,6 +12,8 @@ use strum::{EnumIter, IntoEnumIterator};
 	Copy,
 	PartialEq,
 	Eq,
+	PartialOrd,
+	Ord,
 	Hash,
 	EnumIter,
 	IntoPrimitive,
@@ -54,3 +56,101 @@ impl Display for Language {
 		})
 	}
 }
+
+/// Policy used to select the language to read when a sheet does not support
+/// the requested language.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum LanguagePolicy {
+	/// Only read the requested language. Sheets that are not localised, and only
+	/// support `Language::None`, are still read in `Language::None`.
+	Strict,
+
+	/// Read `Language::None` if the requested language is not supported.
+	#[default]
+	FallbackToNone,
+
+	/// Try each of the listed languages in order if the requested language is not
+	/// supported, followed by `Language::None`.
+	Chain(Vec<Language>),
+}
+
+impl LanguagePolicy {
+	/// Select the first language that is accepted by `supported`, starting with
+	/// the requested language.
+	pub(super) fn resolve(
+		&self,
+		language: Language,
+		supported: impl Fn(Language) -> bool,
+	) -> Option<Language> {
+		let fallbacks = match self {
+			Self::Strict if supported(language) => return Some(language),
+			Self::Strict => {
+				let localised =
+					Language::iter().any(|other| other != Language::None && supported(other));
+				return (!localised && supported(Language::None)).then_some(Language::None);
+			}
+			Self::FallbackToNone => &[][..],
+			Self::Chain(languages) => languages.as_slice(),
+		};
+
+		[language]
+			.iter()
+			.chain(fallbacks)
+			.chain([&Language::None])
+			.copied()
+			.find(|&language| supported(language))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		error::{Error, ErrorValue},
+		excel::fixture::{excel, item_writer},
+	};
+
+	use super::{Language, LanguagePolicy};
+
+	#[test]
+	fn resolves_by_policy() {
+		use Language as L;
+		let localised = |language| matches!(language, L::English | L::ChineseSimplified);
+		let unlocalised = |language| language == L::None;
+
+		let strict = LanguagePolicy::Strict;
+		assert_eq!(strict.resolve(L::English, localised), Some(L::English));
+		assert_eq!(strict.resolve(L::German, localised), None);
+		assert_eq!(strict.resolve(L::German, unlocalised), Some(L::None));
+
+		let fallback = LanguagePolicy::FallbackToNone;
+		assert_eq!(fallback.resolve(L::German, localised), None);
+		assert_eq!(fallback.resolve(L::German, unlocalised), Some(L::None));
+
+		let chain = LanguagePolicy::Chain(vec![
+			L::ChineseTraditional,
+			L::ChineseSimplified,
+			L::English,
+		]);
+		assert_eq!(
+			chain.resolve(L::Korean, localised),
+			Some(L::ChineseSimplified)
+		);
+		assert_eq!(chain.resolve(L::English, localised), Some(L::English));
+		assert_eq!(chain.resolve(L::Korean, unlocalised), Some(L::None));
+	}
+
+	#[test]
+	fn applies_sheet_policy() {
+		let excel = excel(&item_writer()).with_language_policy(LanguagePolicy::Strict);
+		let sheet = excel.sheet("Item").unwrap();
+		assert!(matches!(
+			sheet.row_with_options(1, Language::French),
+			Err(Error::NotFound(ErrorValue::Language(Language::French)))
+		));
+
+		let sheet = sheet.with_language_policy(LanguagePolicy::Chain(vec![Language::German]));
+		let row = sheet.row_with_options(1, Language::French).unwrap();
+		let name = row.field(0).unwrap().into_string().unwrap();
+		assert_eq!(name.as_bytes(), b"Trank 1");
+	}
+}
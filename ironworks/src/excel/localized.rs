@@ -0,0 +1,182 @@
+use crate::error::Result;
+
+use super::{
+	field::Field,
+	language::Language,
+	row::{ColumnSpecifier, Row},
+};
+
+/// A (sub)row read in several languages at once.
+#[derive(Debug)]
+pub struct LocalizedRow {
+	row_id: u32,
+	subrow_id: u16,
+	rows: Vec<(Language, Language, Row)>,
+}
+
+impl LocalizedRow {
+	pub(super) fn new(row_id: u32, subrow_id: u16, rows: Vec<(Language, Language, Row)>) -> Self {
+		Self {
+			row_id,
+			subrow_id,
+			rows,
+		}
+	}
+
+	/// Row ID of this row.
+	pub fn row_id(&self) -> u32 {
+		self.row_id
+	}
+
+	/// Subrow ID of this row.
+	pub fn subrow_id(&self) -> u16 {
+		self.subrow_id
+	}
+
+	/// Languages this row was requested in, in request order.
+	pub fn languages(&self) -> impl Iterator<Item = Language> + '_ {
+		self.rows.iter().map(|(language, _, _)| *language)
+	}
+
+	/// Requested languages that are not supported by the sheet, and were read
+	/// in a fallback language instead.
+	pub fn fallbacks(&self) -> impl Iterator<Item = Language> + '_ {
+		self.rows
+			.iter()
+			.filter(|(language, resolved, _)| language != resolved)
+			.map(|(language, _, _)| *language)
+	}
+
+	/// Get the row as read for the specified language, if it was requested.
+	pub fn row(&self, language: Language) -> Option<&Row> {
+		self.rows
+			.iter()
+			.find(|(requested, _, _)| *requested == language)
+			.map(|(_, _, row)| row)
+	}
+
+	/// Read the field at the specified column in every requested language, in
+	/// request order.
+	pub fn field<'a>(
+		&self,
+		specifier: impl Into<ColumnSpecifier<'a>>,
+	) -> Result<Vec<LocalizedField>> {
+		let specifier = specifier.into();
+		self.rows
+			.iter()
+			.map(|(language, resolved, row)| {
+				Ok(LocalizedField {
+					language: *language,
+					resolved: *resolved,
+					field: row.field(specifier)?,
+				})
+			})
+			.collect()
+	}
+}
+
+/// The value of a field in a single language.
+#[derive(Debug)]
+pub struct LocalizedField {
+	language: Language,
+	resolved: Language,
+	field: Field,
+}
+
+impl LocalizedField {
+	/// Language the field was requested in.
+	pub fn language(&self) -> Language {
+		self.language
+	}
+
+	/// Language the field was read from. This will differ from the requested
+	/// language if it is not supported by the sheet.
+	pub fn resolved_language(&self) -> Language {
+		self.resolved
+	}
+
+	/// Whether the requested language fell back to another language.
+	pub fn is_fallback(&self) -> bool {
+		self.language != self.resolved
+	}
+
+	/// Value of the field.
+	pub fn field(&self) -> &Field {
+		&self.field
+	}
+
+	/// Consume this value, returning the field.
+	pub fn into_field(self) -> Field {
+		self.field
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		excel::{
+			Field, Language, SheetWriter,
+			fixture::{excel, item_writer, string},
+		},
+		file::exh::{ColumnDefinition, ColumnKind, SheetKind},
+	};
+
+	#[test]
+	fn reads_every_language() {
+		let excel = excel(&item_writer());
+		let sheet = excel.sheet("Item").unwrap();
+
+		let row = sheet.localized_row(5).unwrap();
+		assert_eq!(
+			row.languages().collect::<Vec<_>>(),
+			[Language::English, Language::German]
+		);
+		assert_eq!(row.fallbacks().count(), 0);
+
+		let names = row
+			.field(0)
+			.unwrap()
+			.into_iter()
+			.map(|name| (name.language(), name.into_field().into_string().unwrap()))
+			.map(|(language, name)| (language, name.to_string()))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			names,
+			[
+				(Language::English, "Potion 5".to_string()),
+				(Language::German, "Trank 5".to_string()),
+			]
+		);
+		assert!(matches!(
+			row.row(Language::German).unwrap().field(4),
+			Ok(Field::I32(-5))
+		));
+	}
+
+	#[test]
+	fn reports_fallbacks() {
+		let mut writer = SheetWriter::new(
+			"Addon",
+			SheetKind::Default,
+			vec![ColumnDefinition::new(ColumnKind::String, 0)],
+		);
+		writer
+			.set_row(Language::None, 1, 0, vec![string("Hello")])
+			.unwrap();
+
+		let excel = excel(&writer);
+		let sheet = excel.sheet("Addon").unwrap();
+		let row = sheet
+			.localized_subrow_with_languages(1, 0, [Language::None, Language::French])
+			.unwrap();
+		assert_eq!(row.fallbacks().collect::<Vec<_>>(), [Language::French]);
+
+		let fields = row.field(0).unwrap();
+		assert!(!fields[0].is_fallback());
+		assert!(fields[1].is_fallback());
+		assert_eq!(fields[1].resolved_language(), Language::None);
+		assert_eq!(fields[1].field().as_string().unwrap().as_bytes(), b"Hello");
+
+		assert!(sheet.localized_subrow(1, 1).is_err());
+	}
+}
@@ -2,21 +2,32 @@
 
 mod excel;
 mod field;
+mod index;
 mod iterator;
 mod language;
+mod localized;
 mod metadata;
 pub mod path;
+mod query;
 mod row;
 mod sheet;
+mod writer;
+
+#[cfg(test)]
+pub(crate) mod fixture;
 
 pub use {
 	excel::Excel,
 	field::Field,
+	index::{ColumnIndex, IndexKey},
 	iterator::SheetIterator,
-	language::Language,
+	language::{Language, LanguagePolicy},
+	localized::{LocalizedField, LocalizedRow},
 	metadata::SheetMetadata,
+	query::{Query, QueryRow},
 	row::{ColumnSpecifier, Row},
 	sheet::{RowOptions, Sheet},
+	writer::SheetWriter,
 };
 
 #[cfg(test)]
@@ -26,26 +37,48 @@ mod test {
 	#[test]
 	fn test_send() {
 		fn assert_send<T: Send>() {}
+		assert_send::<ColumnIndex>();
 		assert_send::<ColumnSpecifier>();
 		// assert_send::<Excel>();
 		assert_send::<Field>();
+		assert_send::<QueryRow>();
 		assert_send::<Language>();
+		assert_send::<LanguagePolicy>();
+		assert_send::<LocalizedField>();
+		assert_send::<LocalizedRow>();
 		assert_send::<Row>();
 		assert_send::<RowOptions>();
+		assert_send::<SheetWriter>();
 		// assert_send::<Sheet<()>>();
 		// assert_send::<SheetIterator<()>>();
+		#[cfg(feature = "async")]
+		{
+			assert_send::<Excel<Box<dyn crate::AsyncResource>>>();
+			assert_send::<Sheet<(), Box<dyn crate::AsyncResource>>>();
+		}
 	}
 
 	#[test]
 	fn test_sync() {
 		fn assert_sync<T: Sync>() {}
+		assert_sync::<ColumnIndex>();
 		assert_sync::<ColumnSpecifier>();
 		// assert_sync::<Excel>();
 		assert_sync::<Field>();
+		assert_sync::<QueryRow>();
 		assert_sync::<Language>();
+		assert_sync::<LanguagePolicy>();
+		assert_sync::<LocalizedField>();
+		assert_sync::<LocalizedRow>();
 		assert_sync::<Row>();
 		assert_sync::<RowOptions>();
+		assert_sync::<SheetWriter>();
 		// assert_sync::<Sheet<()>>();
 		// assert_sync::<SheetIterator<()>>();
+		#[cfg(feature = "async")]
+		{
+			assert_sync::<Excel<Box<dyn crate::AsyncResource>>>();
+			assert_sync::<Sheet<(), Box<dyn crate::AsyncResource>>>();
+		}
 	}
 }
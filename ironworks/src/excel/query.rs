@@ -0,0 +1,281 @@
+use std::{fmt, ops::ControlFlow};
+
+use crate::{
+	error::{Error, ErrorValue, Result},
+	file::exh,
+	ironworks::Resource,
+};
+
+use super::{
+	field::Field, language::Language, metadata::SheetMetadata, row::read_field, sheet::Sheet,
+};
+
+type Predicate<'a> = Box<dyn Fn(&Field) -> bool + 'a>;
+
+/// Query over the rows of a sheet, filtering and projecting columns.
+///
+/// Fields are read directly from the sheet's cached pages, and only the columns
+/// required by the filters are read until a row matches.
+pub struct Query<'a, S, R> {
+	sheet: &'a Sheet<S, R>,
+
+	filters: Vec<(usize, Predicate<'a>)>,
+	columns: Option<Vec<usize>>,
+	language: Option<Language>,
+}
+
+impl<'a, S: SheetMetadata, R: Resource> Query<'a, S, R> {
+	pub(super) fn new(sheet: &'a Sheet<S, R>) -> Self {
+		Self {
+			sheet,
+			filters: vec![],
+			columns: None,
+			language: None,
+		}
+	}
+
+	/// Only match rows where the field in the column at `column` satisfies the
+	/// predicate. Filters are checked in the order they are added.
+	pub fn filter(mut self, column: usize, predicate: impl Fn(&Field) -> bool + 'a) -> Self {
+		self.filters.push((column, Box::new(predicate)));
+		self
+	}
+
+	/// Set the columns to read from matching rows, by index. By default, all
+	/// columns are read.
+	pub fn with_columns(mut self, columns: impl IntoIterator<Item = usize>) -> Self {
+		self.set_columns(columns);
+		self
+	}
+
+	/// Set the columns to read from matching rows, by index. By default, all
+	/// columns are read.
+	pub fn set_columns(&mut self, columns: impl IntoIterator<Item = usize>) {
+		self.columns = Some(columns.into_iter().collect());
+	}
+
+	/// Set the language to query. By default, the sheet's default language is used.
+	pub fn with_language(mut self, language: Language) -> Self {
+		self.set_language(language);
+		self
+	}
+
+	/// Set the language to query. By default, the sheet's default language is used.
+	pub fn set_language(&mut self, language: Language) {
+		self.language = Some(language);
+	}
+
+	/// Read all matching rows, in sheet order.
+	pub fn rows(&self) -> Result<Vec<QueryRow>> {
+		let mut rows = vec![];
+		self.visit(|row| {
+			rows.push(row);
+			ControlFlow::Continue(())
+		})?;
+		Ok(rows)
+	}
+
+	/// Read the first matching row, if any.
+	pub fn first(&self) -> Result<Option<QueryRow>> {
+		let mut first = None;
+		self.visit(|row| {
+			first = Some(row);
+			ControlFlow::Break(())
+		})?;
+		Ok(first)
+	}
+
+	fn visit(&self, mut visit: impl FnMut(QueryRow) -> ControlFlow<()>) -> Result<()> {
+		let header = self.sheet.header()?;
+		let column = |index: usize| {
+			header
+				.columns()
+				.get(index)
+				.ok_or_else(|| Error::NotFound(ErrorValue::Other(format!("Column {index}"))))
+		};
+
+		let filters = self
+			.filters
+			.iter()
+			.map(|(index, predicate)| Ok((column(*index)?, predicate)))
+			.collect::<Result<Vec<_>>>()?;
+
+		let projection = match &self.columns {
+			Some(indices) => indices
+				.iter()
+				.map(|&index| column(index))
+				.collect::<Result<Vec<_>>>()?,
+			None => header.columns().iter().collect(),
+		};
+
+		let language = self.sheet.language_for_header(
+			&header,
+			self.language.unwrap_or(self.sheet.default_language),
+		)?;
+
+		let row_size = header.row_size();
+		let mut check = |row_id: u32, subrow_id: u16, data: &[u8]| -> Result<ControlFlow<()>> {
+			for (column, predicate) in &filters {
+				if !predicate(&read_field(data, row_size, column)?) {
+					return Ok(ControlFlow::Continue(()));
+				}
+			}
+
+			let fields = projection
+				.iter()
+				.map(|column| read_field(data, row_size, column))
+				.collect::<Result<_, _>>()?;
+
+			Ok(visit(QueryRow {
+				row_id,
+				subrow_id,
+				fields,
+			}))
+		};
+
+		for page_definition in header.pages() {
+			let page = self.sheet.page(page_definition.start_id(), language)?;
+			for row in &page.rows {
+				match header.kind() {
+					exh::SheetKind::Subrows => {
+						for (subrow_id, data) in page.subrows(row.id)? {
+							if check(row.id, subrow_id, data)?.is_break() {
+								return Ok(());
+							}
+						}
+					}
+
+					_ => {
+						if check(row.id, 0, page.row_data(row.id)?)?.is_break() {
+							return Ok(());
+						}
+					}
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl<S, R> fmt::Debug for Query<'_, S, R> {
+	fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		formatter
+			.debug_struct("Query")
+			.field("columns", &self.columns)
+			.field("language", &self.language)
+			.finish_non_exhaustive()
+	}
+}
+
+/// A row matched by a query.
+#[derive(Debug)]
+pub struct QueryRow {
+	row_id: u32,
+	subrow_id: u16,
+	fields: Vec<Field>,
+}
+
+impl QueryRow {
+	/// Row ID of this row.
+	pub fn row_id(&self) -> u32 {
+		self.row_id
+	}
+
+	/// Subrow ID of this row.
+	pub fn subrow_id(&self) -> u16 {
+		self.subrow_id
+	}
+
+	/// Fields read from this row, in the order of the query's columns.
+	pub fn fields(&self) -> &[Field] {
+		&self.fields
+	}
+
+	/// Consume this row, returning its fields in the order of the query's
+	/// columns.
+	pub fn into_fields(self) -> Vec<Field> {
+		self.fields
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		excel::{
+			Field, Language, SheetWriter,
+			fixture::{excel, item_writer},
+		},
+		file::exh::{ColumnDefinition, ColumnKind, SheetKind},
+	};
+
+	#[test]
+	fn filters_and_projects() {
+		let excel = excel(&item_writer());
+		let sheet = excel.sheet("Item").unwrap();
+
+		let rows = sheet
+			.query()
+			.with_language(Language::German)
+			.filter(1, |field| field.as_u16().is_some_and(|&value| value > 10))
+			.with_columns([4, 0])
+			.rows()
+			.unwrap();
+
+		let ids = rows.iter().map(|row| row.row_id()).collect::<Vec<_>>();
+		assert_eq!(ids, [2, 5]);
+		let fields = rows[1].fields();
+		assert!(matches!(fields[0], Field::I32(-5)));
+		assert_eq!(fields[1].as_string().unwrap().as_bytes(), b"Trank 5");
+	}
+
+	#[test]
+	fn finds_by_column() {
+		let excel = excel(&item_writer());
+		let sheet = excel
+			.sheet("Item")
+			.unwrap()
+			.with_default_language(Language::English);
+
+		let found = sheet
+			.query()
+			.filter(2, |field| field.as_bool() == Some(&false))
+			.first()
+			.unwrap()
+			.unwrap();
+		assert_eq!(found.row_id(), 2);
+
+		let missing = sheet.query().filter(1, |_| false).first().unwrap();
+		assert!(missing.is_none());
+		assert!(sheet.query().filter(9, |_| true).rows().is_err());
+	}
+
+	#[test]
+	fn queries_subrows() {
+		let mut writer = SheetWriter::new(
+			"Recipe",
+			SheetKind::Subrows,
+			vec![ColumnDefinition::new(ColumnKind::UInt8, 0)],
+		);
+		for (row_id, subrow_id) in [(1, 0), (1, 1), (2, 0)] {
+			let fields = vec![Field::U8(subrow_id as u8)];
+			writer
+				.set_row(Language::None, row_id, subrow_id, fields)
+				.unwrap();
+		}
+
+		let excel = excel(&writer);
+		let rows = excel
+			.sheet("Recipe")
+			.unwrap()
+			.query()
+			.filter(0, |field| field.as_u8() == Some(&0))
+			.rows()
+			.unwrap();
+		let ids = rows
+			.iter()
+			.map(|row| (row.row_id(), row.subrow_id()))
+			.collect::<Vec<_>>();
+		assert_eq!(ids, [(1, 0), (2, 0)]);
+	}
+}
@@ -11,7 +11,7 @@ use crate::{
 use super::field::Field;
 
 /// Specifier for targeting a single column within a sheet.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum ColumnSpecifier<'a> {
 	/// Specifies the column at the Nth index within the sheet's column array.
 	Index(usize),
@@ -66,6 +66,11 @@ impl Row {
 		self.subrow_id
 	}
 
+	/// Definitions of the columns within this row, in header order.
+	pub fn columns(&self) -> &[exh::ColumnDefinition] {
+		self.header.columns()
+	}
+
 	/// Read the field at the specified column from this row.
 	pub fn field<'a>(&self, specifier: impl Into<ColumnSpecifier<'a>>) -> Result<Field> {
 		let column = match specifier.into() {
@@ -78,54 +83,60 @@ impl Row {
 			}
 		};
 
-		Ok(self.read_field(column)?)
+		Ok(read_field(&self.data, self.header.row_size(), column)?)
 	}
+}
 
-	fn read_field(&self, column: &exh::ColumnDefinition) -> BinResult<Field> {
-		use Field as F;
-		use exh::ColumnKind as K;
-
-		let mut cursor = Cursor::new(&self.data);
-
-		cursor.set_position(column.offset().into());
-
-		let field = match column.kind() {
-			K::String => {
-				let string_offset = cursor.read_be::<u32>()?;
-				cursor.set_position(u64::from(string_offset) + u64::from(self.header.row_size()));
-				let wrapper = cursor.read_be::<SeStringWrapper>()?;
-				F::String(SeString::from(wrapper.0))
-			}
+/// Read a field from the data of a single (sub)row. String data is expected to
+/// follow the first `row_size` bytes of structured data.
+pub(super) fn read_field(
+	data: &[u8],
+	row_size: u16,
+	column: &exh::ColumnDefinition,
+) -> BinResult<Field> {
+	use Field as F;
+	use exh::ColumnKind as K;
+
+	let mut cursor = Cursor::new(data);
+
+	cursor.set_position(column.offset().into());
+
+	let field = match column.kind() {
+		K::String => {
+			let string_offset = cursor.read_be::<u32>()?;
+			cursor.set_position(u64::from(string_offset) + u64::from(row_size));
+			let wrapper = cursor.read_be::<SeStringWrapper>()?;
+			F::String(SeString::from(wrapper.0))
+		}
 
-			K::Bool => F::Bool(cursor.read_be::<u8>()? != 0),
-			K::PackedBool0
-			| K::PackedBool1
-			| K::PackedBool2
-			| K::PackedBool3
-			| K::PackedBool4
-			| K::PackedBool5
-			| K::PackedBool6
-			| K::PackedBool7 => {
-				let mask = 1 << (u16::from(column.kind()) - u16::from(K::PackedBool0));
-				let value = cursor.read_be::<u8>()?;
-				F::Bool((value & mask) == mask)
-			}
+		K::Bool => F::Bool(cursor.read_be::<u8>()? != 0),
+		K::PackedBool0
+		| K::PackedBool1
+		| K::PackedBool2
+		| K::PackedBool3
+		| K::PackedBool4
+		| K::PackedBool5
+		| K::PackedBool6
+		| K::PackedBool7 => {
+			let mask = 1 << (u16::from(column.kind()) - u16::from(K::PackedBool0));
+			let value = cursor.read_be::<u8>()?;
+			F::Bool((value & mask) == mask)
+		}
 
-			K::Int8 => F::I8(cursor.read_be::<i8>()?),
-			K::Int16 => F::I16(cursor.read_be::<i16>()?),
-			K::Int32 => F::I32(cursor.read_be::<i32>()?),
-			K::Int64 => F::I64(cursor.read_be::<i64>()?),
+		K::Int8 => F::I8(cursor.read_be::<i8>()?),
+		K::Int16 => F::I16(cursor.read_be::<i16>()?),
+		K::Int32 => F::I32(cursor.read_be::<i32>()?),
+		K::Int64 => F::I64(cursor.read_be::<i64>()?),
 
-			K::UInt8 => F::U8(cursor.read_be::<u8>()?),
-			K::UInt16 => F::U16(cursor.read_be::<u16>()?),
-			K::UInt32 => F::U32(cursor.read_be::<u32>()?),
-			K::UInt64 => F::U64(cursor.read_be::<u64>()?),
+		K::UInt8 => F::U8(cursor.read_be::<u8>()?),
+		K::UInt16 => F::U16(cursor.read_be::<u16>()?),
+		K::UInt32 => F::U32(cursor.read_be::<u32>()?),
+		K::UInt64 => F::U64(cursor.read_be::<u64>()?),
 
-			K::Float32 => F::F32(cursor.read_be::<f32>()?),
-		};
+		K::Float32 => F::F32(cursor.read_be::<f32>()?),
+	};
 
-		Ok(field)
-	}
+	Ok(field)
 }
 
 // In excel, SeStrings are stored with null terminators.
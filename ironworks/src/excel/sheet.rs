@@ -1,6 +1,6 @@
 use std::{
 	collections::HashMap,
-	sync::{Arc, OnceLock, RwLock},
+	sync::{Arc, Mutex, OnceLock, RwLock},
 };
 
 use derivative::Derivative;
@@ -9,37 +9,55 @@ use num_enum::TryFromPrimitive;
 use crate::{
 	error::{Error, ErrorValue, Result},
 	file::{exd, exh},
-	ironworks::Ironworks,
+	ironworks::{Ironworks, Resource},
 };
 
-use super::{iterator::SheetIterator, language::Language, metadata::SheetMetadata, path, row::Row};
+#[cfg(feature = "async")]
+use crate::async_resource::AsyncResource;
+
+use super::{
+	index::ColumnIndex,
+	iterator::SheetIterator,
+	language::{Language, LanguagePolicy},
+	localized::LocalizedRow,
+	metadata::SheetMetadata,
+	path,
+	query::Query,
+	row::Row,
+};
 
 /// A sheet within an Excel database.
 #[derive(Derivative)]
 #[derivative(Debug)]
-pub struct Sheet<S> {
+pub struct Sheet<S, R = Box<dyn Resource>> {
 	#[derivative(Debug = "ignore")]
-	ironworks: Arc<Ironworks>,
+	ironworks: Arc<Ironworks<R>>,
 
 	metadata: S,
 	pub(super) default_language: Language,
+	language_policy: LanguagePolicy,
 
 	#[derivative(Debug = "ignore")]
 	cache: Arc<SheetCache>,
+	#[derivative(Debug = "ignore")]
+	data: OnceLock<Arc<VersionCache>>,
 }
 
-impl<S: SheetMetadata> Sheet<S> {
+impl<S: SheetMetadata, R> Sheet<S, R> {
 	pub(crate) fn new(
-		ironworks: Arc<Ironworks>,
+		ironworks: Arc<Ironworks<R>>,
 		metadata: S,
 		default_language: Language,
+		language_policy: LanguagePolicy,
 		cache: Arc<SheetCache>,
 	) -> Self {
 		Self {
 			ironworks,
 			metadata,
 			default_language,
+			language_policy,
 			cache,
+			data: OnceLock::new(),
 		}
 	}
 
@@ -54,11 +72,99 @@ impl<S: SheetMetadata> Sheet<S> {
 		self.default_language = default_language;
 	}
 
+	/// Set the policy used to select a language when this sheet does not support
+	/// the requested language.
+	pub fn with_language_policy(mut self, language_policy: LanguagePolicy) -> Self {
+		self.set_language_policy(language_policy);
+		self
+	}
+
+	/// Set the policy used to select a language when this sheet does not support
+	/// the requested language.
+	pub fn set_language_policy(&mut self, language_policy: LanguagePolicy) {
+		self.language_policy = language_policy;
+	}
+
 	/// Name of the sheet as specified by the provided metadata.
 	pub fn name(&self) -> String {
 		self.metadata.name()
 	}
 
+	fn row_error_value(&self, row_id: u32, subrow_id: u16) -> ErrorValue {
+		ErrorValue::Row {
+			row: row_id,
+			subrow: subrow_id,
+			sheet: self.name().into(),
+		}
+	}
+
+	/// Find the page start ID and language that the requested (sub)row is stored in.
+	fn locate_row(
+		&self,
+		header: &exh::ExcelHeader,
+		row_id: u32,
+		subrow_id: u16,
+		options: &RowOptions,
+	) -> Result<(u32, Language)> {
+		// Fail out early if a subrow >0 was requested on a non-subrow sheet.
+		if header.kind() != exh::SheetKind::Subrows && subrow_id > 0 {
+			return Err(Error::NotFound(self.row_error_value(row_id, subrow_id)));
+		}
+
+		let start_id = start_id_for_row(header, row_id)
+			.ok_or_else(|| Error::NotFound(self.row_error_value(row_id, subrow_id)))?;
+		let language =
+			self.language_for_header(header, options.language.unwrap_or(self.default_language))?;
+
+		Ok((start_id, language))
+	}
+
+	pub(super) fn read_row(
+		&self,
+		header: Arc<exh::ExcelHeader>,
+		page: &exd::ExcelData,
+		row_id: u32,
+		subrow_id: u16,
+	) -> Result<S::Row> {
+		let row = self.raw_row(header, page, row_id, subrow_id)?;
+
+		self.metadata.populate_row(row).map_err(|error| {
+			Error::Invalid(self.row_error_value(row_id, subrow_id), error.to_string())
+		})
+	}
+
+	fn raw_row(
+		&self,
+		header: Arc<exh::ExcelHeader>,
+		page: &exd::ExcelData,
+		row_id: u32,
+		subrow_id: u16,
+	) -> Result<Row> {
+		let data = match header.kind() {
+			exh::SheetKind::Subrows => page.subrow_data(row_id, subrow_id),
+			_ => page.row_data(row_id),
+		}?;
+
+		// TODO: This means I'm cloning the entire row byte array each time, even if someone's asking for 2 fields. Perhaps consider using a "row reader" that operates on a temporary lifetime with the byte slice, and only to_vec the data in a concrete Row for raw reading?
+		Ok(Row::new(row_id, subrow_id, header, data.to_vec()))
+	}
+
+	/// Select the language to read for a request, as per this sheet's language
+	/// policy.
+	pub(super) fn language_for_header(
+		&self,
+		header: &exh::ExcelHeader,
+		language: Language,
+	) -> Result<Language> {
+		self.language_policy
+			.resolve(language, |language| {
+				header.languages().contains(&language.into())
+			})
+			.ok_or_else(|| Error::NotFound(ErrorValue::Language(language)))
+	}
+}
+
+impl<S: SheetMetadata, R: Resource> Sheet<S, R> {
 	/// Get the kind of this sheet.
 	pub fn kind(&self) -> Result<exh::SheetKind> {
 		Ok(self.header()?.kind())
@@ -86,6 +192,55 @@ impl<S: SheetMetadata> Sheet<S> {
 		Ok(columns)
 	}
 
+	/// Fetch the definitions of the pages in this sheet, each covering a range of
+	/// row IDs.
+	pub fn pages(&self) -> Result<Vec<exh::PageDefinition>> {
+		let pages = self.header()?.pages().clone();
+		Ok(pages)
+	}
+
+	/// Build a query over the rows of this sheet.
+	pub fn query(&self) -> Query<'_, S, R> {
+		Query::new(self)
+	}
+
+	/// Get an index of the rows in this sheet by the value of the column at
+	/// `column`, building it if it has not been already. Indexes are cached
+	/// alongside the sheet's pages, for the version of the sheet first read
+	/// through this handle.
+	pub fn index(&self, column: usize) -> Result<Arc<ColumnIndex>> {
+		self.index_with_options(column, RowOptions::new())
+	}
+
+	/// Get an index of the rows in this sheet by the value of the column at
+	/// `column`, along with any additional options for reading the rows.
+	pub fn index_with_options(
+		&self,
+		column: usize,
+		options: impl Into<RowOptions>,
+	) -> Result<Arc<ColumnIndex>> {
+		let options: RowOptions = options.into();
+		let language = self.resolve_language(options.language.unwrap_or(self.default_language))?;
+
+		let key = (column, language);
+		let data = self.data();
+		if let Some(index) = data.indexes.read().expect("poisoned").get(&key) {
+			return Ok(index.clone());
+		}
+
+		let rows = self
+			.query()
+			.with_language(language)
+			.with_columns([column])
+			.rows()?;
+		let index = Arc::new(ColumnIndex::build(rows)?);
+
+		let mut indexes = data.indexes.write().expect("poisoned");
+		indexes.insert(key, index.clone());
+
+		Ok(index)
+	}
+
 	/// Fetch a row from this sheet by ID. In the case of a sheet with subrows,
 	/// this will return subrow 0.
 	pub fn row(&self, row_id: u32) -> Result<S::Row> {
@@ -113,64 +268,80 @@ impl<S: SheetMetadata> Sheet<S> {
 		let options: RowOptions = options.into();
 		let header = self.header()?;
 
-		let row_error_value = || ErrorValue::Row {
-			row: row_id,
-			subrow: subrow_id,
-			sheet: self.name().into(),
-		};
-
-		// Fail out early if a subrow >0 was requested on a non-subrow sheet.
-		if header.kind() != exh::SheetKind::Subrows && subrow_id > 0 {
-			return Err(Error::NotFound(row_error_value()));
-		}
-
 		// Try to read in the page for the requested (sub)row.
-		let start_id = self
-			.start_id_for_row(row_id)
-			.ok_or_else(|| Error::NotFound(row_error_value()))?;
-		let language = self.resolve_language(options.language.unwrap_or(self.default_language))?;
+		let (start_id, language) = self.locate_row(&header, row_id, subrow_id, &options)?;
 		let page = self.page(start_id, language)?;
 
-		let data = match header.kind() {
-			exh::SheetKind::Subrows => page.subrow_data(row_id, subrow_id),
-			_ => page.row_data(row_id),
-		}?;
+		self.read_row(header, &page, row_id, subrow_id)
+	}
 
-		// TODO: This means I'm cloning the entire row byte array each time, even if someone's asking for 2 fields. Perhaps consider using a "row reader" that operates on a temporary lifetime with the byte slice, and only to_vec the data in a concrete Row for raw reading?
-		let row = Row::new(row_id, subrow_id, header, data.to_vec());
+	/// Fetch a row from this sheet by ID in every language supported by the
+	/// sheet, in language ID order. In the case of a sheet with subrows, this will
+	/// return subrow 0.
+	pub fn localized_row(&self, row_id: u32) -> Result<LocalizedRow> {
+		self.localized_subrow(row_id, 0)
+	}
 
-		self.metadata
-			.populate_row(row)
-			.map_err(|error| Error::Invalid(row_error_value(), error.to_string()))
+	/// Fetch a row from this sheet by its ID and subrow ID in every language
+	/// supported by the sheet, in language ID order.
+	pub fn localized_subrow(&self, row_id: u32, subrow_id: u16) -> Result<LocalizedRow> {
+		let mut languages = self.languages()?;
+		languages.sort_unstable();
+		self.localized_subrow_with_languages(row_id, subrow_id, languages)
+	}
+
+	/// Fetch a row from this sheet by its ID and subrow ID in each of the
+	/// specified languages. Languages not supported by the sheet fall back as per
+	/// the sheet's language policy, and are reported by the returned row.
+	pub fn localized_subrow_with_languages(
+		&self,
+		row_id: u32,
+		subrow_id: u16,
+		languages: impl IntoIterator<Item = Language>,
+	) -> Result<LocalizedRow> {
+		let header = self.header()?;
+
+		let rows = languages
+			.into_iter()
+			.map(|language| {
+				let (start_id, resolved) =
+					self.locate_row(&header, row_id, subrow_id, &language.into())?;
+				let page = self.page(start_id, resolved)?;
+				let row = self.raw_row(header.clone(), &page, row_id, subrow_id)?;
+				Ok((language, resolved, row))
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		Ok(LocalizedRow::new(row_id, subrow_id, rows))
+	}
+
+	/// Cached data for the version of the sheet first read through this handle.
+	fn data(&self) -> &VersionCache {
+		self.data.get_or_init(|| {
+			let version = self.ironworks.version(&path::exh(&self.name()));
+			self.cache.for_version(version.ok())
+		})
 	}
 
 	pub(super) fn header(&self) -> Result<Arc<exh::ExcelHeader>> {
-		// TODO: get_or_try_init once (if?) that gets stabilised.
-		if let Some(header) = self.cache.header.get() {
+		let data = self.data();
+		if let Some(header) = &*data.header.read().expect("poisoned") {
 			return Ok(header.clone());
 		}
 
 		let path = path::exh(&self.name());
 		let header = self.ironworks.file(&path)?;
 
-		Ok(self.cache.header.get_or_init(|| Arc::new(header)).clone())
-	}
-
-	fn start_id_for_row(&self, row_id: u32) -> Option<u32> {
-		let header = self.header().ok()?;
-
-		header
-			.pages()
-			.iter()
-			.find(|page| page.start_id() <= row_id && page.start_id() + page.row_count() > row_id)
-			.map(|page| page.start_id())
+		let mut cached = data.header.write().expect("poisoned");
+		Ok(cached.get_or_insert_with(|| Arc::new(header)).clone())
 	}
 
 	pub(super) fn page(&self, start_id: u32, language: Language) -> Result<Arc<exd::ExcelData>> {
 		let key = (start_id, language);
 
 		// Try to fetch from the hot path.
-		let pages = self.cache.pages.read().expect("poisoned");
+		let data = self.data();
+		let pages = data.pages.read().expect("poisoned");
 		if let Some(page) = pages.get(&key) {
 			return Ok(page.clone());
 		}
@@ -178,44 +349,161 @@ impl<S: SheetMetadata> Sheet<S> {
 		// No page already present, take ownership over reading + caching it.
 		// This is likely slightly susceptible to a race, but that's a lot cheaper than a mutex.
 		drop(pages);
-		let mut pages_mut = self.cache.pages.write().expect("poisoned");
+		let mut pages_mut = data.pages.write().expect("poisoned");
 
-		let path = path::exd(&self.name(), start_id, language);
-		let data = Arc::new(self.ironworks.file::<exd::ExcelData>(&path)?);
+		let page = self.read_page(start_id, language)?;
+
+		pages_mut.insert(key, page.clone());
 
-		pages_mut.insert(key, data.clone());
+		Ok(page)
+	}
 
-		Ok(data)
+	/// Read a page without consulting or populating the page cache.
+	pub(super) fn read_page(
+		&self,
+		start_id: u32,
+		language: Language,
+	) -> Result<Arc<exd::ExcelData>> {
+		let path = path::exd(&self.name(), start_id, language);
+		Ok(Arc::new(self.ironworks.file::<exd::ExcelData>(&path)?))
 	}
 
 	pub(super) fn resolve_language(&self, language: Language) -> Result<Language> {
-		let header = self.header()?;
+		self.language_for_header(&*self.header()?, language)
+	}
+}
 
-		// Get the language to load, or NONE if the language is not supported by this sheet.
-		// TODO: Should an explicit language request fail hard on miss?
-		[language, Language::None]
-			.into_iter()
-			.find(|&language| header.languages().contains(&language.into()))
-			// TODO: Should this be Invalid or NotFound?
-			// TODO: Should we have an explicit ErrorValue for language?
-			.ok_or_else(|| Error::NotFound(ErrorValue::Other(format!("language {language:?}"))))
+#[cfg(feature = "async")]
+impl<S: SheetMetadata, R: AsyncResource> Sheet<S, R> {
+	/// Fetch a row from this sheet by ID. In the case of a sheet with subrows,
+	/// this will return subrow 0.
+	pub async fn row_async(&self, row_id: u32) -> Result<S::Row> {
+		self.subrow_with_options_async(row_id, 0, RowOptions::new())
+			.await
+	}
+
+	/// Fetch a row from this sheet by ID, along with any additional options for
+	/// reading the row. In the case of a sheet with subrows, this will return subrow 0.
+	pub async fn row_with_options_async(
+		&self,
+		row_id: u32,
+		options: impl Into<RowOptions>,
+	) -> Result<S::Row> {
+		self.subrow_with_options_async(row_id, 0, options).await
+	}
+
+	/// Fetch a row from this sheet by its ID and subrow ID.
+	pub async fn subrow_async(&self, row_id: u32, subrow_id: u16) -> Result<S::Row> {
+		self.subrow_with_options_async(row_id, subrow_id, RowOptions::new())
+			.await
 	}
+
+	/// Fetch a row from this sheet by its ID and subrow ID, along with any additional options for reading the row.
+	pub async fn subrow_with_options_async(
+		&self,
+		row_id: u32,
+		subrow_id: u16,
+		options: impl Into<RowOptions>,
+	) -> Result<S::Row> {
+		let options: RowOptions = options.into();
+		let header = self.header_async().await?;
+
+		let (start_id, language) = self.locate_row(&header, row_id, subrow_id, &options)?;
+		let page = self.page_async(start_id, language).await?;
+
+		self.read_row(header, &page, row_id, subrow_id)
+	}
+
+	async fn data_async(&self) -> &VersionCache {
+		if let Some(data) = self.data.get() {
+			return data;
+		}
+
+		let version = self.ironworks.version_async(&path::exh(&self.name())).await;
+		self.data
+			.get_or_init(|| self.cache.for_version(version.ok()))
+	}
+
+	async fn header_async(&self) -> Result<Arc<exh::ExcelHeader>> {
+		let data = self.data_async().await;
+		if let Some(header) = &*data.header.read().expect("poisoned") {
+			return Ok(header.clone());
+		}
+
+		let path = path::exh(&self.name());
+		let header = self.ironworks.file_async(&path).await?;
+
+		let mut cached = data.header.write().expect("poisoned");
+		Ok(cached.get_or_insert_with(|| Arc::new(header)).clone())
+	}
+
+	async fn page_async(&self, start_id: u32, language: Language) -> Result<Arc<exd::ExcelData>> {
+		let key = (start_id, language);
+
+		let data = self.data_async().await;
+		if let Some(page) = data.pages.read().expect("poisoned").get(&key) {
+			return Ok(page.clone());
+		}
+
+		// The cache lock can't be held over the read, so concurrent misses may
+		// read the same page - the first to finish is kept.
+		let path = path::exd(&self.name(), start_id, language);
+		let page = Arc::new(self.ironworks.file_async::<exd::ExcelData>(&path).await?);
+
+		let mut pages = data.pages.write().expect("poisoned");
+		Ok(pages.entry(key).or_insert(page).clone())
+	}
+}
+
+fn start_id_for_row(header: &exh::ExcelHeader, row_id: u32) -> Option<u32> {
+	header
+		.pages()
+		.iter()
+		.find(|page| page.start_id() <= row_id && page.start_id() + page.row_count() > row_id)
+		.map(|page| page.start_id())
 }
 
-impl<S: SheetMetadata> IntoIterator for Sheet<S> {
+impl<S: SheetMetadata, R: Resource> IntoIterator for Sheet<S, R> {
 	type Item = S::Row;
-	type IntoIter = SheetIterator<S>;
+	type IntoIter = SheetIterator<S, R>;
 
 	fn into_iter(self) -> Self::IntoIter {
 		SheetIterator::new(self)
 	}
 }
 
-/// Data cache for raw values, decoupled from mapping/metadata concerns.
+/// Data cache for raw values, decoupled from mapping/metadata concerns. Data
+/// is kept for the most recently read version of the sheet.
 #[derive(Default)]
 pub struct SheetCache {
-	header: OnceLock<Arc<exh::ExcelHeader>>,
+	current: Mutex<Option<Arc<VersionCache>>>,
+}
+
+impl SheetCache {
+	/// Get the cached data for `version`, replacing any data cached for another
+	/// version. Sheets without a readable version share a single unversioned
+	/// cache.
+	fn for_version(&self, version: Option<String>) -> Arc<VersionCache> {
+		let mut current = self.current.lock().expect("poisoned");
+		match &*current {
+			Some(data) if data.version == version => data.clone(),
+			_ => current
+				.insert(Arc::new(VersionCache {
+					version,
+					header: Default::default(),
+					pages: Default::default(),
+					indexes: Default::default(),
+				}))
+				.clone(),
+		}
+	}
+}
+
+struct VersionCache {
+	version: Option<String>,
+	header: RwLock<Option<Arc<exh::ExcelHeader>>>,
 	pages: RwLock<HashMap<(u32, Language), Arc<exd::ExcelData>>>,
+	indexes: RwLock<HashMap<(usize, Language), Arc<ColumnIndex>>>,
 }
 
 /// Options used when reading a row from a sheet.
@@ -224,6 +512,12 @@ pub struct RowOptions {
 	language: Option<Language>,
 }
 
+impl Default for RowOptions {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
 impl RowOptions {
 	/// Build the default options for reading a row.
 	pub fn new() -> Self {
@@ -0,0 +1,479 @@
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	io::Cursor,
+};
+
+use crate::{
+	error::{Error, ErrorValue, Result},
+	file::{exd, exh},
+	ironworks::Resource,
+};
+
+use super::{excel::Excel, field::Field, language::Language, path};
+
+const DEFAULT_PAGE_SIZE: u32 = 500;
+
+type Rows = BTreeMap<(u32, u16), Vec<Field>>;
+
+/// Writer building the .exh and .exd files for an Excel sheet from rows of
+/// fields.
+#[derive(Debug)]
+pub struct SheetWriter {
+	name: String,
+	kind: exh::SheetKind,
+	row_size: u16,
+	columns: Vec<exh::ColumnDefinition>,
+	page_size: u32,
+	rows: BTreeMap<Language, Rows>,
+}
+
+impl SheetWriter {
+	/// Create a writer for an empty sheet with the provided columns. The row
+	/// size is derived from the column layout.
+	pub fn new(
+		name: impl Into<String>,
+		kind: exh::SheetKind,
+		columns: Vec<exh::ColumnDefinition>,
+	) -> Self {
+		let row_size = columns
+			.iter()
+			.map(|column| column.offset() + column.size())
+			.max()
+			.unwrap_or(0)
+			.next_multiple_of(4);
+
+		Self {
+			name: name.into(),
+			kind,
+			row_size,
+			columns,
+			page_size: DEFAULT_PAGE_SIZE,
+			rows: Default::default(),
+		}
+	}
+
+	/// Create a writer containing the current layout and rows of a sheet in the
+	/// database, in every language it supports.
+	pub fn from_excel<R: Resource>(excel: &Excel<R>, sheet: &str) -> Result<Self> {
+		let source = excel.sheet(sheet)?;
+		let header = source.header()?;
+
+		let mut writer = Self {
+			name: sheet.to_string(),
+			kind: header.kind(),
+			row_size: header.row_size(),
+			columns: header.columns().clone(),
+			page_size: DEFAULT_PAGE_SIZE,
+			rows: Default::default(),
+		};
+
+		for language in source.languages()? {
+			let rows = writer.rows.entry(language).or_default();
+			for row in excel.sheet(sheet)?.with_default_language(language) {
+				let fields = (0..header.columns().len())
+					.map(|index| row.field(index))
+					.collect::<Result<Vec<_>>>()?;
+				rows.insert((row.row_id(), row.subrow_id()), fields);
+			}
+		}
+
+		Ok(writer)
+	}
+
+	/// Name of the sheet being written.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Set the maximum number of rows written to each page.
+	pub fn with_page_size(mut self, page_size: u32) -> Self {
+		self.set_page_size(page_size);
+		self
+	}
+
+	/// Set the maximum number of rows written to each page.
+	pub fn set_page_size(&mut self, page_size: u32) {
+		self.page_size = page_size.max(1);
+	}
+
+	/// Get the fields of a (sub)row in the specified language.
+	pub fn row(&self, language: Language, row_id: u32, subrow_id: u16) -> Option<&[Field]> {
+		self.rows
+			.get(&language)
+			.and_then(|rows| rows.get(&(row_id, subrow_id)))
+			.map(Vec::as_slice)
+	}
+
+	/// Insert or replace a (sub)row in the specified language. Fields must be
+	/// provided for every column, in header order.
+	pub fn set_row(
+		&mut self,
+		language: Language,
+		row_id: u32,
+		subrow_id: u16,
+		fields: Vec<Field>,
+	) -> Result<()> {
+		if self.kind != exh::SheetKind::Subrows && subrow_id > 0 {
+			return Err(Error::Invalid(
+				self.row_error_value(row_id, subrow_id),
+				"only subrow sheets may contain subrows".into(),
+			));
+		}
+
+		if fields.len() != self.columns.len() {
+			return Err(Error::Invalid(
+				self.row_error_value(row_id, subrow_id),
+				format!(
+					"expected {} fields, got {}",
+					self.columns.len(),
+					fields.len()
+				),
+			));
+		}
+
+		for (column, field) in self.columns.iter().zip(&fields) {
+			self.check_field(row_id, subrow_id, column, field)?;
+		}
+
+		self.rows
+			.entry(language)
+			.or_default()
+			.insert((row_id, subrow_id), fields);
+
+		Ok(())
+	}
+
+	/// Replace the field at the specified column of an existing (sub)row.
+	pub fn set_field(
+		&mut self,
+		language: Language,
+		row_id: u32,
+		subrow_id: u16,
+		column: usize,
+		field: Field,
+	) -> Result<()> {
+		let definition = self
+			.columns
+			.get(column)
+			.ok_or_else(|| Error::NotFound(ErrorValue::Other(format!("Column {column}"))))?;
+		self.check_field(row_id, subrow_id, definition, &field)?;
+
+		let error_value = self.row_error_value(row_id, subrow_id);
+		let fields = self
+			.rows
+			.get_mut(&language)
+			.and_then(|rows| rows.get_mut(&(row_id, subrow_id)))
+			.ok_or(Error::NotFound(error_value))?;
+		fields[column] = field;
+
+		Ok(())
+	}
+
+	/// Remove a (sub)row in the specified language, returning its fields if it
+	/// was present.
+	pub fn remove_row(
+		&mut self,
+		language: Language,
+		row_id: u32,
+		subrow_id: u16,
+	) -> Option<Vec<Field>> {
+		self.rows
+			.get_mut(&language)
+			.and_then(|rows| rows.remove(&(row_id, subrow_id)))
+	}
+
+	/// Build the header and data page files for the sheet, keyed by their paths
+	/// within the game data.
+	pub fn write(&self) -> Result<BTreeMap<String, Vec<u8>>> {
+		// Every language shares the header's page layout, so pages are split over
+		// the IDs present in any language.
+		let row_ids = self
+			.rows
+			.values()
+			.flat_map(|rows| rows.keys().map(|&(row_id, _)| row_id))
+			.collect::<BTreeSet<_>>()
+			.into_iter()
+			.collect::<Vec<_>>();
+
+		let pages = row_ids
+			.chunks(usize::try_from(self.page_size).unwrap())
+			.map(|ids| {
+				let (first, last) = (ids[0], ids[ids.len() - 1]);
+				exh::PageDefinition::new(first, last - first + 1)
+			})
+			.collect::<Vec<_>>();
+
+		let languages = match self.rows.is_empty() {
+			true => [Language::None.into()].into(),
+			false => self.rows.keys().map(|&language| language.into()).collect(),
+		};
+
+		let mut files = BTreeMap::new();
+
+		let header = exh::ExcelHeader::new(
+			self.row_size,
+			self.kind,
+			self.columns.clone(),
+			pages.clone(),
+			languages,
+		);
+		let mut buffer = Cursor::new(Vec::new());
+		header.write(&mut buffer)?;
+		files.insert(path::exh(&self.name), buffer.into_inner());
+
+		for (&language, rows) in &self.rows {
+			for page in &pages {
+				let last = page.start_id() + (page.row_count() - 1);
+				let page_rows = rows.range((page.start_id(), 0)..=(last, u16::MAX));
+
+				// Group subrows under their parent row.
+				let mut grouped = BTreeMap::<u32, Vec<(u16, &[Field])>>::new();
+				for (&(row_id, subrow_id), fields) in page_rows {
+					grouped.entry(row_id).or_default().push((subrow_id, fields));
+				}
+
+				let data = exd::ExcelData::new(
+					grouped
+						.into_iter()
+						.map(|(row_id, subrows)| self.row_data(row_id, &subrows)),
+				);
+				let mut buffer = Cursor::new(Vec::new());
+				data.write(&mut buffer)?;
+				files.insert(
+					path::exd(&self.name, page.start_id(), language),
+					buffer.into_inner(),
+				);
+			}
+		}
+
+		Ok(files)
+	}
+
+	fn row_data(&self, row_id: u32, subrows: &[(u16, &[Field])]) -> (u32, u16, Vec<u8>) {
+		let mut data = Vec::new();
+		match self.kind {
+			// Subrows are stored back to back, each prefixed with its ID.
+			exh::SheetKind::Subrows => {
+				for &(subrow_id, fields) in subrows {
+					let (fixed, _) = self.encode(fields);
+					data.extend_from_slice(&subrow_id.to_be_bytes());
+					data.extend_from_slice(&fixed);
+				}
+			}
+
+			// Strings are stored immediately after the row's structured data.
+			_ => {
+				let (fixed, strings) = self.encode(subrows[0].1);
+				data.extend_from_slice(&fixed);
+				data.extend_from_slice(&strings);
+				data.resize(data.len().next_multiple_of(4), 0);
+			}
+		}
+
+		(row_id, u16::try_from(subrows.len()).unwrap(), data)
+	}
+
+	fn encode(&self, fields: &[Field]) -> (Vec<u8>, Vec<u8>) {
+		use Field as F;
+
+		let mut fixed = vec![0u8; usize::from(self.row_size)];
+		let mut strings = Vec::new();
+
+		for (column, field) in self.columns.iter().zip(fields) {
+			let offset = usize::from(column.offset());
+			let mut put = |bytes: &[u8]| fixed[offset..offset + bytes.len()].copy_from_slice(bytes);
+
+			match field {
+				F::String(string) => {
+					let string_offset = u32::try_from(strings.len()).unwrap();
+					strings.extend_from_slice(string.as_bytes());
+					strings.push(0);
+					put(&string_offset.to_be_bytes());
+				}
+
+				F::Bool(value) => match packed_bool_mask(column.kind()) {
+					None => put(&[u8::from(*value)]),
+					Some(mask) if *value => fixed[offset] |= mask,
+					Some(mask) => fixed[offset] &= !mask,
+				},
+
+				F::I8(value) => put(&value.to_be_bytes()),
+				F::I16(value) => put(&value.to_be_bytes()),
+				F::I32(value) => put(&value.to_be_bytes()),
+				F::I64(value) => put(&value.to_be_bytes()),
+
+				F::U8(value) => put(&value.to_be_bytes()),
+				F::U16(value) => put(&value.to_be_bytes()),
+				F::U32(value) => put(&value.to_be_bytes()),
+				F::U64(value) => put(&value.to_be_bytes()),
+
+				F::F32(value) => put(&value.to_be_bytes()),
+			}
+		}
+
+		(fixed, strings)
+	}
+
+	fn check_field(
+		&self,
+		row_id: u32,
+		subrow_id: u16,
+		column: &exh::ColumnDefinition,
+		field: &Field,
+	) -> Result<()> {
+		use Field as F;
+		use exh::ColumnKind as K;
+
+		let matches = match column.kind() {
+			K::String => matches!(field, F::String(_)),
+			K::Bool => matches!(field, F::Bool(_)),
+			kind if packed_bool_mask(kind).is_some() => matches!(field, F::Bool(_)),
+			K::Int8 => matches!(field, F::I8(_)),
+			K::Int16 => matches!(field, F::I16(_)),
+			K::Int32 => matches!(field, F::I32(_)),
+			K::Int64 => matches!(field, F::I64(_)),
+			K::UInt8 => matches!(field, F::U8(_)),
+			K::UInt16 => matches!(field, F::U16(_)),
+			K::UInt32 => matches!(field, F::U32(_)),
+			K::UInt64 => matches!(field, F::U64(_)),
+			K::Float32 => matches!(field, F::F32(_)),
+			_ => false,
+		};
+
+		if !matches {
+			return Err(Error::Invalid(
+				self.row_error_value(row_id, subrow_id),
+				format!(
+					"field {field:?} does not match column kind {:?}",
+					column.kind()
+				),
+			));
+		}
+
+		// Subrow data has no space for trailing string payloads.
+		if self.kind == exh::SheetKind::Subrows && matches!(field, F::String(_)) {
+			return Err(Error::Invalid(
+				self.row_error_value(row_id, subrow_id),
+				"subrow sheets do not support strings".into(),
+			));
+		}
+
+		Ok(())
+	}
+
+	fn row_error_value(&self, row_id: u32, subrow_id: u16) -> ErrorValue {
+		ErrorValue::Row {
+			row: row_id,
+			subrow: subrow_id,
+			sheet: Some(self.name.clone()),
+		}
+	}
+}
+
+fn packed_bool_mask(kind: exh::ColumnKind) -> Option<u8> {
+	let index = u16::from(kind).checked_sub(exh::ColumnKind::PackedBool0.into())?;
+	(index < 8).then(|| 1 << index)
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		excel::{
+			Field, Language,
+			fixture::{excel, item_writer, string},
+		},
+		file::exh::{ColumnDefinition, ColumnKind, SheetKind},
+	};
+
+	use super::SheetWriter;
+
+	#[test]
+	fn round_trips_rows() {
+		let excel = excel(&item_writer());
+		let sheet = excel.sheet("Item").unwrap();
+		assert_eq!(sheet.languages().unwrap().len(), 2);
+
+		let row = sheet.row_with_options(5, Language::German).unwrap();
+		let name = row.field(0).unwrap().into_string().unwrap();
+		assert_eq!(name.as_bytes(), b"Trank 5");
+		assert_eq!(row.field(1).unwrap().into_u16().unwrap(), 50);
+		assert!(row.field(2).unwrap().into_bool().unwrap());
+		assert!(row.field(3).unwrap().into_bool().unwrap());
+		assert_eq!(row.field(4).unwrap().into_i32().unwrap(), -5);
+
+		let row = sheet.row_with_options(2, Language::English).unwrap();
+		assert!(!row.field(2).unwrap().into_bool().unwrap());
+		assert!(row.field(3).unwrap().into_bool().unwrap());
+
+		let ids = sheet
+			.with_default_language(Language::English)
+			.into_iter()
+			.map(|row| row.row_id())
+			.collect::<Vec<_>>();
+		assert_eq!(ids, [1, 2, 5]);
+	}
+
+	#[test]
+	fn patches_existing_sheet() {
+		let excel = excel(&item_writer());
+		let mut writer = SheetWriter::from_excel(&excel, "Item").unwrap();
+		writer
+			.set_field(Language::English, 2, 0, 0, string("Hi-Potion"))
+			.unwrap();
+		assert!(
+			writer
+				.set_field(Language::English, 2, 0, 1, Field::U8(1))
+				.is_err()
+		);
+		assert!(
+			writer
+				.set_field(Language::English, 3, 0, 1, Field::U16(1))
+				.is_err()
+		);
+
+		let excel = self::excel(&writer);
+		let sheet = excel.sheet("Item").unwrap();
+		let name = |language, row_id| {
+			let row = sheet.row_with_options(row_id, language).unwrap();
+			row.field(0)
+				.unwrap()
+				.into_string()
+				.unwrap()
+				.as_bytes()
+				.to_vec()
+		};
+		assert_eq!(name(Language::English, 2), b"Hi-Potion");
+		assert_eq!(name(Language::English, 1), b"Potion 1");
+		assert_eq!(name(Language::German, 2), b"Trank 2");
+	}
+
+	#[test]
+	fn round_trips_subrows() {
+		let mut writer = SheetWriter::new(
+			"Recipe",
+			SheetKind::Subrows,
+			vec![
+				ColumnDefinition::new(ColumnKind::UInt8, 0),
+				ColumnDefinition::new(ColumnKind::Float32, 4),
+			],
+		);
+		for (row_id, subrow_id) in [(3, 0), (3, 1), (3, 2), (4, 0)] {
+			let fields = vec![Field::U8(subrow_id as u8), Field::F32(row_id as f32)];
+			writer
+				.set_row(Language::None, row_id, subrow_id, fields)
+				.unwrap();
+		}
+
+		let excel = excel(&writer);
+		let sheet = excel.sheet("Recipe").unwrap();
+		let row = sheet.subrow(3, 2).unwrap();
+		assert_eq!(row.field(0).unwrap().into_u8().unwrap(), 2);
+		assert_eq!(row.field(1).unwrap().into_f32().unwrap(), 3.0);
+
+		let ids = sheet
+			.into_iter()
+			.map(|row| (row.row_id(), row.subrow_id()))
+			.collect::<Vec<_>>();
+		assert_eq!(ids, [(3, 0), (3, 1), (3, 2), (4, 0)]);
+	}
+}
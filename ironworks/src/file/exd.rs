@@ -1,8 +1,8 @@
 //! Structs and utilities for parsing .exd files.
 
-use std::io::{Cursor, Read, Seek};
+use std::io::{Cursor, Read, Seek, Write};
 
-use binrw::{BinRead, BinResult, Endian, binread};
+use binrw::{BinRead, BinResult, BinWriterExt, Endian, binread};
 
 use crate::{
 	FileStream,
@@ -37,7 +37,58 @@ pub struct ExcelData {
 	pub data: Vec<u8>,
 }
 
+const VERSION: u16 = 2;
+const HEADER_SIZE: u32 = 32;
+
 impl ExcelData {
+	/// Build a data page from rows, each with its ID, the number of subrows it
+	/// contains, and its data. Subrow data is expected to include each subrow's
+	/// ID header.
+	pub fn new(rows: impl IntoIterator<Item = (u32, u16, Vec<u8>)>) -> Self {
+		let rows = rows.into_iter().collect::<Vec<_>>();
+		let data_offset = HEADER_SIZE + RowDefinition::SIZE * u32::try_from(rows.len()).unwrap();
+
+		let mut definitions = Vec::with_capacity(rows.len());
+		let mut data = Vec::new();
+		for (id, row_count, row_data) in rows {
+			let offset = data_offset + u32::try_from(data.len()).unwrap();
+			definitions.push(RowDefinition { id, offset });
+
+			let data_size = u32::try_from(row_data.len()).unwrap();
+			data.extend_from_slice(&data_size.to_be_bytes());
+			data.extend_from_slice(&row_count.to_be_bytes());
+			data.extend_from_slice(&row_data);
+		}
+
+		Self {
+			_version: VERSION,
+			rows: definitions,
+			data_offset: data_offset.into(),
+			data,
+		}
+	}
+
+	/// Write this page in the .exd file format.
+	pub fn write(&self, mut writer: impl Write + Seek) -> Result<()> {
+		let index_size = RowDefinition::SIZE * u32::try_from(self.rows.len()).unwrap();
+
+		writer.write_all(b"EXDF")?;
+		writer.write_be(&self._version)?;
+		writer.write_be(&0u16)?;
+		writer.write_be(&index_size)?;
+		writer.write_be(&u32::try_from(self.data.len()).unwrap())?;
+		writer.write_be(&[0u8; 16])?;
+
+		for row in &self.rows {
+			writer.write_be(&row.id)?;
+			writer.write_be(&row.offset)?;
+		}
+
+		writer.write_all(&self.data)?;
+
+		Ok(())
+	}
+
 	/// Fetch the slice of data associated with the specified row. If this data
 	/// page is for a sheet with subrows, this will include all child rows of the
 	/// specified row. Otherwise, it will contain the row and any trailing string data.
@@ -65,7 +116,7 @@ impl ExcelData {
 		let maybe_subrow_offset = (0..row_header.row_count)
 			// TODO: map->try_find whenever _that_ stabilises
 			.find_map(|index| -> Option<Result<_>> {
-				let subrow_offset = offset + subrow_size * usize::try_from(index).unwrap();
+				let subrow_offset = offset + subrow_size * usize::from(index);
 				cursor.set_position(subrow_offset.try_into().unwrap());
 				match SubrowHeader::read(&mut cursor) {
 					Err(e) => Some(Err(e.into())),
@@ -95,6 +146,38 @@ impl ExcelData {
 		Ok(&self.data[subrow_offset + SubrowHeader::SIZE..subrow_offset + subrow_size])
 	}
 
+	/// Iterate over the subrows of the specified row, yielding the ID and data
+	/// of each subrow in storage order.
+	pub fn subrows(&self, row_id: u32) -> Result<impl Iterator<Item = (u16, &[u8])>> {
+		let (row_header, offset) = self.row_meta(row_id)?;
+
+		let row_count = usize::from(row_header.row_count);
+		let subrow_size = match row_count {
+			0 => 0,
+			count => usize::try_from(row_header.data_size).unwrap() / count,
+		};
+
+		let data = self
+			.data
+			.get(offset..offset + subrow_size * row_count)
+			.ok_or_else(|| {
+				Error::Invalid(
+					ErrorValue::Row {
+						row: row_id,
+						subrow: 0,
+						sheet: None,
+					},
+					"subrow data extends past the end of the page".into(),
+				)
+			})?;
+		Ok(data
+			.chunks_exact(subrow_size.max(SubrowHeader::SIZE))
+			.map(|subrow| {
+				let (id, data) = subrow.split_at(SubrowHeader::SIZE);
+				(u16::from_be_bytes([id[0], id[1]]), data)
+			}))
+	}
+
 	// TODO: This is a hacky implementation for use in excel's sheet iterator. Remove once iterator is rewritten to be less insane.
 	pub(crate) fn subrow_max(&self, row_id: u32) -> Result<u16> {
 		let (row_header, offset) = self.row_meta(row_id)?;
@@ -105,13 +188,11 @@ impl ExcelData {
 		let mut cursor = Cursor::new(&self.data);
 		(0..row_header.row_count)
 			.map(move |index| -> Result<_> {
-				let subrow_offset = offset + subrow_size * usize::try_from(index).unwrap();
+				let subrow_offset = offset + subrow_size * usize::from(index);
 				cursor.set_position(subrow_offset.try_into().unwrap());
 				Ok(SubrowHeader::read(&mut cursor)?)
 			})
-			.fold(Ok(0), |a, b| {
-				a.and_then(|a| b.map(|b| std::cmp::max(a, b.id)))
-			})
+			.try_fold(0, |a, b| b.map(|b| std::cmp::max(a, b.id)))
 	}
 
 	fn row_meta(&self, row_id: u32) -> Result<(RowHeader, usize)> {
@@ -130,7 +211,7 @@ impl ExcelData {
 	fn row_definition(&self, row_id: u32) -> Result<&RowDefinition> {
 		// In all likelihood the row can be found simply by indexing
 		// the vector based on the ID's offset from the first row.
-		let first_row_id = self.rows.get(0).map_or(0, |row| row.id);
+		let first_row_id = self.rows.first().map_or(0, |row| row.id);
 		if let Some(row_index) = row_id.checked_sub(first_row_id).map(|i| i as usize) {
 			if row_index < self.rows.len() && self.rows[row_index].id == row_id {
 				return Ok(&self.rows[row_index]);
@@ -200,3 +281,29 @@ pub struct SubrowHeader {
 impl SubrowHeader {
 	pub const SIZE: usize = 2;
 }
+
+#[cfg(test)]
+mod test {
+	use crate::error::Error;
+
+	use super::ExcelData;
+
+	fn page() -> ExcelData {
+		// Two subrows of a single byte each, following their IDs.
+		ExcelData::new([(1, 2, vec![0, 0, 10, 0, 1, 11])])
+	}
+
+	#[test]
+	fn iterates_subrows() {
+		let page = page();
+		let subrows = page.subrows(1).unwrap().collect::<Vec<_>>();
+		assert_eq!(subrows, [(0, &[10][..]), (1, &[11][..])]);
+	}
+
+	#[test]
+	fn rejects_truncated_subrows() {
+		let mut page = page();
+		page.data.truncate(page.data.len() - 2);
+		assert!(matches!(page.subrows(1), Err(Error::Invalid(..))));
+	}
+}
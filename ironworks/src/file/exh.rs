@@ -1,8 +1,11 @@
 //! Structs and utilities for parsing .exh files.
 
-use std::collections::HashSet;
+use std::{
+	collections::HashSet,
+	io::{Seek, Write},
+};
 
-use binrw::{BinRead, binread};
+use binrw::{BinRead, BinWriterExt, binread};
 use getset::{CopyGetters, Getters};
 use num_enum::IntoPrimitive;
 
@@ -63,6 +66,65 @@ pub struct ExcelHeader {
 	languages: HashSet<u8>,
 }
 
+impl ExcelHeader {
+	/// Build a header for a sheet with the provided layout and pages.
+	pub fn new(
+		row_size: u16,
+		kind: SheetKind,
+		columns: Vec<ColumnDefinition>,
+		pages: Vec<PageDefinition>,
+		languages: HashSet<u8>,
+	) -> Self {
+		Self {
+			_version: VERSION,
+			row_size,
+			kind,
+			_row_count: pages.iter().map(|page| page.row_count).sum(),
+			columns,
+			pages,
+			languages,
+		}
+	}
+
+	/// Write this header in the .exh file format.
+	pub fn write(&self, mut writer: impl Write + Seek) -> Result<()> {
+		let count = |count: usize| u16::try_from(count).unwrap();
+
+		writer.write_all(b"EXHF")?;
+		writer.write_be(&self._version)?;
+		writer.write_be(&self.row_size)?;
+		writer.write_be(&count(self.columns.len()))?;
+		writer.write_be(&count(self.pages.len()))?;
+		writer.write_be(&count(self.languages.len()))?;
+		writer.write_be(&[0u8; 3])?;
+		writer.write_be(&(self.kind as u8))?;
+		writer.write_be(&0u16)?;
+		writer.write_be(&self._row_count)?;
+		writer.write_be(&[0u8; 8])?;
+
+		for column in &self.columns {
+			writer.write_be(&u16::from(column.kind))?;
+			writer.write_be(&column.offset)?;
+		}
+
+		for page in &self.pages {
+			writer.write_be(&page.start_id)?;
+			writer.write_be(&page.row_count)?;
+		}
+
+		// Languages are stored in a set - sort them to keep output stable.
+		let mut languages = self.languages.iter().copied().collect::<Vec<_>>();
+		languages.sort_unstable();
+		for language in languages {
+			writer.write_be(&[language, 0])?;
+		}
+
+		Ok(())
+	}
+}
+
+const VERSION: u16 = 3;
+
 impl File for ExcelHeader {
 	fn read(mut stream: impl FileStream) -> Result<Self> {
 		Ok(<Self as BinRead>::read(&mut stream)?)
@@ -100,6 +162,35 @@ pub struct ColumnDefinition {
 	offset: u16,
 }
 
+impl ColumnDefinition {
+	/// Build a definition for a column of the given kind at a byte offset within
+	/// the row structured data.
+	pub fn new(kind: ColumnKind, offset: u16) -> Self {
+		Self { kind, offset }
+	}
+
+	/// Size of the column's data in bytes.
+	pub fn size(&self) -> u16 {
+		use ColumnKind as K;
+		match self.kind {
+			K::Bool
+			| K::Int8
+			| K::UInt8
+			| K::PackedBool0
+			| K::PackedBool1
+			| K::PackedBool2
+			| K::PackedBool3
+			| K::PackedBool4
+			| K::PackedBool5
+			| K::PackedBool6
+			| K::PackedBool7 => 1,
+			K::Int16 | K::UInt16 => 2,
+			K::String | K::Int32 | K::UInt32 | K::Float32 => 4,
+			K::Int64 | K::UInt64 => 8,
+		}
+	}
+}
+
 /// The kind of data structure stored in a column.
 #[allow(missing_docs)]
 #[binread]
@@ -146,6 +237,16 @@ pub struct PageDefinition {
 	row_count: u32,
 }
 
+impl PageDefinition {
+	/// Build a definition for a page covering `row_count` IDs from `start_id`.
+	pub fn new(start_id: u32, row_count: u32) -> Self {
+		Self {
+			start_id,
+			row_count,
+		}
+	}
+}
+
 #[binread]
 #[derive(Debug)]
 #[br(big)]
@@ -0,0 +1,183 @@
+use std::{
+	collections::{BTreeMap, HashMap},
+	sync::Arc,
+};
+
+/// Statistics for an ironworks file cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+	/// Number of file reads served from the cache.
+	pub hits: u64,
+	/// Number of file reads that had to be fetched from a resource.
+	pub misses: u64,
+	/// Number of files currently cached.
+	pub entries: usize,
+	/// Total size of the cached files, in bytes.
+	pub size: usize,
+	/// Maximum total size of cached files, in bytes.
+	pub capacity: usize,
+}
+
+/// (resource index, path)
+type Key = (usize, String);
+
+/// Least-recently-used cache of file bytes, bounded by total size.
+#[derive(Debug)]
+pub struct FileCache {
+	entries: HashMap<Key, (Arc<[u8]>, u64)>,
+	// Last use -> key, oldest first.
+	recency: BTreeMap<u64, Key>,
+	tick: u64,
+	stats: CacheStats,
+}
+
+impl FileCache {
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			entries: Default::default(),
+			recency: Default::default(),
+			tick: 0,
+			stats: CacheStats {
+				capacity,
+				..Default::default()
+			},
+		}
+	}
+
+	pub fn stats(&self) -> CacheStats {
+		self.stats
+	}
+
+	pub fn get(&mut self, resource: usize, path: &str) -> Option<Arc<[u8]>> {
+		// TODO: This allocates a key per lookup - worth avoiding if it shows up in profiles.
+		let key = (resource, path.to_string());
+		let (data, last_used) = self.entries.get_mut(&key)?;
+
+		self.stats.hits += 1;
+		self.tick += 1;
+		self.recency.remove(last_used);
+		self.recency.insert(self.tick, key);
+		*last_used = self.tick;
+
+		Some(data.clone())
+	}
+
+	pub fn record_miss(&mut self) {
+		self.stats.misses += 1;
+	}
+
+	pub fn insert(&mut self, resource: usize, path: &str, data: Arc<[u8]>) {
+		// Files that could never fit are not worth evicting everything else for.
+		if data.len() > self.stats.capacity {
+			return;
+		}
+
+		let key = (resource, path.to_string());
+		self.remove(&key);
+
+		self.tick += 1;
+		self.stats.size += data.len();
+		self.stats.entries += 1;
+		self.recency.insert(self.tick, key.clone());
+		self.entries.insert(key, (data, self.tick));
+
+		while self.stats.size > self.stats.capacity {
+			let Some((_, oldest)) = self.recency.pop_first() else {
+				break;
+			};
+			self.remove(&oldest);
+		}
+	}
+
+	/// Remove a path from the cache, for every resource.
+	pub fn invalidate(&mut self, path: &str) {
+		let keys = self
+			.entries
+			.keys()
+			.filter(|(_, key_path)| key_path == path)
+			.cloned()
+			.collect::<Vec<_>>();
+
+		for key in keys {
+			self.remove(&key);
+		}
+	}
+
+	pub fn clear(&mut self) {
+		self.entries.clear();
+		self.recency.clear();
+		self.stats.entries = 0;
+		self.stats.size = 0;
+	}
+
+	fn remove(&mut self, key: &Key) {
+		if let Some((data, last_used)) = self.entries.remove(key) {
+			self.recency.remove(&last_used);
+			self.stats.size -= data.len();
+			self.stats.entries -= 1;
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::sync::Arc;
+
+	use super::FileCache;
+
+	fn data(size: usize) -> Arc<[u8]> {
+		vec![0; size].into()
+	}
+
+	#[test]
+	fn least_recently_used_files_are_evicted_first() {
+		let mut cache = FileCache::new(30);
+		cache.insert(0, "a", data(10));
+		cache.insert(0, "b", data(10));
+		cache.insert(0, "c", data(10));
+
+		// Touch a, so b is now the oldest.
+		assert!(cache.get(0, "a").is_some());
+		cache.insert(0, "d", data(10));
+
+		assert!(cache.get(0, "a").is_some());
+		assert!(cache.get(0, "b").is_none());
+		assert!(cache.get(0, "c").is_some());
+		assert_eq!(cache.stats().size, 30);
+		assert_eq!(cache.stats().entries, 3);
+	}
+
+	#[test]
+	fn files_larger_than_the_cache_are_skipped() {
+		let mut cache = FileCache::new(10);
+		cache.insert(0, "a", data(5));
+		cache.insert(0, "b", data(11));
+
+		assert!(cache.get(0, "a").is_some());
+		assert!(cache.get(0, "b").is_none());
+	}
+
+	#[test]
+	fn invalidation_covers_every_resource() {
+		let mut cache = FileCache::new(100);
+		cache.insert(0, "a", data(1));
+		cache.insert(1, "a", data(1));
+		cache.insert(1, "b", data(1));
+
+		cache.invalidate("a");
+		assert!(cache.get(0, "a").is_none());
+		assert!(cache.get(1, "a").is_none());
+		assert!(cache.get(1, "b").is_some());
+		assert_eq!(cache.stats().entries, 1);
+	}
+
+	#[test]
+	fn replacing_a_file_updates_the_size() {
+		let mut cache = FileCache::new(100);
+		cache.insert(0, "a", data(10));
+		cache.insert(0, "a", data(20));
+
+		assert_eq!(cache.stats().size, 20);
+		assert_eq!(cache.stats().entries, 1);
+	}
+}
@@ -1,7 +1,7 @@
 use std::{
-	io::{Read, Seek},
+	io::{Cursor, Read, Seek},
 	rc::Rc,
-	sync::Arc,
+	sync::{Arc, Mutex},
 };
 
 use derivative::Derivative;
@@ -9,8 +9,12 @@ use derivative::Derivative;
 use crate::{
 	error::{Error, ErrorValue, Result},
 	file::File,
+	file_cache::{CacheStats, FileCache},
 };
 
+#[cfg(feature = "async")]
+use crate::async_resource::AsyncResource;
+
 /// Representation of a file stream read from a resource.
 pub trait FileStream: Read + Seek + 'static {}
 impl<T> FileStream for T where T: Read + Seek + 'static {}
@@ -85,25 +89,30 @@ impl<R: Resource + ?Sized> Resource for Arc<R> {
 /// Core ironworks struct. Add one or more resources to query files.
 #[derive(Derivative)]
 #[derivative(Debug)]
-pub struct Ironworks<R: Resource = Box<dyn Resource>> {
+pub struct Ironworks<R = Box<dyn Resource>> {
 	#[derivative(Debug = "ignore")]
 	resources: Vec<R>,
-	// todo: does this own the file cache, then?
+	cache: Option<Mutex<FileCache>>,
 }
 
 pub type SharedIronworks = Ironworks<Box<dyn Resource + Send + Sync>>;
 
+/// Ironworks instance serving files from async resources.
+#[cfg(feature = "async")]
+pub type AsyncIronworks = Ironworks<Box<dyn AsyncResource>>;
+
 impl Default for Ironworks {
 	fn default() -> Self {
 		Self::new()
 	}
 }
 
-impl<R: Resource> Ironworks<R> {
+impl<R> Ironworks<R> {
 	/// Build a new instance of ironworks.
 	pub fn new() -> Self {
 		Self {
 			resources: Default::default(),
+			cache: None,
 		}
 	}
 
@@ -129,6 +138,56 @@ impl<R: Resource> Ironworks<R> {
 		self
 	}
 
+	/// Cache up to `capacity` bytes of file data read through this instance.
+	/// Least recently used files are evicted first once the limit is reached.
+	#[must_use]
+	pub fn with_cache(mut self, capacity: usize) -> Self {
+		self.set_cache(capacity);
+		self
+	}
+
+	/// Cache up to `capacity` bytes of file data read through this instance.
+	/// Replaces any existing cache, discarding its contents and statistics.
+	pub fn set_cache(&mut self, capacity: usize) {
+		self.cache = Some(Mutex::new(FileCache::new(capacity)));
+	}
+
+	/// Statistics for the file cache, if one has been configured.
+	pub fn cache_stats(&self) -> Option<CacheStats> {
+		self.cache.as_ref().map(|cache| lock(cache).stats())
+	}
+
+	/// Remove any cached data for the file at `path`, such that the next read
+	/// will be fetched from the resources again.
+	pub fn invalidate(&self, path: &str) {
+		if let Some(cache) = &self.cache {
+			lock(cache).invalidate(&path.to_lowercase());
+		}
+	}
+
+	/// Remove all cached file data. Statistics are retained.
+	pub fn clear_cache(&self) {
+		if let Some(cache) = &self.cache {
+			lock(cache).clear();
+		}
+	}
+
+	fn cached(&self, index: usize, path: &str) -> Option<Arc<[u8]>> {
+		lock(self.cache.as_ref()?).get(index, path)
+	}
+
+	fn cache_file(&self, index: usize, path: &str, data: Vec<u8>) -> Arc<[u8]> {
+		let data = Arc::<[u8]>::from(data);
+		if let Some(cache) = &self.cache {
+			let mut cache = lock(cache);
+			cache.record_miss();
+			cache.insert(index, path, data.clone());
+		}
+		data
+	}
+}
+
+impl<R: Resource> Ironworks<R> {
 	/// Get the version string for the file at `path`.
 	pub fn version(&self, path: &str) -> Result<String> {
 		self.find_first(path, |resource| resource.version(path))
@@ -137,8 +196,28 @@ impl<R: Resource> Ironworks<R> {
 	/// Read the file at `path`, using file type F to parse. To retrieve the file
 	/// as raw bytes, pass `Vec<u8>` to F.
 	pub fn file<F: File>(&self, path: &str) -> Result<F> {
-		let stream = self.find_first(path, |resource| resource.file(path))?;
-		F::read(stream)
+		if self.cache.is_none() {
+			let stream = self.find_first(path, |resource| resource.file(path))?;
+			return F::read(stream);
+		}
+
+		let key = path.to_lowercase();
+		for (index, resource) in self.resources.iter().enumerate().rev() {
+			if let Some(data) = self.cached(index, &key) {
+				return F::read(Cursor::new(data));
+			}
+
+			let mut stream = match resource.file(path) {
+				Err(Error::NotFound(ErrorValue::Path(_))) => continue,
+				other => other?,
+			};
+
+			let mut data = Vec::new();
+			stream.read_to_end(&mut data)?;
+			return F::read(Cursor::new(self.cache_file(index, &key, data)));
+		}
+
+		Err(Error::NotFound(ErrorValue::Path(path.into())))
 	}
 
 	/// Check whether the file at `path` exists in any resource, without reading
@@ -168,3 +247,210 @@ impl<R: Resource> Ironworks<R> {
 			.unwrap_or_else(|| Err(Error::NotFound(ErrorValue::Path(path.into()))))
 	}
 }
+
+#[cfg(feature = "async")]
+impl<R: AsyncResource> Ironworks<R> {
+	/// Get the version string for the file at `path`.
+	pub async fn version_async(&self, path: &str) -> Result<String> {
+		for resource in self.resources.iter().rev() {
+			match resource.version(path).await {
+				Err(Error::NotFound(ErrorValue::Path(_))) => continue,
+				other => return other,
+			}
+		}
+		Err(Error::NotFound(ErrorValue::Path(path.into())))
+	}
+
+	/// Read the file at `path`, using file type F to parse. Resources are
+	/// searched in the same order, and share the same cache, as [`Ironworks::file`].
+	pub async fn file_async<F: File>(&self, path: &str) -> Result<F> {
+		let key = path.to_lowercase();
+		for (index, resource) in self.resources.iter().enumerate().rev() {
+			if let Some(data) = self.cached(index, &key) {
+				return F::read(Cursor::new(data));
+			}
+
+			let data = match resource.file(path).await {
+				Err(Error::NotFound(ErrorValue::Path(_))) => continue,
+				other => other?,
+			};
+
+			return F::read(Cursor::new(self.cache_file(index, &key, data)));
+		}
+
+		Err(Error::NotFound(ErrorValue::Path(path.into())))
+	}
+
+	/// Check whether the file at `path` exists in any resource.
+	pub async fn exists_async(&self, path: &str) -> Result<bool> {
+		for resource in self.resources.iter().rev() {
+			match resource.exists(path).await {
+				Ok(true) => return Ok(true),
+				Ok(false) => continue,
+				Err(Error::NotFound(ErrorValue::Path(_))) => continue,
+				Err(error) => return Err(error),
+			}
+		}
+		Ok(false)
+	}
+}
+
+fn lock(cache: &Mutex<FileCache>) -> std::sync::MutexGuard<'_, FileCache> {
+	// The cache is left consistent between operations, so a poisoned lock is safe to reuse.
+	cache.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+#[cfg(test)]
+mod test {
+	use std::{
+		collections::HashMap,
+		io::Cursor,
+		sync::{
+			Arc,
+			atomic::{AtomicUsize, Ordering},
+		},
+	};
+
+	use crate::error::{Error, ErrorValue, Result};
+
+	use super::{FileStream, Ironworks, Resource};
+
+	#[derive(Default)]
+	struct TestResource {
+		files: HashMap<&'static str, &'static [u8]>,
+		reads: Arc<AtomicUsize>,
+	}
+
+	impl TestResource {
+		fn with_file(mut self, path: &'static str, data: &'static [u8]) -> Self {
+			self.files.insert(path, data);
+			self
+		}
+	}
+
+	impl Resource for TestResource {
+		fn version(&self, path: &str) -> Result<String> {
+			Err(Error::NotFound(ErrorValue::Path(path.into())))
+		}
+
+		fn file(&self, path: &str) -> Result<Box<dyn FileStream>> {
+			self.reads.fetch_add(1, Ordering::SeqCst);
+			let data = self
+				.files
+				.get(path)
+				.ok_or_else(|| Error::NotFound(ErrorValue::Path(path.into())))?;
+			Ok(Box::new(Cursor::new(data.to_vec())))
+		}
+	}
+
+	#[test]
+	fn cached_reads_skip_the_resource() {
+		let resource = TestResource::default().with_file("a", b"data");
+		let reads = resource.reads.clone();
+		let ironworks = Ironworks::<TestResource>::new()
+			.with_resource(resource)
+			.with_cache(1024);
+
+		assert_eq!(ironworks.file::<Vec<u8>>("a").unwrap(), b"data");
+		assert_eq!(ironworks.file::<Vec<u8>>("a").unwrap(), b"data");
+		assert_eq!(reads.load(Ordering::SeqCst), 1);
+
+		let stats = ironworks.cache_stats().unwrap();
+		assert_eq!((stats.hits, stats.misses), (1, 1));
+		assert_eq!((stats.entries, stats.size), (1, 4));
+	}
+
+	#[test]
+	fn invalidated_files_are_read_again() {
+		let resource = TestResource::default().with_file("a", b"data");
+		let reads = resource.reads.clone();
+		let ironworks = Ironworks::<TestResource>::new()
+			.with_resource(resource)
+			.with_cache(1024);
+
+		ironworks.file::<Vec<u8>>("a").unwrap();
+		ironworks.invalidate("A");
+		ironworks.file::<Vec<u8>>("a").unwrap();
+		assert_eq!(reads.load(Ordering::SeqCst), 2);
+
+		ironworks.clear_cache();
+		assert_eq!(ironworks.cache_stats().unwrap().entries, 0);
+	}
+
+	#[test]
+	fn later_resources_take_precedence_over_cached_files() {
+		let ironworks = Ironworks::<TestResource>::new()
+			.with_resource(TestResource::default().with_file("a", b"base"))
+			.with_resource(TestResource::default().with_file("b", b"other"))
+			.with_cache(1024);
+
+		assert_eq!(ironworks.file::<Vec<u8>>("a").unwrap(), b"base");
+		assert_eq!(ironworks.file::<Vec<u8>>("a").unwrap(), b"base");
+
+		let ironworks = ironworks.with_resource(TestResource::default().with_file("a", b"mod"));
+		assert_eq!(ironworks.file::<Vec<u8>>("a").unwrap(), b"mod");
+	}
+
+	#[test]
+	fn missing_files_are_not_found() {
+		let ironworks = Ironworks::<TestResource>::new()
+			.with_resource(TestResource::default())
+			.with_cache(1024);
+
+		assert!(matches!(
+			ironworks.file::<Vec<u8>>("a"),
+			Err(Error::NotFound(ErrorValue::Path(_)))
+		));
+	}
+
+	#[cfg(feature = "async")]
+	fn block_on<T>(future: impl Future<Output = T>) -> T {
+		tokio::runtime::Builder::new_current_thread()
+			.build()
+			.unwrap()
+			.block_on(future)
+	}
+
+	#[cfg(feature = "async")]
+	#[test]
+	fn async_reads_lift_sync_resources() {
+		use crate::{AsyncIronworks, AsyncResource, Blocking};
+
+		let resource = TestResource::default().with_file("a", b"data");
+		let reads = resource.reads.clone();
+		let ironworks = AsyncIronworks::new()
+			.with_resource(Box::new(Blocking::new(resource)) as Box<dyn AsyncResource>)
+			.with_cache(1024);
+
+		block_on(async {
+			assert_eq!(ironworks.file_async::<Vec<u8>>("a").await.unwrap(), b"data");
+			assert_eq!(ironworks.file_async::<Vec<u8>>("a").await.unwrap(), b"data");
+			assert!(!ironworks.exists_async("b").await.unwrap());
+			assert!(matches!(
+				ironworks.file_async::<Vec<u8>>("b").await,
+				Err(Error::NotFound(ErrorValue::Path(_)))
+			));
+		});
+
+		// One read for a, one for each lookup of b.
+		assert_eq!(reads.load(Ordering::SeqCst), 3);
+	}
+
+	#[cfg(feature = "async")]
+	#[test]
+	fn blocking_reads_start_when_polled() {
+		use crate::{AsyncResource, Blocking};
+
+		let resource = TestResource::default().with_file("a", b"data");
+		let reads = resource.reads.clone();
+		let blocking = Blocking::new(resource);
+
+		// Built and dropped outside of a runtime.
+		drop(blocking.file("a"));
+		assert_eq!(reads.load(Ordering::SeqCst), 0);
+
+		let future = blocking.file("a");
+		assert_eq!(block_on(future).unwrap(), b"data");
+		assert_eq!(reads.load(Ordering::SeqCst), 1);
+	}
+}
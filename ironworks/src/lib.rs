@@ -6,13 +6,22 @@
 // Doc config
 #![cfg_attr(docsrs, feature(doc_auto_cfg, doc_cfg))]
 
+#[cfg(feature = "async")]
+mod async_resource;
 mod error;
+mod file_cache;
 mod ironworks;
 mod utility;
 
+#[cfg(feature = "discovery")]
+pub mod discovery;
 #[cfg(feature = "excel")]
 pub mod excel;
 pub mod file;
+#[cfg(feature = "loose")]
+pub mod loose;
+#[cfg(feature = "modpack")]
+pub mod modpack;
 #[cfg(feature = "sestring")]
 pub mod sestring;
 #[cfg(feature = "sqpack")]
@@ -23,6 +32,13 @@ pub mod zipatch;
 pub use {
 	crate::ironworks::{FileStream, Ironworks, Resource, SharedIronworks},
 	error::{Error, ErrorValue},
+	file_cache::CacheStats,
+};
+
+#[cfg(feature = "async")]
+pub use {
+	crate::ironworks::AsyncIronworks,
+	async_resource::{AsyncResource, Blocking, BoxFuture},
 };
 
 #[cfg(test)]
@@ -35,6 +51,8 @@ mod test {
 		assert_send::<SharedIronworks>();
 		assert_send::<Error>();
 		assert_send::<ErrorValue>();
+		#[cfg(feature = "async")]
+		assert_send::<AsyncIronworks>();
 	}
 
 	#[test]
@@ -43,5 +61,7 @@ mod test {
 		assert_sync::<SharedIronworks>();
 		assert_sync::<Error>();
 		assert_sync::<ErrorValue>();
+		#[cfg(feature = "async")]
+		assert_sync::<AsyncIronworks>();
 	}
 }
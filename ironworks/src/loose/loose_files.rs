@@ -0,0 +1,257 @@
+use std::{
+	collections::HashMap,
+	fs, io,
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex},
+};
+
+use crate::{
+	Resource,
+	error::{Error, ErrorValue, Result},
+	ironworks::FileStream,
+};
+
+type FileTable = HashMap<String, PathBuf>;
+
+/// Resource serving loose files from a directory, laid out by their game
+/// paths, i.e. `chara/equipment/e0001/...`.
+///
+/// Paths are matched case-insensitively. The directory is scanned on first
+/// lookup; call [`refresh`](Self::refresh) to pick up files changed since.
+#[derive(Debug)]
+pub struct LooseFiles {
+	root: PathBuf,
+	remaps: HashMap<String, String>,
+	version: Option<String>,
+
+	files: Mutex<Option<Arc<FileTable>>>,
+}
+
+impl LooseFiles {
+	/// Build a resource serving files from the directory at `root`.
+	pub fn new(root: impl Into<PathBuf>) -> Self {
+		Self {
+			root: root.into(),
+			remaps: Default::default(),
+			version: None,
+
+			files: Default::default(),
+		}
+	}
+
+	/// Serve the game path `path` from `target`, relative to the root directory.
+	/// Remapped paths take priority over files at their own game path.
+	#[must_use]
+	pub fn with_remap(mut self, path: &str, target: &str) -> Self {
+		self.add_remap(path, target);
+		self
+	}
+
+	/// Serve the game path `path` from `target`, relative to the root directory.
+	/// Remapped paths take priority over files at their own game path.
+	pub fn add_remap(&mut self, path: &str, target: &str) {
+		self.remaps.insert(normalize(path), normalize(target));
+	}
+
+	/// Report `version` for files served by this resource. Without a version,
+	/// version lookups fall through to the next resource.
+	#[must_use]
+	pub fn with_version(mut self, version: impl Into<String>) -> Self {
+		self.set_version(version);
+		self
+	}
+
+	/// Report `version` for files served by this resource. Without a version,
+	/// version lookups fall through to the next resource.
+	pub fn set_version(&mut self, version: impl Into<String>) {
+		self.version = Some(version.into());
+	}
+
+	/// Discard the scanned directory listing, such that it is rebuilt on next
+	/// lookup. Lookups already holding the previous listing are unaffected.
+	pub fn refresh(&self) {
+		*self.files.lock().unwrap() = None;
+	}
+
+	fn files(&self) -> Result<Arc<FileTable>> {
+		let mut files = self.files.lock().unwrap();
+		if let Some(files) = &*files {
+			return Ok(files.clone());
+		}
+
+		// A root that doesn't exist (yet) simply serves no files.
+		let mut table = FileTable::new();
+		if self.root.exists() {
+			scan(&self.root, "", &mut table)?;
+		}
+		let table = Arc::new(table);
+		*files = Some(table.clone());
+		Ok(table)
+	}
+
+	/// Resolve a game path to the file on disk that serves it.
+	fn resolve(&self, path: &str) -> Result<PathBuf> {
+		let path = normalize(path);
+		let target = self.remaps.get(&path).unwrap_or(&path);
+
+		self.files()?
+			.get(target)
+			.cloned()
+			.ok_or_else(|| Error::NotFound(ErrorValue::Path(path)))
+	}
+}
+
+/// Game paths are lower case and `/`-separated.
+fn normalize(path: &str) -> String {
+	path.trim_start_matches(['/', '\\'])
+		.replace('\\', "/")
+		.to_lowercase()
+}
+
+fn scan(directory: &Path, prefix: &str, table: &mut FileTable) -> io::Result<()> {
+	for entry in fs::read_dir(directory)? {
+		let entry = entry?;
+		let name = entry.file_name().to_string_lossy().to_lowercase();
+		let path = format!("{prefix}{name}");
+
+		match entry.file_type()?.is_dir() {
+			true => scan(&entry.path(), &format!("{path}/"), table)?,
+			false => {
+				table.insert(path, entry.path());
+			}
+		}
+	}
+
+	Ok(())
+}
+
+impl Resource for LooseFiles {
+	fn version(&self, path: &str) -> Result<String> {
+		self.resolve(path)?;
+		self.version
+			.clone()
+			.ok_or_else(|| Error::NotFound(ErrorValue::Path(path.into())))
+	}
+
+	fn file(&self, path: &str) -> Result<Box<dyn FileStream>> {
+		let file = fs::File::open(self.resolve(path)?)?;
+		Ok(Box::new(file))
+	}
+
+	fn exists(&self, path: &str) -> Result<bool> {
+		match self.resolve(path) {
+			Ok(_) => Ok(true),
+			Err(Error::NotFound(_)) => Ok(false),
+			Err(error) => Err(error),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::{fs, io::Read};
+
+	use crate::{Error, ErrorValue, Ironworks, Resource, utility::TempDirectory};
+
+	use super::LooseFiles;
+
+	fn read(resource: &impl Resource, path: &str) -> String {
+		let mut contents = String::new();
+		resource
+			.file(path)
+			.unwrap()
+			.read_to_string(&mut contents)
+			.unwrap();
+		contents
+	}
+
+	#[test]
+	fn files_are_found_regardless_of_case() {
+		let directory = TempDirectory::with_files(&[("Chara/Equipment/e0001/Top.mdl", "top")]);
+		let loose = LooseFiles::new(directory.path());
+
+		assert_eq!(read(&loose, "chara/equipment/e0001/top.mdl"), "top");
+		assert_eq!(read(&loose, "CHARA/equipment/E0001/top.MDL"), "top");
+	}
+
+	#[test]
+	fn missing_files_are_not_found() {
+		let directory = TempDirectory::with_files(&[("exd/root.exl", "")]);
+		let loose = LooseFiles::new(directory.path());
+
+		assert!(!loose.exists("exd/item.exh").unwrap());
+		assert!(matches!(
+			loose.file("exd/item.exh"),
+			Err(Error::NotFound(ErrorValue::Path(_)))
+		));
+	}
+
+	#[test]
+	fn remapped_paths_are_served_from_their_target() {
+		let directory = TempDirectory::with_files(&[
+			("files/replacement.tex", "replacement"),
+			("ui/icon/000000/000001.tex", "original"),
+		]);
+		let loose = LooseFiles::new(directory.path())
+			.with_remap("ui/icon/000000/000001.tex", "files/Replacement.tex");
+
+		assert!(loose.exists("ui/icon/000000/000001.tex").unwrap());
+		assert_eq!(read(&loose, "ui/icon/000000/000001.tex"), "replacement");
+	}
+
+	#[test]
+	fn a_missing_root_serves_nothing() {
+		let directory = TempDirectory::new();
+		let loose = LooseFiles::new(directory.path().join("missing"));
+
+		assert!(!loose.exists("exd/root.exl").unwrap());
+	}
+
+	#[test]
+	fn refreshing_picks_up_new_files() {
+		let directory = TempDirectory::with_files(&[("exd/root.exl", "")]);
+		let ironworks =
+			Ironworks::<LooseFiles>::new().with_resource(LooseFiles::new(directory.path()));
+		assert!(!ironworks.exists("exd/item.exh").unwrap());
+
+		fs::write(directory.path().join("exd/item.exh"), "").unwrap();
+		assert!(!ironworks.exists("exd/item.exh").unwrap());
+
+		ironworks.resources()[0].refresh();
+		assert!(ironworks.exists("exd/item.exh").unwrap());
+	}
+
+	#[test]
+	fn versions_fall_through_unless_set() {
+		let directory = TempDirectory::with_files(&[("exd/root.exl", "")]);
+
+		let loose = LooseFiles::new(directory.path());
+		assert!(matches!(
+			loose.version("exd/root.exl"),
+			Err(Error::NotFound(ErrorValue::Path(_)))
+		));
+
+		let loose = loose.with_version("2024.01.01.0000.0000");
+		assert_eq!(
+			loose.version("exd/root.exl").unwrap(),
+			"2024.01.01.0000.0000"
+		);
+		assert!(loose.version("exd/item.exh").is_err());
+	}
+
+	#[test]
+	fn later_directories_override_earlier_ones() {
+		let base = TempDirectory::with_files(&[("exd/root.exl", "base"), ("exd/item.exh", "item")]);
+		let overlay = TempDirectory::with_files(&[("exd/root.exl", "overlay")]);
+
+		let ironworks = Ironworks::<LooseFiles>::new()
+			.with_resource(LooseFiles::new(base.path()))
+			.with_resource(LooseFiles::new(overlay.path()));
+
+		assert_eq!(
+			ironworks.file::<Vec<u8>>("exd/root.exl").unwrap(),
+			b"overlay"
+		);
+		assert_eq!(ironworks.file::<Vec<u8>>("exd/item.exh").unwrap(), b"item");
+	}
+}
@@ -0,0 +1,22 @@
+//! Resources serving game files from loose files on disk.
+
+mod loose_files;
+
+pub use loose_files::LooseFiles;
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_send() {
+		fn assert_send<T: Send>() {}
+		assert_send::<LooseFiles>();
+	}
+
+	#[test]
+	fn test_sync() {
+		fn assert_sync<T: Sync>() {}
+		assert_sync::<LooseFiles>();
+	}
+}
@@ -0,0 +1,51 @@
+use std::{
+	collections::HashMap,
+	io::{Read, Seek},
+};
+
+use serde::de::DeserializeOwned;
+use zip::ZipArchive;
+
+use crate::error::{Error, ErrorValue, Result};
+
+use super::modpack::zip_error;
+
+/// Read and parse a JSON entry from the archive.
+pub fn read_json<T: DeserializeOwned>(
+	archive: &mut ZipArchive<impl Read + Seek>,
+	name: &str,
+) -> Result<T> {
+	let text = read_text(archive, name)?;
+	serde_json::from_str(&text).map_err(|error| {
+		Error::Invalid(
+			ErrorValue::Path(name.into()),
+			format!("malformed manifest: {error}"),
+		)
+	})
+}
+
+/// Read a text entry from the archive, as written by tools that may prefix a BOM.
+pub fn read_text(archive: &mut ZipArchive<impl Read + Seek>, name: &str) -> Result<String> {
+	let mut text = String::new();
+	archive
+		.by_name(name)
+		.map_err(zip_error)?
+		.read_to_string(&mut text)?;
+
+	Ok(match text.strip_prefix('\u{feff}') {
+		Some(stripped) => stripped.to_string(),
+		None => text,
+	})
+}
+
+/// Lookup from lower case, `/`-separated entry names to the names stored in the archive.
+pub fn entry_names(archive: &ZipArchive<impl Read + Seek>) -> HashMap<String, String> {
+	archive
+		.file_names()
+		.map(|name| (normalize(name), name.to_string()))
+		.collect()
+}
+
+pub fn normalize(path: &str) -> String {
+	path.replace('\\', "/").to_lowercase()
+}
@@ -0,0 +1,27 @@
+//! Resources serving files from Penumbra and TexTools mod packages.
+
+mod archive;
+mod modpack;
+mod penumbra;
+mod textools;
+
+pub use modpack::{Group, GroupKind, GroupOption, Meta, ModPack};
+
+#[cfg(test)]
+mod test {
+	use std::io::Cursor;
+
+	use super::*;
+
+	#[test]
+	fn test_send() {
+		fn assert_send<T: Send>() {}
+		assert_send::<ModPack<Cursor<Vec<u8>>>>();
+	}
+
+	#[test]
+	fn test_sync() {
+		fn assert_sync<T: Sync>() {}
+		assert_sync::<ModPack<Cursor<Vec<u8>>>>();
+	}
+}
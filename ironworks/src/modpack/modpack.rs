@@ -0,0 +1,599 @@
+use std::{
+	collections::HashMap,
+	io::{Cursor, Read, Seek},
+	sync::Mutex,
+};
+
+use derivative::Derivative;
+use getset::{CopyGetters, Getters};
+use zip::{ZipArchive, result::ZipError};
+
+use crate::{
+	Resource,
+	error::{Error, ErrorValue, Result},
+	ironworks::FileStream,
+	sqpack,
+	utility::{HashMapCache, HashMapCacheExt},
+};
+
+use super::{penumbra, textools};
+
+/// Descriptive information about a mod package.
+#[derive(Debug, Default, Getters)]
+#[get = "pub"]
+pub struct Meta {
+	/// Name of the mod.
+	pub(super) name: String,
+	/// Author of the mod.
+	pub(super) author: String,
+	/// Version of the mod, as specified by its author.
+	pub(super) version: String,
+	/// Description of the mod.
+	pub(super) description: String,
+}
+
+/// How options within a group may be selected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupKind {
+	/// Exactly one option is selected at a time.
+	Single,
+	/// Any combination of options may be selected.
+	Multi,
+}
+
+/// A group of options the user may choose between.
+#[derive(Debug, Getters, CopyGetters)]
+pub struct Group {
+	/// Name of the group.
+	#[get = "pub"]
+	pub(super) name: String,
+	/// Description of the group.
+	#[get = "pub"]
+	pub(super) description: String,
+	/// How the group's options may be selected.
+	#[get_copy = "pub"]
+	pub(super) kind: GroupKind,
+	/// Options available within the group.
+	#[get = "pub"]
+	pub(super) options: Vec<GroupOption>,
+
+	pub(super) priority: i32,
+	/// Selection as a single option index, or a bitset of option indices.
+	pub(super) default_selection: u64,
+}
+
+/// A single option within a group.
+#[derive(Debug, Getters)]
+pub struct GroupOption {
+	/// Name of the option.
+	#[get = "pub"]
+	pub(super) name: String,
+	/// Description of the option.
+	#[get = "pub"]
+	pub(super) description: String,
+
+	pub(super) priority: i32,
+	pub(super) files: Vec<(String, Source)>,
+}
+
+/// Where the data for a redirected file is stored within the package.
+#[derive(Debug, Clone)]
+pub(super) enum Source {
+	/// A loose file, stored as its own archive entry.
+	Entry(String),
+	/// A SqPack-encoded file within a data blob entry.
+	Packed {
+		entry: String,
+		offset: u64,
+		size: u64,
+	},
+}
+
+/// Contents of a mod package, as read from its manifest.
+#[derive(Debug)]
+pub(super) struct Manifest {
+	pub meta: Meta,
+	/// Redirections applied regardless of selected options.
+	pub files: Vec<(String, Source)>,
+	pub groups: Vec<Group>,
+}
+
+/// Resource serving the files redirected by a Penumbra (`.pmp`) or TexTools
+/// (`.ttmp2`) mod package, according to the selected options.
+///
+/// Only file redirections are supported; file swaps and metadata manipulations
+/// are ignored, as are group kinds other than single and multi selection.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct ModPack<R> {
+	#[derivative(Debug = "ignore")]
+	archive: Mutex<ZipArchive<R>>,
+	// Packed data blobs are compressed as a single entry, so they are inflated
+	// once and kept, rather than inflating up to each file's offset per read.
+	#[derivative(Debug = "ignore")]
+	blobs: HashMapCache<String, Vec<u8>>,
+
+	meta: Meta,
+	files: Vec<(String, Source)>,
+	groups: Vec<Group>,
+
+	selection: Vec<u64>,
+	redirections: HashMap<String, Source>,
+}
+
+impl<R: Read + Seek> ModPack<R> {
+	/// Open a mod package from its archive. The package format is detected from
+	/// the archive's contents, and options are initially selected as the
+	/// package's defaults.
+	pub fn new(reader: R) -> Result<Self> {
+		let mut archive = ZipArchive::new(reader).map_err(zip_error)?;
+
+		let Manifest {
+			meta,
+			files,
+			groups,
+		} = if archive.index_for_name(penumbra::META).is_some() {
+			penumbra::read(&mut archive)?
+		} else if archive.index_for_name(textools::MANIFEST).is_some() {
+			textools::read(&mut archive)?
+		} else {
+			return Err(Error::Invalid(
+				ErrorValue::Other("mod package".into()),
+				"no Penumbra or TexTools manifest found".into(),
+			));
+		};
+
+		if let Some(group) = groups
+			.iter()
+			.find(|group| group.kind == GroupKind::Multi && group.options.len() > 64)
+		{
+			return Err(Error::Invalid(
+				ErrorValue::Other(format!("mod group {:?}", group.name)),
+				"multi selection groups may have at most 64 options".into(),
+			));
+		}
+
+		let selection = groups.iter().map(|group| group.default_selection).collect();
+
+		let mut modpack = Self {
+			archive: archive.into(),
+			blobs: Default::default(),
+
+			meta,
+			files,
+			groups,
+
+			selection,
+			redirections: Default::default(),
+		};
+		modpack.redirect();
+
+		Ok(modpack)
+	}
+
+	/// Descriptive information about the package.
+	pub fn meta(&self) -> &Meta {
+		&self.meta
+	}
+
+	/// Option groups available in the package.
+	pub fn groups(&self) -> &[Group] {
+		&self.groups
+	}
+
+	/// Names of the options currently selected in `group`.
+	pub fn selected(&self, group: &str) -> Result<Vec<&str>> {
+		let index = self.group_index(group)?;
+		let (group, selection) = (&self.groups[index], self.selection[index]);
+
+		let selected = group
+			.options
+			.iter()
+			.enumerate()
+			.filter(|(index, _)| is_selected(group.kind, selection, *index))
+			.map(|(_, option)| option.name.as_str())
+			.collect();
+
+		Ok(selected)
+	}
+
+	/// Select precisely the named `options` within `group`. Single selection
+	/// groups require exactly one option.
+	pub fn select(&mut self, group: &str, options: &[&str]) -> Result<()> {
+		let index = self.group_index(group)?;
+		let group = &self.groups[index];
+
+		let indices = options
+			.iter()
+			.map(|&name| {
+				group
+					.options
+					.iter()
+					.position(|option| option.name == name)
+					.ok_or_else(|| {
+						Error::NotFound(ErrorValue::Other(format!(
+							"option {name:?} in mod group {:?}",
+							group.name
+						)))
+					})
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		let selection = match (group.kind, &indices[..]) {
+			(GroupKind::Single, &[option]) => u64::try_from(option).unwrap(),
+			(GroupKind::Single, _) => {
+				return Err(Error::Invalid(
+					ErrorValue::Other(format!("mod group {:?}", group.name)),
+					"exactly one option must be selected".into(),
+				));
+			}
+			(GroupKind::Multi, indices) => {
+				indices.iter().fold(0, |mask, index| mask | (1 << index))
+			}
+		};
+
+		self.selection[index] = selection;
+		self.redirect();
+
+		Ok(())
+	}
+
+	/// Game paths redirected by the package under the current selection.
+	pub fn paths(&self) -> impl Iterator<Item = &str> {
+		self.redirections.keys().map(String::as_str)
+	}
+
+	fn group_index(&self, name: &str) -> Result<usize> {
+		self.groups
+			.iter()
+			.position(|group| group.name == name)
+			.ok_or_else(|| Error::NotFound(ErrorValue::Other(format!("mod group {name:?}"))))
+	}
+
+	/// Rebuild the redirection table for the current selection. Later groups (by
+	/// priority) and later options take precedence over earlier ones.
+	fn redirect(&mut self) {
+		let mut groups = self.groups.iter().zip(&self.selection).collect::<Vec<_>>();
+		groups.sort_by_key(|(group, _)| group.priority);
+
+		let mut redirections = HashMap::from_iter(self.files.iter().cloned());
+		for (group, &selection) in groups {
+			let mut options = group
+				.options
+				.iter()
+				.enumerate()
+				.filter(|(index, _)| is_selected(group.kind, selection, *index))
+				.map(|(_, option)| option)
+				.collect::<Vec<_>>();
+			options.sort_by_key(|option| option.priority);
+
+			for option in options {
+				redirections.extend(option.files.iter().cloned());
+			}
+		}
+
+		self.redirections = redirections;
+	}
+
+	fn read_source(&self, source: &Source) -> Result<Vec<u8>> {
+		match source {
+			Source::Entry(name) => self.read_entry(name),
+
+			Source::Packed {
+				entry,
+				offset,
+				size,
+			} => {
+				let blob = self
+					.blobs
+					.try_get_or_insert(entry.clone(), || self.read_entry(entry))?;
+
+				// Out of range data is truncated, and will fail to decode.
+				let start = usize::try_from(*offset)
+					.unwrap_or(usize::MAX)
+					.min(blob.len());
+				let end = usize::try_from(offset.saturating_add(*size))
+					.unwrap_or(usize::MAX)
+					.min(blob.len());
+				Ok(blob[start..end].to_vec())
+			}
+		}
+	}
+
+	fn read_entry(&self, name: &str) -> Result<Vec<u8>> {
+		let mut buffer = Vec::new();
+		self.archive
+			.lock()
+			.unwrap()
+			.by_name(name)
+			.map_err(zip_error)?
+			.read_to_end(&mut buffer)?;
+		Ok(buffer)
+	}
+}
+
+fn is_selected(kind: GroupKind, selection: u64, index: usize) -> bool {
+	match kind {
+		GroupKind::Single => selection == u64::try_from(index).unwrap(),
+		GroupKind::Multi => (selection >> index) & 1 == 1,
+	}
+}
+
+pub(super) fn zip_error(error: ZipError) -> Error {
+	match error {
+		ZipError::Io(error) => error.into(),
+		error => Error::Resource(error.into()),
+	}
+}
+
+impl<R: Read + Seek + 'static> Resource for ModPack<R> {
+	fn version(&self, path: &str) -> Result<String> {
+		// Mods don't carry game versions; defer to the resources beneath.
+		Err(Error::NotFound(ErrorValue::Path(path.into())))
+	}
+
+	fn file(&self, path: &str) -> Result<Box<dyn FileStream>> {
+		let source = self
+			.redirections
+			.get(&path.to_lowercase())
+			.ok_or_else(|| Error::NotFound(ErrorValue::Path(path.into())))?;
+
+		let data = Cursor::new(self.read_source(source)?);
+		let stream: Box<dyn FileStream> = match source {
+			Source::Entry(_) => Box::new(data),
+			Source::Packed { .. } => Box::new(sqpack::File::new(data)?),
+		};
+
+		Ok(stream)
+	}
+
+	fn exists(&self, path: &str) -> Result<bool> {
+		Ok(self.redirections.contains_key(&path.to_lowercase()))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::io::{Cursor, Read, Write};
+
+	use zip::{ZipWriter, write::SimpleFileOptions};
+
+	use crate::{
+		Error, ErrorValue, Resource,
+		sqpack::{FileKind, Vfs, Writer},
+	};
+
+	use super::{GroupKind, ModPack};
+
+	fn archive(entries: &[(&str, &[u8])]) -> Cursor<Vec<u8>> {
+		let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+		for (name, data) in entries {
+			writer
+				.start_file(*name, SimpleFileOptions::default())
+				.unwrap();
+			writer.write_all(data).unwrap();
+		}
+		let mut cursor = writer.finish().unwrap();
+		cursor.set_position(0);
+		cursor
+	}
+
+	fn read(modpack: &impl Resource, path: &str) -> String {
+		let mut contents = String::new();
+		modpack
+			.file(path)
+			.unwrap()
+			.read_to_string(&mut contents)
+			.unwrap();
+		contents
+	}
+
+	fn penumbra() -> ModPack<Cursor<Vec<u8>>> {
+		let meta =
+			br#"{"FileVersion": 3, "Name": "Shiny Hats", "Author": "someone", "Version": "1.0"}"#;
+		let default_mod = br#"{"Files": {"chara/equipment/e0001/texture/v01_c0101e0001_met_n.tex": "files\\normal.tex"}}"#;
+		let colour = br#"{
+			"Name": "Colour", "Priority": 0, "Type": "Single", "DefaultSettings": 1,
+			"Options": [
+				{"Name": "Red", "Files": {"chara/equipment/e0001/texture/v01_c0101e0001_met_d.tex": "files\\red.tex"}},
+				{"Name": "Blue", "Files": {"chara/equipment/e0001/texture/v01_c0101e0001_met_d.tex": "files\\Blue.tex"}}
+			]
+		}"#;
+		let extras = br#"{
+			"Name": "Extras", "Priority": 1, "Type": "Multi", "DefaultSettings": 0,
+			"Options": [
+				{"Name": "Feather", "Files": {"chara/equipment/e0001/model/c0101e0001_met.mdl": "files\\feather.mdl"}},
+				{"Name": "Flat normal", "Files": {"chara/equipment/e0001/texture/v01_c0101e0001_met_n.tex": "files\\flat.tex"}}
+			]
+		}"#;
+
+		ModPack::new(archive(&[
+			("meta.json", meta),
+			("default_mod.json", default_mod),
+			("group_001_colour.json", colour),
+			("group_002_extras.json", extras),
+			("files/normal.tex", b"normal"),
+			("files/red.tex", b"red"),
+			("files/Blue.tex", b"blue"),
+			("files/feather.mdl", b"feather"),
+			("files/flat.tex", b"flat"),
+		]))
+		.unwrap()
+	}
+
+	const DIFFUSE: &str = "chara/equipment/e0001/texture/v01_c0101e0001_met_d.tex";
+	const NORMAL: &str = "chara/equipment/e0001/texture/v01_c0101e0001_met_n.tex";
+	const MODEL: &str = "chara/equipment/e0001/model/c0101e0001_met.mdl";
+
+	#[test]
+	fn penumbra_packages_apply_default_options() {
+		let modpack = penumbra();
+		assert_eq!(modpack.meta().name(), "Shiny Hats");
+
+		let groups = modpack.groups();
+		assert_eq!(groups.len(), 2);
+		assert_eq!(groups[0].kind(), GroupKind::Single);
+		assert_eq!(groups[1].options()[1].name(), "Flat normal");
+
+		assert_eq!(modpack.selected("Colour").unwrap(), ["Blue"]);
+		assert_eq!(read(&modpack, DIFFUSE), "blue");
+		assert_eq!(read(&modpack, NORMAL), "normal");
+		assert!(!modpack.exists(MODEL).unwrap());
+	}
+
+	#[test]
+	fn selecting_options_changes_redirections() {
+		let mut modpack = penumbra();
+		modpack.select("Colour", &["Red"]).unwrap();
+		modpack
+			.select("Extras", &["Feather", "Flat normal"])
+			.unwrap();
+
+		assert_eq!(read(&modpack, DIFFUSE), "red");
+		assert_eq!(read(&modpack, MODEL), "feather");
+		// Groups override the default option.
+		assert_eq!(read(&modpack, NORMAL), "flat");
+	}
+
+	#[test]
+	fn invalid_selections_are_rejected() {
+		let mut modpack = penumbra();
+		assert!(matches!(
+			modpack.select("Colour", &["Red", "Blue"]),
+			Err(Error::Invalid(..))
+		));
+		assert!(matches!(
+			modpack.select("Colour", &["Green"]),
+			Err(Error::NotFound(_))
+		));
+		assert!(matches!(
+			modpack.select("Size", &[]),
+			Err(Error::NotFound(_))
+		));
+	}
+
+	#[test]
+	fn versions_and_unknown_paths_fall_through() {
+		let modpack = penumbra();
+		assert!(matches!(
+			modpack.version(DIFFUSE),
+			Err(Error::NotFound(ErrorValue::Path(_)))
+		));
+		assert!(matches!(
+			modpack.file("exd/root.exl"),
+			Err(Error::NotFound(ErrorValue::Path(_)))
+		));
+	}
+
+	// TTMP data is a run of SqPack file entries, as found in a dat after its headers.
+	fn sqpack_entry(path: &str, data: &[u8]) -> Vec<u8> {
+		let mut writer = Writer::new();
+		writer.add_file_as(path, FileKind::Standard, data).unwrap();
+		let package = writer.build().unwrap();
+		let dat = package.read("ffxiv/040000.win32.dat0").unwrap();
+		dat[0x800..].to_vec()
+	}
+
+	#[test]
+	fn textools_packages_decode_sqpack_data() {
+		let diffuse = sqpack_entry(DIFFUSE, b"diffuse");
+		let normal = sqpack_entry(NORMAL, &[3; 20_000]);
+		let alternate = sqpack_entry(NORMAL, b"alternate");
+		let (normal_offset, alternate_offset) = (diffuse.len(), diffuse.len() + normal.len());
+
+		let manifest = format!(
+			r#"{{
+				"TTMPVersion": "1.3w", "Name": "Wizard", "Author": "someone",
+				"SimpleModsList": [
+					{{"FullPath": "{DIFFUSE}", "ModOffset": 0, "ModSize": {}}}
+				],
+				"ModPackPages": [{{"PageIndex": 0, "ModGroups": [{{
+					"GroupName": "Normals", "SelectionType": "Single",
+					"OptionList": [
+						{{"Name": "Default", "IsChecked": true, "ModsJsons": [
+							{{"FullPath": "{NORMAL}", "ModOffset": {normal_offset}, "ModSize": {}}}
+						]}},
+						{{"Name": "Alternate", "IsChecked": false, "ModsJsons": [
+							{{"FullPath": "{NORMAL}", "ModOffset": {alternate_offset}, "ModSize": {}}}
+						]}}
+					]
+				}}]}}]
+			}}"#,
+			diffuse.len(),
+			normal.len(),
+			alternate.len(),
+		);
+		let data = [diffuse, normal, alternate].concat();
+
+		let mut modpack = ModPack::new(archive(&[
+			("TTMPL.mpl", manifest.as_bytes()),
+			("TTMPD.mpd", &data),
+		]))
+		.unwrap();
+		assert_eq!(modpack.meta().name(), "Wizard");
+
+		assert_eq!(read(&modpack, DIFFUSE), "diffuse");
+		let mut normal = Vec::new();
+		modpack
+			.file(NORMAL)
+			.unwrap()
+			.read_to_end(&mut normal)
+			.unwrap();
+		assert_eq!(normal, [3; 20_000]);
+
+		modpack.select("Normals", &["Alternate"]).unwrap();
+		assert_eq!(read(&modpack, NORMAL), "alternate");
+	}
+
+	#[test]
+	fn legacy_textools_manifests_list_entries_per_line() {
+		let diffuse = sqpack_entry(DIFFUSE, b"diffuse");
+		let manifest = format!(
+			r#"{{"FullPath": "{DIFFUSE}", "ModOffset": 0, "ModSize": {}, "DatFile": "040000"}}"#,
+			diffuse.len()
+		);
+
+		let modpack = ModPack::new(archive(&[
+			("TTMPL.mpl", manifest.as_bytes()),
+			("TTMPD.mpd", &diffuse),
+		]))
+		.unwrap();
+
+		assert!(modpack.groups().is_empty());
+		assert_eq!(read(&modpack, DIFFUSE), "diffuse");
+	}
+
+	#[test]
+	fn oversized_multi_groups_are_rejected() {
+		let options = (0..65)
+			.map(|index| {
+				format!(r#"{{"Name": "Option {index}", "IsChecked": true, "ModsJsons": []}}"#)
+			})
+			.collect::<Vec<_>>()
+			.join(",");
+		let manifest = format!(
+			r#"{{
+				"TTMPVersion": "1.3w", "Name": "Wizard", "Author": "someone",
+				"ModPackPages": [{{"PageIndex": 0, "ModGroups": [{{
+					"GroupName": "Many", "SelectionType": "Multi", "OptionList": [{options}]
+				}}]}}]
+			}}"#
+		);
+
+		assert!(matches!(
+			ModPack::new(archive(&[
+				("TTMPL.mpl", manifest.as_bytes()),
+				("TTMPD.mpd", b""),
+			])),
+			Err(Error::Invalid(..))
+		));
+	}
+
+	#[test]
+	fn unrecognised_archives_are_rejected() {
+		assert!(matches!(
+			ModPack::new(archive(&[("readme.txt", b"hello")])),
+			Err(Error::Invalid(..))
+		));
+	}
+}
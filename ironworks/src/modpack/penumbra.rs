@@ -0,0 +1,140 @@
+use std::{
+	collections::HashMap,
+	io::{Read, Seek},
+};
+
+use serde::Deserialize;
+use zip::ZipArchive;
+
+use crate::error::Result;
+
+use super::{
+	archive::{entry_names, normalize, read_json},
+	modpack::{Group, GroupKind, GroupOption, Manifest, Meta, Source},
+};
+
+pub const META: &str = "meta.json";
+const DEFAULT_MOD: &str = "default_mod.json";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PenumbraMeta {
+	#[serde(default)]
+	name: Option<String>,
+	#[serde(default)]
+	author: Option<String>,
+	#[serde(default)]
+	version: Option<String>,
+	#[serde(default)]
+	description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PenumbraGroup {
+	name: String,
+	#[serde(default)]
+	description: Option<String>,
+	#[serde(default)]
+	priority: i32,
+	#[serde(rename = "Type")]
+	kind: String,
+	#[serde(default)]
+	default_settings: u64,
+	#[serde(default)]
+	options: Vec<PenumbraOption>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PenumbraOption {
+	#[serde(default)]
+	name: String,
+	#[serde(default)]
+	description: Option<String>,
+	#[serde(default)]
+	priority: i32,
+	/// Game path -> path within the package.
+	#[serde(default)]
+	files: HashMap<String, String>,
+}
+
+pub fn read(archive: &mut ZipArchive<impl Read + Seek>) -> Result<Manifest> {
+	let meta = read_json::<PenumbraMeta>(archive, META)?;
+	let entries = entry_names(archive);
+	let files = |files: HashMap<String, String>| {
+		files
+			.into_iter()
+			.map(|(path, target)| {
+				let target = normalize(&target);
+				let entry = entries.get(&target).cloned().unwrap_or(target);
+				(path.to_lowercase(), Source::Entry(entry))
+			})
+			.collect::<Vec<_>>()
+	};
+
+	let default = match archive.index_for_name(DEFAULT_MOD) {
+		Some(_) => files(read_json::<PenumbraOption>(archive, DEFAULT_MOD)?.files),
+		None => vec![],
+	};
+
+	// Groups are stored as `group_NNN_name.json`, numbered in display order.
+	let mut group_names = archive
+		.file_names()
+		.filter(|name| name.starts_with("group_") && name.ends_with(".json"))
+		.map(String::from)
+		.collect::<Vec<_>>();
+	group_names.sort();
+
+	let mut groups = Vec::with_capacity(group_names.len());
+	for name in group_names {
+		let group = read_json::<PenumbraGroup>(archive, &name)?;
+
+		// Other kinds (i.e. IMC) don't redirect files.
+		let kind = match group.kind.as_str() {
+			"Single" => GroupKind::Single,
+			"Multi" => GroupKind::Multi,
+			_ => continue,
+		};
+
+		let options = group
+			.options
+			.into_iter()
+			.map(|option| GroupOption {
+				name: option.name,
+				description: option.description.unwrap_or_default(),
+				priority: option.priority,
+				files: files(option.files),
+			})
+			.collect::<Vec<_>>();
+
+		let default_selection = match kind {
+			GroupKind::Single
+				if group.default_settings >= u64::try_from(options.len()).unwrap() =>
+			{
+				0
+			}
+			_ => group.default_settings,
+		};
+
+		groups.push(Group {
+			name: group.name,
+			description: group.description.unwrap_or_default(),
+			kind,
+			options,
+			priority: group.priority,
+			default_selection,
+		});
+	}
+
+	Ok(Manifest {
+		meta: Meta {
+			name: meta.name.unwrap_or_default(),
+			author: meta.author.unwrap_or_default(),
+			version: meta.version.unwrap_or_default(),
+			description: meta.description.unwrap_or_default(),
+		},
+		files: default,
+		groups,
+	})
+}
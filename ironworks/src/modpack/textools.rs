@@ -0,0 +1,191 @@
+use std::io::{Read, Seek};
+
+use serde::Deserialize;
+use zip::ZipArchive;
+
+use crate::error::{Error, ErrorValue, Result};
+
+use super::{
+	archive::read_text,
+	modpack::{Group, GroupKind, GroupOption, Manifest, Meta, Source},
+};
+
+pub const MANIFEST: &str = "TTMPL.mpl";
+const DATA: &str = "TTMPD.mpd";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TexToolsManifest {
+	#[serde(default)]
+	name: Option<String>,
+	#[serde(default)]
+	author: Option<String>,
+	#[serde(default)]
+	version: Option<String>,
+	#[serde(default)]
+	description: Option<String>,
+	#[serde(default)]
+	mod_pack_pages: Option<Vec<Page>>,
+	#[serde(default)]
+	simple_mods_list: Option<Vec<ModEntry>>,
+
+	// Only present on legacy single-entry manifests.
+	#[serde(default)]
+	full_path: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Page {
+	#[serde(default)]
+	mod_groups: Vec<TexToolsGroup>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TexToolsGroup {
+	group_name: String,
+	selection_type: String,
+	#[serde(default)]
+	option_list: Vec<TexToolsOption>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TexToolsOption {
+	name: String,
+	#[serde(default)]
+	description: Option<String>,
+	#[serde(default)]
+	mods_jsons: Vec<ModEntry>,
+	#[serde(default)]
+	is_checked: bool,
+}
+
+/// A single file, stored SqPack-encoded within the data blob.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ModEntry {
+	full_path: String,
+	mod_offset: u64,
+	mod_size: u64,
+}
+
+impl ModEntry {
+	fn redirection(self) -> (String, Source) {
+		(
+			self.full_path.to_lowercase(),
+			Source::Packed {
+				entry: DATA.into(),
+				offset: self.mod_offset,
+				size: self.mod_size,
+			},
+		)
+	}
+}
+
+pub fn read(archive: &mut ZipArchive<impl Read + Seek>) -> Result<Manifest> {
+	// Early packs list one file entry per line rather than a full manifest.
+	let text = read_text(archive, MANIFEST)?;
+	let manifest = match serde_json::from_str::<TexToolsManifest>(&text) {
+		Ok(manifest) if manifest.full_path.is_none() => manifest,
+		_ => legacy_manifest(&text)?,
+	};
+
+	let files = manifest
+		.simple_mods_list
+		.unwrap_or_default()
+		.into_iter()
+		.map(ModEntry::redirection)
+		.collect();
+
+	let groups = manifest
+		.mod_pack_pages
+		.unwrap_or_default()
+		.into_iter()
+		.flat_map(|page| page.mod_groups)
+		.enumerate()
+		.map(|(index, group)| {
+			let kind = match group.selection_type.as_str() {
+				"Multi" => GroupKind::Multi,
+				_ => GroupKind::Single,
+			};
+
+			let checked = group.option_list.iter().map(|option| option.is_checked);
+			let default_selection = match kind {
+				GroupKind::Single => checked
+					.clone()
+					.position(|checked| checked)
+					.map_or(0, |index| u64::try_from(index).unwrap()),
+				GroupKind::Multi => checked
+					.enumerate()
+					.filter(|(_, checked)| *checked)
+					// Groups with more than 64 options are rejected once read, so
+					// out-of-range bits are dropped rather than overflowing here.
+					.filter_map(|(index, _)| u32::try_from(index).ok())
+					.fold(0, |mask, index| mask | 1u64.checked_shl(index).unwrap_or(0)),
+			};
+
+			let options = group
+				.option_list
+				.into_iter()
+				.map(|option| GroupOption {
+					name: option.name,
+					description: option.description.unwrap_or_default(),
+					priority: 0,
+					files: option
+						.mods_jsons
+						.into_iter()
+						.map(ModEntry::redirection)
+						.collect(),
+				})
+				.collect();
+
+			Group {
+				name: group.group_name,
+				description: String::new(),
+				kind,
+				options,
+				// Wizard groups apply in the order they are presented.
+				priority: index.try_into().unwrap_or(i32::MAX),
+				default_selection,
+			}
+		})
+		.collect();
+
+	Ok(Manifest {
+		meta: Meta {
+			name: manifest.name.unwrap_or_default(),
+			author: manifest.author.unwrap_or_default(),
+			version: manifest.version.unwrap_or_default(),
+			description: manifest.description.unwrap_or_default(),
+		},
+		files,
+		groups,
+	})
+}
+
+fn legacy_manifest(text: &str) -> Result<TexToolsManifest> {
+	let entries = text
+		.lines()
+		.filter(|line| !line.trim().is_empty())
+		.map(|line| {
+			serde_json::from_str::<ModEntry>(line).map_err(|error| {
+				Error::Invalid(
+					ErrorValue::Path(MANIFEST.into()),
+					format!("malformed manifest: {error}"),
+				)
+			})
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	Ok(TexToolsManifest {
+		name: None,
+		author: None,
+		version: None,
+		description: None,
+		mod_pack_pages: None,
+		simple_mods_list: Some(entries),
+		full_path: None,
+	})
+}
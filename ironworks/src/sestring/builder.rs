@@ -0,0 +1,143 @@
+use super::{
+	error::{Error, Result},
+	expression::{Expression, write_u32},
+	macro_kind::MacroKind,
+	payload::{MACRO_END, MACRO_START},
+	sestr::SeStr,
+	sestring::SeString,
+};
+
+/// Builder for [`SeString`] data, appending text and macro payloads in order.
+///
+/// Errors caused by invalid text or arguments are reported when the string is
+/// built.
+#[derive(Debug, Default)]
+pub struct SeStringBuilder {
+	data: Vec<u8>,
+	error: Option<Error>,
+}
+
+impl SeStringBuilder {
+	/// Create a builder for an empty string.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Append text to the string. Text may not contain U+0002, which marks the
+	/// start of a macro.
+	pub fn with_text(mut self, text: &str) -> Self {
+		self.push_text(text);
+		self
+	}
+
+	/// Append text to the string. Text may not contain U+0002, which marks the
+	/// start of a macro.
+	pub fn push_text(&mut self, text: &str) {
+		if text.as_bytes().contains(&MACRO_START) {
+			self.fail(Error::InvalidText);
+			return;
+		}
+		self.data.extend_from_slice(text.as_bytes());
+	}
+
+	/// Append the payloads of an existing string.
+	pub fn with_se_str(mut self, string: &SeStr) -> Self {
+		self.push_se_str(string);
+		self
+	}
+
+	/// Append the payloads of an existing string.
+	pub fn push_se_str(&mut self, string: &SeStr) {
+		self.data.extend_from_slice(string.as_bytes());
+	}
+
+	/// Append a call to a macro, with the provided expressions as arguments.
+	pub fn with_macro<'a>(
+		mut self,
+		kind: MacroKind,
+		arguments: impl IntoIterator<Item = Expression<'a>>,
+	) -> Self {
+		self.push_macro(kind, arguments);
+		self
+	}
+
+	/// Append a call to a macro, with the provided expressions as arguments.
+	pub fn push_macro<'a>(
+		&mut self,
+		kind: MacroKind,
+		arguments: impl IntoIterator<Item = Expression<'a>>,
+	) {
+		let mut body = vec![];
+		for argument in arguments {
+			if let Err(error) = argument.write(&mut body) {
+				self.fail(error);
+				return;
+			}
+		}
+
+		let Ok(length) = u32::try_from(body.len()) else {
+			self.fail(Error::InvalidMacro);
+			return;
+		};
+
+		self.data.extend([MACRO_START, kind.into()]);
+		write_u32(&mut self.data, length);
+		self.data.extend(body);
+		self.data.push(MACRO_END);
+	}
+
+	/// Build the string, failing if any invalid text or arguments were appended.
+	pub fn build(self) -> Result<SeString> {
+		match self.error {
+			Some(error) => Err(error),
+			None => Ok(SeString::from(self.data)),
+		}
+	}
+
+	fn fail(&mut self, error: Error) {
+		self.error.get_or_insert(error);
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::sestring::{Error, Expression, MacroKind, SeString};
+
+	use super::SeStringBuilder;
+
+	#[test]
+	fn builds_payloads() {
+		let plural = SeStringBuilder::new().with_text("s").build().unwrap();
+		let string = SeStringBuilder::new()
+			.with_text("Hello ")
+			.with_macro(MacroKind::Color, [Expression::U32(0xF201F8)])
+			.with_text("world")
+			.with_macro(MacroKind::Color, [Expression::StackColor])
+			.with_macro(
+				MacroKind::If,
+				[
+					Expression::Gt(
+						Box::new(Expression::LocalNumber(Box::new(1.into()))),
+						Box::new(1.into()),
+					),
+					Expression::from(&*plural),
+					Expression::from(&*SeString::new()),
+				],
+			)
+			.with_macro(MacroKind::NewLine, [])
+			.build()
+			.unwrap();
+
+		let expected = SeString::from_macro_string(
+			"Hello <color(15860216)>world<color(stackcolor)><if([lnum1>1],s,)><br>",
+		)
+		.unwrap();
+		assert_eq!(string.as_bytes(), expected.as_bytes());
+	}
+
+	#[test]
+	fn rejects_macro_start_in_text() {
+		let result = SeStringBuilder::new().with_text("a\u{2}b").build();
+		assert_eq!(result.unwrap_err(), Error::InvalidText);
+	}
+}
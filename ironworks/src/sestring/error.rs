@@ -29,6 +29,11 @@ pub enum Error {
 	#[error("invalid argument data for macro")]
 	InvalidArgumentData,
 
+	/// Macro string text could not be parsed. Contains the byte offset of the
+	/// error within the text.
+	#[error("invalid macro string at offset {0}")]
+	InvalidMacroString(usize),
+
 	/// An error occurred during formatting.
 	#[error("formatting error")]
 	FormatError(#[from] std::fmt::Error),
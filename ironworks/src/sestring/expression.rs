@@ -42,6 +42,8 @@ pub enum Expression<'a> {
 	Unknown(u8),
 }
 
+pub(super) const INLINE_STRING: u8 = 0xFF;
+
 impl<'a> Expression<'a> {
 	pub(super) fn read(cursor: &mut SliceCursor<'a>) -> Result<Self> {
 		let kind = cursor.next()?;
@@ -76,7 +78,7 @@ impl<'a> Expression<'a> {
 
 			kind @ 0xF0..=0xFE => Self::U32(read_packed_u32(cursor, kind)?),
 
-			0xFF => Self::SeString(read_inline_sestring(cursor)?),
+			INLINE_STRING => Self::SeString(read_inline_sestring(cursor)?),
 
 			other => Self::Unknown(other),
 		};
@@ -84,6 +86,70 @@ impl<'a> Expression<'a> {
 		Ok(expression)
 	}
 
+	/// Encodes this expression as SeString data, appending it to `data`.
+	/// Integers are written using the shortest available encoding.
+	pub fn write(&self, data: &mut Vec<u8>) -> Result<()> {
+		let code = match self {
+			Self::U32(value) => {
+				write_u32(data, *value);
+				return Ok(());
+			}
+			Self::SeString(string) => {
+				let length = u32::try_from(string.len()).map_err(|_| Error::InvalidExpression)?;
+				data.push(INLINE_STRING);
+				write_u32(data, length);
+				data.extend_from_slice(string.as_bytes());
+				return Ok(());
+			}
+
+			Self::Millisecond => 0xD8,
+			Self::Second => 0xD9,
+			Self::Minute => 0xDA,
+			Self::Hour => 0xDB,
+			Self::Day => 0xDC,
+			Self::Weekday => 0xDD,
+			Self::Month => 0xDE,
+			Self::Year => 0xDF,
+
+			Self::Ge(_, _) => 0xE0,
+			Self::Gt(_, _) => 0xE1,
+			Self::Le(_, _) => 0xE2,
+			Self::Lt(_, _) => 0xE3,
+			Self::Eq(_, _) => 0xE4,
+			Self::Ne(_, _) => 0xE5,
+
+			Self::LocalNumber(_) => 0xE8,
+			Self::GlobalNumber(_) => 0xE9,
+			Self::LocalString(_) => 0xEA,
+			Self::GlobalString(_) => 0xEB,
+
+			Self::StackColor => 0xEC,
+
+			Self::Unknown(value) => *value,
+		};
+		data.push(code);
+
+		match self {
+			Self::LocalNumber(inner)
+			| Self::GlobalNumber(inner)
+			| Self::LocalString(inner)
+			| Self::GlobalString(inner) => inner.write(data)?,
+
+			Self::Ge(lhs, rhs)
+			| Self::Gt(lhs, rhs)
+			| Self::Le(lhs, rhs)
+			| Self::Lt(lhs, rhs)
+			| Self::Eq(lhs, rhs)
+			| Self::Ne(lhs, rhs) => {
+				lhs.write(data)?;
+				rhs.write(data)?;
+			}
+
+			_ => {}
+		}
+		Ok(())
+	}
+
 	pub fn name(&self) -> &'static str {
 		match self {
 			Self::Millisecond => "t_msec",
@@ -117,6 +183,37 @@ impl<'a> Expression<'a> {
 	}
 }
 
+impl From<u32> for Expression<'_> {
+	fn from(value: u32) -> Self {
+		Self::U32(value)
+	}
+}
+
+impl<'a> From<&'a SeStr> for Expression<'a> {
+	fn from(string: &'a SeStr) -> Self {
+		Self::SeString(string)
+	}
+}
+
+/// Write an integer expression, using the shortest available encoding.
+pub(super) fn write_u32(data: &mut Vec<u8>, value: u32) {
+	if value < 0xCF {
+		data.push(value as u8 + 1);
+		return;
+	}
+
+	let bytes = value.to_le_bytes();
+	let flags = (0..4)
+		.filter(|&index| bytes[index] != 0)
+		.fold(0u8, |flags, index| flags | (1 << index));
+	data.push(0xEF + flags);
+	for index in (0..4).rev() {
+		if flags & (1 << index) != 0 {
+			data.push(bytes[index]);
+		}
+	}
+}
+
 fn read_packed_u32(cursor: &mut SliceCursor, kind: u8) -> Result<u32> {
 	let flags = (kind + 1) & 0b1111;
 	let mut bytes = [0; 4];
@@ -197,7 +294,33 @@ mod test {
 		assert_matches!(two.as_ref(), Expression::U32(100));
 	}
 
-	fn read<'a>(bytes: &'a [u8]) -> Expression<'a> {
+	#[test]
+	fn write_round_trip() {
+		let inner: &SeStr = b"test".as_slice().into();
+		let expression = Expression::Ne(
+			Box::new(Expression::GlobalNumber(Box::new(Expression::U32(1)))),
+			Box::new(Expression::SeString(inner)),
+		);
+		let bytes = write(&expression);
+		assert_eq!(bytes, b"\xE5\xE9\x02\xFF\x05test");
+		assert_eq!(write(&read(&bytes)), bytes);
+	}
+
+	#[test]
+	fn write_packed() {
+		assert_eq!(write(&Expression::U32(0xCE)), [0xCF]);
+		assert_eq!(write(&Expression::U32(0xCF)), [0xF0, 0xCF]);
+		assert_eq!(write(&Expression::U32(0x0100_0001)), [0xF8, 0x01, 0x01]);
+		assert_matches!(read(&[0xF8, 0x01, 0x01]), Expression::U32(0x0100_0001));
+	}
+
+	fn write(expression: &Expression) -> Vec<u8> {
+		let mut data = vec![];
+		expression.write(&mut data).expect("write should not fail");
+		data
+	}
+
+	fn read(bytes: &[u8]) -> Expression<'_> {
 		let mut cursor = SliceCursor::new(bytes);
 		Expression::read(&mut cursor).expect("read should not fail")
 	}
@@ -0,0 +1,138 @@
+use std::fmt;
+
+use crate::sestring::error::{Error, Result};
+
+use super::{
+	style::{Color, ColorUsage, Style},
+	styled::{StyleState, TextStyle},
+	write::Write,
+};
+
+pub type AnsiString = AnsiWriter<String>;
+
+/// [`Write`] implementation producing text with ANSI escape sequences, for
+/// display in terminals supporting 24-bit color.
+///
+/// Foreground colors, bold, and italic styles are written. Terminals have no
+/// equivalent for outlines or drop shadows, so edge and shadow colors are
+/// ignored.
+#[derive(Debug)]
+pub struct AnsiWriter<T: fmt::Write> {
+	inner: T,
+	state: StyleState,
+	written: TextStyle,
+}
+
+impl<T: fmt::Write> AnsiWriter<T> {
+	/// Creates a new `AnsiWriter` with the given inner writer.
+	pub fn new(inner: T) -> Self {
+		Self {
+			inner,
+			state: StyleState::default(),
+			written: TextStyle::default(),
+		}
+	}
+
+	/// Consumes this writer, resetting any active styles and returning the inner
+	/// writer.
+	pub fn into_inner(mut self) -> Result<T> {
+		if self.written != TextStyle::default() {
+			self.write_raw("\x1b[0m")?;
+		}
+		Ok(self.inner)
+	}
+
+	fn sync_style(&mut self) -> Result<()> {
+		// Only the parts of the style that can be displayed are tracked.
+		let style = self.state.style();
+		let style = TextStyle {
+			bold: style.bold,
+			italic: style.italic,
+			foreground: style.foreground,
+			..TextStyle::default()
+		};
+		if style == self.written {
+			return Ok(());
+		}
+
+		let mut codes = vec!["0".to_string()];
+		if style.bold {
+			codes.push("1".into());
+		}
+		if style.italic {
+			codes.push("3".into());
+		}
+		if let Some(Color { r, g, b, .. }) = style.foreground {
+			codes.push(format!("38;2;{r};{g};{b}"));
+		}
+		self.write_raw(&format!("\x1b[{}m", codes.join(";")))?;
+		self.written = style;
+
+		Ok(())
+	}
+
+	fn write_raw(&mut self, str: &str) -> Result<()> {
+		self.inner.write_str(str).map_err(|_| Error::InvalidText)
+	}
+}
+
+impl<T: fmt::Write + Default> Default for AnsiWriter<T> {
+	fn default() -> Self {
+		Self::new(T::default())
+	}
+}
+
+impl<T: fmt::Write> Write for AnsiWriter<T> {
+	fn write_str(&mut self, str: &str) -> Result<()> {
+		if str.is_empty() {
+			return Ok(());
+		}
+		self.sync_style()?;
+		self.write_raw(str)
+	}
+
+	fn set_style(&mut self, style: Style, enabled: bool) -> Result<()> {
+		self.state.set_style(style, enabled);
+		Ok(())
+	}
+
+	fn push_color(&mut self, usage: ColorUsage, color: Color) -> Result<()> {
+		self.state.push_color(usage, color);
+		Ok(())
+	}
+
+	fn pop_color(&mut self, usage: ColorUsage) -> Result<()> {
+		self.state.pop_color(usage);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::sestring::{
+		SeString,
+		format::{Input, format},
+	};
+
+	use super::AnsiString;
+
+	#[test]
+	fn writes_escapes() {
+		let string = SeString::from_macro_string(
+			"a<color(4294901760)><edgecolor(4278190335)>b<bold(1)>c<color(stackcolor)>d<bold(0)>e",
+		)
+		.unwrap();
+		let mut writer = AnsiString::default();
+		format(&string, &Input::new(), &mut writer).unwrap();
+		assert_eq!(
+			writer.into_inner().unwrap(),
+			concat!(
+				"a",
+				"\x1b[0;38;2;255;0;0mb",
+				"\x1b[0;1;38;2;255;0;0mc",
+				"\x1b[0;1md",
+				"\x1b[0me",
+			)
+		);
+	}
+}
@@ -36,7 +36,7 @@ pub fn hyphen<'a>(arguments: impl Arguments<'a>, state: &mut State) -> Result<()
 
 // TODO: is there a better category for this?
 pub fn icon<'a>(arguments: impl Arguments<'a>, state: &mut State) -> Result<()> {
-	let _id = arguments.exhaustive::<u32>(state)?;
-	// TODO: how would this be output? something on the writer? we only have the ID here, and it'd need to be looked up in the fontdata, etc.
+	let id = arguments.exhaustive::<u32>(state)?;
+	state.writer.write_icon(id)?;
 	Ok(())
 }
@@ -1,20 +1,146 @@
+#[cfg(feature = "excel")]
+use std::fmt;
+
 use crate::sestring::error::Result;
+#[cfg(feature = "excel")]
+use crate::{
+	Resource,
+	excel::{Excel, Field, Language, RowOptions},
+};
 
 use super::{argument::Arguments, format::State, value::Value};
+#[cfg(feature = "excel")]
+use super::{expression::evaluate_expression, format::format_sestring, noun, style::ColorUsage};
+
+/// Source of Excel data for macros that read from sheets.
+///
+/// This is implemented for [`Excel`](crate::excel::Excel), and can be provided
+/// to formatting with [`Input::set_excel`](super::Input::set_excel).
+#[cfg(feature = "excel")]
+pub trait ExcelProvider: fmt::Debug {
+	/// Read the field at the specified column index of a (sub)row. If no
+	/// language is specified, the provider's default language should be used.
+	/// Returns `None` if the field could not be read.
+	fn field(
+		&self,
+		sheet: &str,
+		row_id: u32,
+		subrow_id: u16,
+		column: u32,
+		language: Option<Language>,
+	) -> Option<Field>;
+}
+
+#[cfg(feature = "excel")]
+impl<R: Resource> ExcelProvider for Excel<R> {
+	fn field(
+		&self,
+		sheet: &str,
+		row_id: u32,
+		subrow_id: u16,
+		column: u32,
+		language: Option<Language>,
+	) -> Option<Field> {
+		let options = language.map_or_else(RowOptions::new, RowOptions::from);
+		let row = self
+			.sheet(sheet)
+			.ok()?
+			.subrow_with_options(row_id, subrow_id, options)
+			.ok()?;
+		row.field(usize::try_from(column).ok()?).ok()
+	}
+}
+
+#[cfg(feature = "excel")]
+impl<T: ExcelProvider + ?Sized> ExcelProvider for std::sync::Arc<T> {
+	fn field(
+		&self,
+		sheet: &str,
+		row_id: u32,
+		subrow_id: u16,
+		column: u32,
+		language: Option<Language>,
+	) -> Option<Field> {
+		(**self).field(sheet, row_id, subrow_id, column, language)
+	}
+}
+
+/// UI theme used to select colors from the `UIColor` sheet, for the
+/// `colortype` and `edgecolortype` macros.
+#[cfg(feature = "excel")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorTheme {
+	/// Dark theme, the game's default.
+	#[default]
+	Dark,
+	/// Light theme.
+	Light,
+	/// Classic FF theme.
+	ClassicFF,
+	/// Clear blue theme.
+	ClearBlue,
+	/// Clear white theme.
+	ClearWhite,
+	/// Clear green theme.
+	ClearGreen,
+}
 
-// TODO: Implement these.
+#[cfg(feature = "excel")]
+impl ColorTheme {
+	/// Column of `UIColor` holding this theme's colors for the given usage. Each
+	/// theme has a single column, shared by foreground and edge colors.
+	pub(super) fn column(self, usage: ColorUsage) -> Option<u32> {
+		match usage {
+			ColorUsage::Foreground | ColorUsage::Edge => Some(self as u32),
+			ColorUsage::Shadow => None,
+		}
+	}
+}
 
+#[cfg_attr(not(feature = "excel"), allow(unused_variables))]
 pub fn sheet<'a>(mut arguments: impl Arguments<'a>, state: &mut State) -> Result<()> {
-	let (_sheet, _row, _column) = arguments.evaluate::<(String, u32, Option<u32>)>(state)?;
+	let (sheet, row, column) = arguments.evaluate::<(String, u32, Option<u32>)>(state)?;
+
+	let parameters = arguments.collect::<Result<Vec<_>>>()?;
+
+	#[cfg(feature = "excel")]
+	{
+		let parameters = parameters
+			.into_iter()
+			.map(|expression| evaluate_expression(expression, state))
+			.collect::<Result<Vec<_>>>()?;
 
-	let _parameters = arguments.collect::<Result<Vec<_>>>()?;
+		let field = state.input.field(&sheet, row, 0, column.unwrap_or(0), None);
+		write_field(field, &parameters, state)?;
+	}
 
 	Ok(())
 }
 
+#[cfg_attr(not(feature = "excel"), allow(unused_variables))]
 pub fn sheet_sub<'a>(arguments: impl Arguments<'a>, state: &mut State) -> Result<()> {
-	let (_sheet, _row, _subrow, _column, _secondary_sheet, _secondary_col) =
+	let (sheet, row, subrow, column, secondary_sheet, secondary_column) =
 		arguments.exhaustive::<(String, u32, u32, u32, String, u32)>(state)?;
+
+	// The field in the primary sheet is used as the row ID in the secondary sheet.
+	#[cfg(feature = "excel")]
+	{
+		let secondary_row = u16::try_from(subrow)
+			.ok()
+			.and_then(|subrow| state.input.field(&sheet, row, subrow, column, None))
+			.as_ref()
+			.and_then(field_number)
+			.and_then(|number| u32::try_from(number).ok());
+
+		if let Some(secondary_row) = secondary_row {
+			let field =
+				state
+					.input
+					.field(&secondary_sheet, secondary_row, 0, secondary_column, None);
+			write_field(field, &[], state)?;
+		}
+	}
+
 	Ok(())
 }
 
@@ -33,29 +159,552 @@ pub fn switch_platform<'a>(arguments: impl Arguments<'a>, state: &mut State) ->
 	Ok(())
 }
 
+/// Language of the noun macros, which resolve nouns using fixed language rules
+/// regardless of the language of the string being formatted.
+#[derive(Debug, Clone, Copy)]
+pub enum NounLanguage {
+	Japanese,
+	English,
+	German,
+	French,
+	Chinese,
+}
+
 pub fn ja_noun<'a>(arguments: impl Arguments<'a>, state: &mut State) -> Result<()> {
-	noun("ja", arguments, state)
+	noun(NounLanguage::Japanese, arguments, state)
 }
 
 pub fn en_noun<'a>(arguments: impl Arguments<'a>, state: &mut State) -> Result<()> {
-	noun("en", arguments, state)
+	noun(NounLanguage::English, arguments, state)
 }
 
 pub fn de_noun<'a>(arguments: impl Arguments<'a>, state: &mut State) -> Result<()> {
-	noun("de", arguments, state)
+	noun(NounLanguage::German, arguments, state)
 }
 
 pub fn fr_noun<'a>(arguments: impl Arguments<'a>, state: &mut State) -> Result<()> {
-	noun("fr", arguments, state)
+	noun(NounLanguage::French, arguments, state)
 }
 
 pub fn ch_noun<'a>(arguments: impl Arguments<'a>, state: &mut State) -> Result<()> {
-	noun("ch", arguments, state)
+	noun(NounLanguage::Chinese, arguments, state)
 }
 
-// TODO: this should use an excel language
-fn noun<'a>(_lang: &str, arguments: impl Arguments<'a>, state: &mut State) -> Result<()> {
-	let (_sheet, _person, _row, _amount, _case, _unknown) =
+#[cfg_attr(not(feature = "excel"), allow(unused_variables))]
+fn noun<'a>(
+	language: NounLanguage,
+	arguments: impl Arguments<'a>,
+	state: &mut State,
+) -> Result<()> {
+	let (sheet, article_type, row, quantity, case, _unknown) =
 		arguments.exhaustive::<(String, u32, u32, u32, Value, Option<Value>)>(state)?;
+
+	#[cfg(feature = "excel")]
+	{
+		// Cases are 1-indexed, with the nominative case used when omitted.
+		let case = match case {
+			Value::Unknown => 0,
+			other => u32::from(other).saturating_sub(1),
+		};
+
+		let noun = noun::Noun {
+			sheet: &sheet,
+			row_id: row,
+			article_type,
+			quantity,
+			case,
+		};
+		let text = noun::resolve(language, &noun, state)?;
+		state.writer.write_str(&text)?;
+	}
+
 	Ok(())
 }
+
+#[cfg(feature = "excel")]
+fn write_field(field: Option<Field>, parameters: &[Value], state: &mut State) -> Result<()> {
+	let Some(field) = field else {
+		return Ok(());
+	};
+
+	match field {
+		// Strings read from sheets are formatted with any remaining macro
+		// arguments as their local parameters.
+		Field::String(string) => {
+			let mut state = State {
+				input: state.input,
+				writer: &mut *state.writer,
+				time: state.time,
+				local_parameters: Some(parameters),
+			};
+			format_sestring(&string, &mut state)
+		}
+		Field::F32(value) => state.writer.write_str(&value.to_string()),
+		other => match field_number(&other) {
+			Some(number) => state.writer.write_str(&number.to_string()),
+			None => Ok(()),
+		},
+	}
+}
+
+#[cfg(feature = "excel")]
+pub fn field_number(field: &Field) -> Option<i64> {
+	let number = match *field {
+		Field::Bool(value) => value.into(),
+		Field::I8(value) => value.into(),
+		Field::I16(value) => value.into(),
+		Field::I32(value) => value.into(),
+		Field::I64(value) => value,
+		Field::U8(value) => value.into(),
+		Field::U16(value) => value.into(),
+		Field::U32(value) => value.into(),
+		Field::U64(value) => i64::try_from(value).ok()?,
+		Field::String(_) | Field::F32(_) => return None,
+	};
+	Some(number)
+}
+
+#[cfg(all(test, feature = "excel"))]
+mod test {
+	use crate::{
+		Ironworks,
+		excel::{
+			Excel, Field, Language, SheetWriter,
+			fixture::{Files, string},
+		},
+		file::exh::{ColumnDefinition, ColumnKind, SheetKind},
+		sestring::{
+			SeString,
+			format::{
+				Color, ColorTheme, ColorUsage, Input, PlainString, RunWriter, StyledRun, format,
+			},
+		},
+	};
+
+	const ATTRIBUTIVE_COLUMNS: usize = 40;
+
+	fn writer(name: &str, kinds: &[ColumnKind]) -> SheetWriter {
+		let mut offset = 0;
+		let columns = kinds
+			.iter()
+			.map(|kind| {
+				let column = ColumnDefinition::new(*kind, offset);
+				offset += 4;
+				column
+			})
+			.collect();
+		SheetWriter::new(name, SheetKind::Default, columns)
+	}
+
+	/// A row of a noun sheet such as `Item`. `flags` are the adjective,
+	/// possessive pronoun, starts with vowel, plurality, pronoun, and article
+	/// columns, in that order.
+	fn noun(singular: &str, plural: &str, flags: [i8; 6]) -> Vec<Field> {
+		let [adjective, possessive, vowel, plurality, pronoun, article] = flags.map(Field::I8);
+		vec![
+			string(singular),
+			adjective,
+			string(plural),
+			possessive,
+			vowel,
+			plurality,
+			pronoun,
+			article,
+		]
+	}
+
+	/// A row of `Attributive`, with the given text at each column and empty
+	/// strings elsewhere.
+	fn attributive(columns: &[(usize, &str)]) -> Vec<Field> {
+		let mut fields = (0..ATTRIBUTIVE_COLUMNS)
+			.map(|_| string(""))
+			.collect::<Vec<_>>();
+		for &(column, text) in columns {
+			fields[column] = string(text);
+		}
+		fields
+	}
+
+	fn input() -> Input {
+		let files = Files::default();
+
+		let mut addon = writer("Addon", &[ColumnKind::String, ColumnKind::UInt32]);
+		let text = SeString::from_macro_string("You have <num(lnum1)> items.").unwrap();
+		addon
+			.set_row(
+				Language::None,
+				1,
+				0,
+				vec![Field::String(text), Field::U32(2)],
+			)
+			.unwrap();
+		files.insert(&addon);
+
+		let mut quest = writer("Quest", &[ColumnKind::String]);
+		quest
+			.set_row(Language::None, 2, 0, vec![string("Close to Home")])
+			.unwrap();
+		files.insert(&quest);
+
+		use ColumnKind::{Int8 as I, String as S};
+		let mut item = writer("Item", &[S, I, S, I, I, I, I, I]);
+		let nouns = [
+			(
+				Language::English,
+				vec![
+					(1, noun("potion", "potions", [0; 6])),
+					(2, noun("ether", "ethers", [0, 0, 1, 0, 0, 0])),
+					(
+						3,
+						noun("Eternity Ring", "Eternity Rings", [0, 0, 0, 0, 0, 1]),
+					),
+				],
+			),
+			// Masculine, feminine, and neuter nouns. An adjective flag of 1 declines
+			// the noun through `Attributive` row 27, while row 30 is left empty.
+			(
+				Language::German,
+				vec![
+					(1, noun("Trank", "Tränke", [1, 1, 0, 0, 0, 0])),
+					(2, noun("Phiole", "Phiolen", [4, 4, 0, 0, 1, 0])),
+					(3, noun("Elixier", "Elixiere", [1, 1, 0, 0, 2, 0])),
+					(
+						4,
+						noun("Blau[a] Trank", "Blau[a] Tränke", [1, 1, 0, 0, 0, 0]),
+					),
+					(5, noun("Excalibur", "", [4, 4, 0, 0, 2, 1])),
+				],
+			),
+			// Feminine, masculine starting with a vowel, uncountable, and always
+			// plural nouns.
+			(
+				Language::French,
+				vec![
+					(1, noun("potion", "potions", [0, 0, 0, 1, 1, 0])),
+					(2, noun("éther", "éthers", [0, 0, 1, 1, 0, 0])),
+					(3, noun("sable", "", [0; 6])),
+					(4, noun("ciseaux", "ciseaux", [0, 0, 0, 2, 0, 0])),
+					(5, noun("Excalibur", "", [0, 0, 0, 0, 0, 1])),
+				],
+			),
+			(
+				Language::Japanese,
+				vec![(1, noun("ポーション", "", [0; 6]))],
+			),
+		];
+		for (language, rows) in nouns {
+			for (row_id, fields) in rows {
+				item.set_row(language, row_id, 0, fields).unwrap();
+			}
+		}
+		files.insert(&item);
+
+		let mut sheet = writer("Attributive", &[S; ATTRIBUTIVE_COLUMNS]);
+		let rows = [
+			// English indefinite and definite articles.
+			(Language::English, 1, attributive(&[(2, "a "), (5, "an ")])),
+			(
+				Language::English,
+				2,
+				attributive(&[(2, "the "), (4, "the "), (5, "the "), (7, "the ")]),
+			),
+			// German articles for each case, with the gender in the low bits.
+			(
+				Language::German,
+				1,
+				attributive(&[
+					(8, "ein "),
+					(9, "eine "),
+					(10, "ein "),
+					(12, "eines "),
+					(13, "einer "),
+					(14, "eines "),
+					(16, "einem "),
+					(17, "einer "),
+					(18, "einem "),
+					(20, "einen "),
+					(21, "eine "),
+					(22, "ein "),
+				]),
+			),
+			(
+				Language::German,
+				2,
+				attributive(&[
+					(8, "der "),
+					(9, "die "),
+					(10, "das "),
+					(11, "die "),
+					(12, "des "),
+					(13, "der "),
+					(14, "des "),
+					(15, "der "),
+					(16, "dem "),
+					(17, "der "),
+					(18, "dem "),
+					(19, "den "),
+					(20, "den "),
+					(21, "die "),
+					(22, "das "),
+					(23, "die "),
+				]),
+			),
+			// Adjective endings following an indefinite article.
+			(
+				Language::German,
+				26,
+				attributive(&[(8, "er"), (9, "e"), (10, "es"), (20, "en")]),
+			),
+			// Noun declension.
+			(
+				Language::German,
+				27,
+				attributive(&[(12, "s"), (14, "s"), (19, "n")]),
+			),
+			// French articles, in groups of four for masculine, masculine before
+			// a vowel, feminine, and feminine before a vowel.
+			(
+				Language::French,
+				1,
+				attributive(&[
+					(25, "un "),
+					(26, "des "),
+					(27, "du "),
+					(29, "un "),
+					(30, "des "),
+					(31, "de l'"),
+					(33, "une "),
+					(34, "des "),
+					(35, "de la "),
+					(37, "une "),
+					(38, "des "),
+					(39, "de l'"),
+				]),
+			),
+			(
+				Language::French,
+				2,
+				attributive(&[
+					(25, "le "),
+					(26, "les "),
+					(27, "le "),
+					(29, "l'"),
+					(30, "les "),
+					(31, "l'"),
+					(33, "la "),
+					(34, "les "),
+					(35, "la "),
+					(37, "l'"),
+					(38, "les "),
+					(39, "l'"),
+				]),
+			),
+			// Japanese demonstratives and counters, singular and plural.
+			(
+				Language::Japanese,
+				3,
+				attributive(&[(0, "この"), (1, "これらの")]),
+			),
+			(Language::Japanese, 4, attributive(&[(1, "[n]個の")])),
+		];
+		for (language, row_id, fields) in rows {
+			sheet.set_row(language, row_id, 0, fields).unwrap();
+		}
+		files.insert(&sheet);
+
+		// One column per theme: dark, light, classic FF, clear blue, clear white,
+		// and clear green.
+		let mut ui_color = writer("UIColor", &[ColumnKind::UInt32; 6]);
+		let rows = [
+			(
+				500,
+				[
+					0xFF0000FF, 0x0000FFFF, 0x00FF00FF, 0x00FFFFFF, 0xFFFFFFFF, 0x008000FF,
+				],
+			),
+			(
+				501,
+				[
+					0x000000FF, 0xFFFFFFFF, 0x808080FF, 0x000080FF, 0x404040FF, 0x004000FF,
+				],
+			),
+		];
+		for (row_id, colors) in rows {
+			let fields = colors.into_iter().map(Field::U32).collect();
+			ui_color.set_row(Language::None, row_id, 0, fields).unwrap();
+		}
+		files.insert(&ui_color);
+
+		let excel = Excel::new(Ironworks::new().with_resource(files));
+		Input::new().with_excel(excel)
+	}
+
+	fn resolve(input: &Input, macro_string: &str) -> String {
+		let string = SeString::from_macro_string(macro_string).unwrap();
+		let mut writer = PlainString::default();
+		format(&string, input, &mut writer).unwrap();
+		writer.into_inner()
+	}
+
+	#[test]
+	fn sheet() {
+		let input = input();
+		assert_eq!(resolve(&input, "<sheet(Addon,1,0,5)>"), "You have 5 items.");
+		assert_eq!(resolve(&input, "<sheet(Addon,1,1)>"), "2");
+		assert_eq!(resolve(&input, "<sheet(Addon,9,0)>"), "");
+		assert_eq!(resolve(&Input::new(), "<sheet(Addon,1,0,5)>"), "");
+	}
+
+	#[test]
+	fn sheet_sub() {
+		let input = input();
+		assert_eq!(
+			resolve(&input, "<sheetsub(Addon,1,0,1,Quest,0)>"),
+			"Close to Home"
+		);
+	}
+
+	#[test]
+	fn en_noun() {
+		let input = input();
+		let cases = [
+			("<ennoun(Item,1,1,1,1)>", "a potion"),
+			("<ennoun(Item,1,2,1,1)>", "an ether"),
+			("<ennoun(Item,2,1,3,1)>", "the potions"),
+			("<ennoun(Item,1,1,3,1)>", "potions"),
+			("<ennoun(Item,2,3,1,1)>", "Eternity Ring"),
+		];
+		for (macro_string, expected) in cases {
+			assert_eq!(resolve(&input, macro_string), expected, "{macro_string}");
+		}
+	}
+
+	#[test]
+	fn de_noun() {
+		let input = input();
+		let cases = [
+			// Each gender, in each case.
+			("<denoun(Item,2,1,1,1)>", "der Trank"),
+			("<denoun(Item,2,1,1,2)>", "des Tranks"),
+			("<denoun(Item,2,1,1,3)>", "dem Trank"),
+			("<denoun(Item,2,1,1,4)>", "den Trank"),
+			("<denoun(Item,1,2,1,1)>", "eine Phiole"),
+			("<denoun(Item,1,2,1,2)>", "einer Phiole"),
+			("<denoun(Item,2,2,1,4)>", "die Phiole"),
+			("<denoun(Item,2,3,1,1)>", "das Elixier"),
+			("<denoun(Item,2,3,1,2)>", "des Elixiers"),
+			("<denoun(Item,1,3,1,3)>", "einem Elixier"),
+			// Plurals share a column across genders.
+			("<denoun(Item,2,1,2,1)>", "die Tränke"),
+			("<denoun(Item,2,2,3,3)>", "den Phiolen"),
+			("<denoun(Item,2,1,2,3)>", "den Tränken"),
+			("<denoun(Item,1,1,3,1)>", "Tränke"),
+			// Adjectives are declined to match the article.
+			("<denoun(Item,1,4,1,1)>", "ein Blauer Trank"),
+			("<denoun(Item,1,4,1,4)>", "einen Blauen Trank"),
+			("<denoun(Item,2,5,1,1)>", "Excalibur"),
+		];
+		for (macro_string, expected) in cases {
+			assert_eq!(resolve(&input, macro_string), expected, "{macro_string}");
+		}
+	}
+
+	#[test]
+	fn fr_noun() {
+		let input = input();
+		let cases = [
+			("<frnoun(Item,1,1,1,1)>", "une potion"),
+			("<frnoun(Item,2,1,1,1)>", "la potion"),
+			("<frnoun(Item,2,1,3,1)>", "les potions"),
+			("<frnoun(Item,1,1,2,1)>", "des potions"),
+			("<frnoun(Item,1,2,1,1)>", "un éther"),
+			("<frnoun(Item,2,2,1,1)>", "l'éther"),
+			("<frnoun(Item,2,2,2,1)>", "les éthers"),
+			// Uncountable nouns ignore the quantity.
+			("<frnoun(Item,1,3,1,1)>", "du sable"),
+			("<frnoun(Item,1,3,5,1)>", "du sable"),
+			// Some nouns are always plural.
+			("<frnoun(Item,2,4,1,1)>", "les ciseaux"),
+			("<frnoun(Item,2,5,1,1)>", "Excalibur"),
+		];
+		for (macro_string, expected) in cases {
+			assert_eq!(resolve(&input, macro_string), expected, "{macro_string}");
+		}
+	}
+
+	#[test]
+	fn ja_noun() {
+		let input = input();
+		let cases = [
+			("<janoun(Item,1,1,1,1)>", "ポーション"),
+			("<janoun(Item,3,1,1,1)>", "このポーション"),
+			("<janoun(Item,3,1,2,1)>", "これらのポーション"),
+			("<janoun(Item,4,1,1,1)>", "ポーション"),
+			("<janoun(Item,4,1,3,1)>", "3個のポーション"),
+		];
+		for (macro_string, expected) in cases {
+			assert_eq!(resolve(&input, macro_string), expected, "{macro_string}");
+		}
+	}
+
+	#[test]
+	fn ui_color() {
+		let style = |input: &Input| {
+			let string =
+				SeString::from_macro_string("<colortype(500)><edgecolortype(501)>a").unwrap();
+			let mut writer = RunWriter::new();
+			format(&string, input, &mut writer).unwrap();
+			match writer.into_runs().as_slice() {
+				[StyledRun::Text { style, .. }] => (style.foreground.unwrap(), style.edge.unwrap()),
+				other => panic!("unexpected runs {other:?}"),
+			}
+		};
+		let color = |r, g, b, a| Color { r, g, b, a };
+
+		// Foreground and edge colors are both read from the theme's own column.
+		let themes = [
+			(ColorTheme::Dark, color(255, 0, 0, 255), color(0, 0, 0, 255)),
+			(
+				ColorTheme::Light,
+				color(0, 0, 255, 255),
+				color(255, 255, 255, 255),
+			),
+			(
+				ColorTheme::ClassicFF,
+				color(0, 255, 0, 255),
+				color(128, 128, 128, 255),
+			),
+			(
+				ColorTheme::ClearBlue,
+				color(0, 255, 255, 255),
+				color(0, 0, 128, 255),
+			),
+			(
+				ColorTheme::ClearWhite,
+				color(255, 255, 255, 255),
+				color(64, 64, 64, 255),
+			),
+			(
+				ColorTheme::ClearGreen,
+				color(0, 128, 0, 255),
+				color(0, 64, 0, 255),
+			),
+		];
+		for (theme, foreground, edge) in themes {
+			assert_eq!(
+				style(&input().with_color_theme(theme)),
+				(foreground, edge),
+				"{theme:?}"
+			);
+		}
+		assert_eq!(
+			style(&input()),
+			style(&input().with_color_theme(ColorTheme::Dark))
+		);
+
+		assert_eq!(
+			style(&input().with_color(ColorUsage::Edge, 501, color(1, 2, 3, 4))),
+			(color(255, 0, 0, 255), color(1, 2, 3, 4))
+		);
+	}
+}
@@ -31,9 +31,9 @@ pub fn evaluate_expression(expression: Expression, state: &State) -> Result<Valu
 
 		Expression::StackColor => Value::Unknown,
 
-		Expression::LocalNumber(inner) => u32(input.local_parameter(eval(inner)?.into())),
+		Expression::LocalNumber(inner) => u32(state.local_parameter(eval(inner)?.into())),
 		Expression::GlobalNumber(inner) => u32(input.global_parameter(eval(inner)?.into())),
-		Expression::LocalString(inner) => str(input.local_parameter(eval(inner)?.into())),
+		Expression::LocalString(inner) => str(state.local_parameter(eval(inner)?.into())),
 		Expression::GlobalString(inner) => str(input.global_parameter(eval(inner)?.into())),
 
 		Expression::Ge(left, right) => cmp(u32::ge, eval(left)?, eval(right)?),
@@ -49,12 +49,13 @@ pub fn evaluate_expression(expression: Expression, state: &State) -> Result<Valu
 	Ok(value)
 }
 
-fn evaluate_sestring(sestring: &SeStr, state: &State) -> Result<Value> {
+pub fn evaluate_sestring(sestring: &SeStr, state: &State) -> Result<Value> {
 	let mut writer = EvaluationWriter(String::new());
 	let mut state = State {
 		input: state.input,
 		writer: &mut writer,
 		time: state.time,
+		local_parameters: state.local_parameters,
 	};
 	format_sestring(sestring, &mut state)?;
 	Ok(Value::String(writer.0))
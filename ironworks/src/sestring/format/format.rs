@@ -8,7 +8,7 @@ use crate::sestring::{
 
 use super::{
 	argument::Arguments, character, control_flow, excel, expression::evaluate_expression,
-	input::Input, number, runtime, style, text, time, write::Write,
+	input::Input, number, runtime, style, text, time, value::Value, write::Write,
 };
 
 #[allow(missing_debug_implementations)]
@@ -17,6 +17,23 @@ pub struct State<'a> {
 	pub writer: &'a mut dyn Write,
 
 	pub time: u32,
+
+	/// Local parameters overriding those of the input, numbered from 1. Used when
+	/// formatting strings read by macros such as `sheet`.
+	pub local_parameters: Option<&'a [Value]>,
+}
+
+impl State<'_> {
+	pub fn local_parameter(&self, id: u32) -> Value {
+		match self.local_parameters {
+			Some(parameters) => id
+				.checked_sub(1)
+				.and_then(|index| parameters.get(usize::try_from(index).ok()?))
+				.cloned()
+				.unwrap_or(Value::Unknown),
+			None => self.input.local_parameter(id),
+		}
+	}
 }
 
 /// Attempts to format an [`SeString`] using data from an [`Input`] into an a
@@ -30,6 +47,7 @@ pub fn format(sestring: &SeStr, input: &Input, writer: &mut impl Write) -> Resul
 		input,
 		writer,
 		time: time::FFXIV_EPOCH,
+		local_parameters: None,
 	};
 	format_sestring(sestring, &mut state)
 }
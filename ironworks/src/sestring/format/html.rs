@@ -0,0 +1,229 @@
+use std::fmt;
+
+use crate::sestring::error::{Error, Result};
+
+use super::{
+	style::{Color, ColorUsage, Style},
+	styled::{StyleState, TextStyle},
+	write::Write,
+};
+
+pub type HtmlString = HtmlWriter<String>;
+
+/// [`Write`] implementation producing HTML.
+///
+/// Text is escaped, and wrapped in `<span>` elements with inline CSS for any
+/// active styles and colors. Color alpha is ignored. Line breaks are written as
+/// `<br>`, rubies as `<ruby>` elements, and icons as `<img>` elements with the
+/// icon ID in a `data-icon` attribute, and a `src` if an icon source has been
+/// set with [`with_icon_source`](Self::with_icon_source).
+pub struct HtmlWriter<T: fmt::Write> {
+	inner: T,
+	state: StyleState,
+	open: Option<TextStyle>,
+	icon_source: Option<Box<dyn Fn(u32) -> String>>,
+}
+
+impl<T: fmt::Write> HtmlWriter<T> {
+	/// Creates a new `HtmlWriter` with the given inner writer.
+	pub fn new(inner: T) -> Self {
+		Self {
+			inner,
+			state: StyleState::default(),
+			open: None,
+			icon_source: None,
+		}
+	}
+
+	/// Sets a function used to build the `src` URL of icon images from their ID.
+	pub fn with_icon_source(mut self, source: impl Fn(u32) -> String + 'static) -> Self {
+		self.set_icon_source(source);
+		self
+	}
+
+	/// Sets a function used to build the `src` URL of icon images from their ID.
+	pub fn set_icon_source(&mut self, source: impl Fn(u32) -> String + 'static) {
+		self.icon_source = Some(Box::new(source));
+	}
+
+	/// Consumes this writer, closing any open elements and returning the inner
+	/// writer.
+	pub fn into_inner(mut self) -> Result<T> {
+		self.close()?;
+		Ok(self.inner)
+	}
+
+	fn sync_style(&mut self) -> Result<()> {
+		let style = self.state.style();
+		if self.open.as_ref().unwrap_or(&TextStyle::default()) == &style {
+			return Ok(());
+		}
+
+		self.close()?;
+		if style != TextStyle::default() {
+			self.write_raw(&format!("<span style=\"{}\">", css(&style)))?;
+			self.open = Some(style);
+		}
+
+		Ok(())
+	}
+
+	fn close(&mut self) -> Result<()> {
+		if self.open.take().is_some() {
+			self.write_raw("</span>")?;
+		}
+		Ok(())
+	}
+
+	fn write_raw(&mut self, str: &str) -> Result<()> {
+		self.inner.write_str(str).map_err(|_| Error::InvalidText)
+	}
+
+	fn write_escaped(&mut self, str: &str) -> Result<()> {
+		for char in str.chars() {
+			match char {
+				'&' => self.write_raw("&amp;")?,
+				'<' => self.write_raw("&lt;")?,
+				'>' => self.write_raw("&gt;")?,
+				'"' => self.write_raw("&quot;")?,
+				'\'' => self.write_raw("&#39;")?,
+				'\n' => self.write_raw("<br>")?,
+				other => self
+					.inner
+					.write_char(other)
+					.map_err(|_| Error::InvalidText)?,
+			}
+		}
+		Ok(())
+	}
+}
+
+impl<T: fmt::Write + Default> Default for HtmlWriter<T> {
+	fn default() -> Self {
+		Self::new(T::default())
+	}
+}
+
+impl<T: fmt::Write + fmt::Debug> fmt::Debug for HtmlWriter<T> {
+	fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		formatter
+			.debug_struct("HtmlWriter")
+			.field("inner", &self.inner)
+			.field("state", &self.state)
+			.finish_non_exhaustive()
+	}
+}
+
+impl<T: fmt::Write> Write for HtmlWriter<T> {
+	fn write_str(&mut self, str: &str) -> Result<()> {
+		if str.is_empty() {
+			return Ok(());
+		}
+		self.sync_style()?;
+		self.write_escaped(str)
+	}
+
+	fn set_style(&mut self, style: Style, enabled: bool) -> Result<()> {
+		self.state.set_style(style, enabled);
+		Ok(())
+	}
+
+	fn push_color(&mut self, usage: ColorUsage, color: Color) -> Result<()> {
+		self.state.push_color(usage, color);
+		Ok(())
+	}
+
+	fn pop_color(&mut self, usage: ColorUsage) -> Result<()> {
+		self.state.pop_color(usage);
+		Ok(())
+	}
+
+	fn write_ruby(&mut self, text: &str, ruby: &str) -> Result<()> {
+		self.sync_style()?;
+		self.write_raw("<ruby>")?;
+		self.write_escaped(text)?;
+		self.write_raw("<rt>")?;
+		self.write_escaped(ruby)?;
+		self.write_raw("</rt></ruby>")
+	}
+
+	fn write_icon(&mut self, id: u32) -> Result<()> {
+		self.sync_style()?;
+		self.write_raw(&format!("<img class=\"icon\" data-icon=\"{id}\""))?;
+		if let Some(source) = self.icon_source.as_ref().map(|source| source(id)) {
+			self.write_raw(" src=\"")?;
+			self.write_escaped(&source)?;
+			self.write_raw("\"")?;
+		}
+		self.write_raw(" alt=\"\">")
+	}
+}
+
+fn css(style: &TextStyle) -> String {
+	let mut css = vec![];
+	if let Some(color) = style.foreground {
+		css.push(format!("color:{};", hex(color)));
+	}
+	if style.bold {
+		css.push("font-weight:bold;".into());
+	}
+	if style.italic {
+		css.push("font-style:italic;".into());
+	}
+	if let Some(color) = style.edge {
+		css.push(format!("-webkit-text-stroke:1px {};", hex(color)));
+	}
+	if style.shadow {
+		let color = style.shadow_color.map_or_else(|| "#000000".into(), hex);
+		css.push(format!("text-shadow:1px 1px {color};"));
+	}
+	css.concat()
+}
+
+fn hex(color: Color) -> String {
+	format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+#[cfg(test)]
+mod test {
+	use crate::sestring::{
+		SeString,
+		format::{Input, format},
+	};
+
+	use super::HtmlString;
+
+	fn html(macro_string: &str, mut writer: HtmlString) -> String {
+		let string = SeString::from_macro_string(macro_string).unwrap();
+		format(&string, &Input::new(), &mut writer).unwrap();
+		writer.into_inner().unwrap()
+	}
+
+	#[test]
+	fn writes_spans() {
+		assert_eq!(
+			html(
+				"Tom & \"Jerry\"<color(4294901760)>red<italic(1)>both<color(stackcolor)><br>italic",
+				HtmlString::default()
+			),
+			concat!(
+				"Tom &amp; &quot;Jerry&quot;",
+				"<span style=\"color:#ff0000;\">red</span>",
+				"<span style=\"color:#ff0000;font-style:italic;\">both</span>",
+				"<span style=\"font-style:italic;\"><br>italic</span>",
+			)
+		);
+	}
+
+	#[test]
+	fn writes_ruby_and_icons() {
+		let writer = HtmlString::default().with_icon_source(|id| format!("/icons/{id}.png"));
+		assert_eq!(
+			html("<ruby(漢字,かんじ)><icon(5)>", writer),
+			concat!(
+				"<ruby>漢字<rt>かんじ</rt></ruby>",
+				"<img class=\"icon\" data-icon=\"5\" src=\"/icons/5.png\" alt=\"\">",
+			)
+		);
+	}
+}
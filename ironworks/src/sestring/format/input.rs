@@ -1,5 +1,10 @@
 use std::{borrow::Cow, collections::HashMap};
 
+#[cfg(feature = "excel")]
+use crate::excel::{Field, Language};
+
+#[cfg(feature = "excel")]
+use super::excel::{ColorTheme, ExcelProvider};
 use super::{
 	runtime::{Gender, Player},
 	style::{Color, ColorUsage},
@@ -21,6 +26,16 @@ pub struct Input {
 	local: HashMap<u32, Value>,
 	global: HashMap<u32, Value>,
 	colors: HashMap<u32, HashMap<ColorUsage, Color>>,
+	#[cfg(feature = "excel")]
+	excel: Option<Box<dyn ExcelProvider>>,
+	#[cfg(feature = "excel")]
+	color_theme: ColorTheme,
+}
+
+impl Default for Input {
+	fn default() -> Self {
+		Self::new()
+	}
 }
 
 impl Input {
@@ -32,6 +47,10 @@ impl Input {
 			local: HashMap::new(),
 			global: HashMap::new(),
 			colors: HashMap::new(),
+			#[cfg(feature = "excel")]
+			excel: None,
+			#[cfg(feature = "excel")]
+			color_theme: ColorTheme::default(),
 		}
 	}
 
@@ -66,11 +85,29 @@ impl Input {
 	/// Adds a color of the specified usage and id. These colors will be provided
 	/// to [`Write`](super::write::Write) implementations when strings call for
 	/// their usage. In-game, these values are typically retrieved from the
-	/// `UIColor` excel sheet.
+	/// `UIColor` excel sheet. Colors added here take precedence over those read
+	/// from Excel data.
 	pub fn add_color(&mut self, usage: ColorUsage, id: u32, color: Color) {
 		self.colors.entry(id).or_default().insert(usage, color);
 	}
 
+	/// Sets the Excel data used by macros that read from sheets, such as `sheet`
+	/// and the noun macros. Without Excel data, these macros will not output any
+	/// text. Colors not added with [`add_color`](Self::add_color) are also read
+	/// from the `UIColor` sheet.
+	#[cfg(feature = "excel")]
+	pub fn set_excel(&mut self, excel: impl ExcelProvider + 'static) {
+		self.excel = Some(Box::new(excel));
+	}
+
+	/// Sets the theme used to read colors from the `UIColor` sheet of the Excel
+	/// data set with [`set_excel`](Self::set_excel). Defaults to
+	/// [`ColorTheme::Dark`].
+	#[cfg(feature = "excel")]
+	pub fn set_color_theme(&mut self, theme: ColorTheme) {
+		self.color_theme = theme;
+	}
+
 	/// Builder-style variant of [`add_player`](Self::add_player).
 	#[must_use]
 	pub fn with_player(mut self, id: u32, player: Player) -> Self {
@@ -106,6 +143,22 @@ impl Input {
 		self
 	}
 
+	/// Builder-style variant of [`set_excel`](Self::set_excel).
+	#[cfg(feature = "excel")]
+	#[must_use]
+	pub fn with_excel(mut self, excel: impl ExcelProvider + 'static) -> Self {
+		self.set_excel(excel);
+		self
+	}
+
+	/// Builder-style variant of [`set_color_theme`](Self::set_color_theme).
+	#[cfg(feature = "excel")]
+	#[must_use]
+	pub fn with_color_theme(mut self, theme: ColorTheme) -> Self {
+		self.set_color_theme(theme);
+		self
+	}
+
 	// NOTE: marking these as pub(super) for now because I get the sense they'll be moved into a trait.
 
 	pub(super) fn player(&'_ self, id: u32) -> Cow<'_, Player> {
@@ -130,20 +183,51 @@ impl Input {
 		self.global.get(&id).cloned().unwrap_or(Value::Unknown)
 	}
 
+	#[cfg(feature = "excel")]
+	pub(super) fn field(
+		&self,
+		sheet: &str,
+		row_id: u32,
+		subrow_id: u16,
+		column: u32,
+		language: Option<Language>,
+	) -> Option<Field> {
+		self.excel
+			.as_ref()?
+			.field(sheet, row_id, subrow_id, column, language)
+	}
+
 	pub(super) fn color(&self, usage: ColorUsage, id: u32) -> Color {
-		self.colors
+		let color = self
+			.colors
 			.get(&id)
 			.and_then(|usages| usages.get(&usage))
-			.copied()
-			.unwrap_or(
-				// magenta as a fallback
-				Color {
-					r: 255,
-					g: 0,
-					b: 255,
-					a: 255,
-				},
-			)
+			.copied();
+
+		#[cfg(feature = "excel")]
+		let color = color.or_else(|| self.sheet_color(usage, id));
+
+		color.unwrap_or(
+			// magenta as a fallback
+			Color {
+				r: 255,
+				g: 0,
+				b: 255,
+				a: 255,
+			},
+		)
+	}
+
+	// UIColor values are stored as RGBA.
+	#[cfg(feature = "excel")]
+	fn sheet_color(&self, usage: ColorUsage, id: u32) -> Option<Color> {
+		let column = self.color_theme.column(usage)?;
+		let value = self
+			.field("UIColor", id, 0, column, None)?
+			.into_u32()
+			.ok()?;
+		let [r, g, b, a] = value.to_be_bytes();
+		Some(Color { r, g, b, a })
 	}
 }
 
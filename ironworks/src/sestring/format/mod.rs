@@ -1,15 +1,20 @@
 //! Utilities for formatting and writing `SeString`s.
 
+mod ansi;
 mod argument;
 mod character;
 mod control_flow;
 mod excel;
 mod expression;
 mod format;
+mod html;
 mod input;
+#[cfg(feature = "excel")]
+mod noun;
 mod number;
 mod runtime;
 mod style;
+mod styled;
 mod text;
 mod time;
 mod value;
@@ -18,11 +23,16 @@ mod write;
 #[cfg(test)]
 mod test;
 
+#[cfg(feature = "excel")]
+pub use excel::{ColorTheme, ExcelProvider};
 pub use {
+	ansi::{AnsiString, AnsiWriter},
 	format::format,
+	html::{HtmlString, HtmlWriter},
 	input::Input,
 	runtime::{Gender, Player},
 	style::{Color, ColorUsage, Style},
+	styled::{RunWriter, StyledRun, TextStyle},
 	value::Value,
 	write::{PlainString, PlainWriter, Write},
 };
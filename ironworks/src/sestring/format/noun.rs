@@ -0,0 +1,209 @@
+//! Noun resolution for the `*noun` macros, following the client's per-language
+//! rules for articles, plurals, and grammatical case.
+
+use crate::{
+	excel::{Field, Language},
+	sestring::error::Result,
+};
+
+use super::{
+	excel::{NounLanguage, field_number},
+	expression::evaluate_sestring,
+	format::State,
+};
+
+// Column offsets within noun sheets such as `Item` and `BNpcName`.
+const SINGULAR: i64 = 0;
+const ADJECTIVE: i64 = 1;
+const PLURAL: i64 = 2;
+const POSSESSIVE_PRONOUN: i64 = 3;
+const STARTS_WITH_VOWEL: i64 = 4;
+const UNKNOWN_5: i64 = 5;
+const PRONOUN: i64 = 6;
+const ARTICLE: i64 = 7;
+
+const ATTRIBUTIVE: &str = "Attributive";
+
+/// Arguments to a noun macro.
+#[derive(Debug)]
+pub struct Noun<'a> {
+	pub sheet: &'a str,
+	pub row_id: u32,
+	pub article_type: u32,
+	pub quantity: u32,
+	/// 0-indexed grammatical case.
+	pub case: u32,
+}
+
+pub fn resolve(language: NounLanguage, noun: &Noun, state: &State) -> Result<String> {
+	let excel_language = match language {
+		NounLanguage::Japanese => Language::Japanese,
+		NounLanguage::English => Language::English,
+		NounLanguage::German => Language::German,
+		NounLanguage::French => Language::French,
+		NounLanguage::Chinese => Language::ChineseSimplified,
+	};
+
+	let context = Context {
+		noun,
+		language: excel_language,
+		state,
+	};
+
+	let text = match language {
+		// Chinese has no articles or plurals, and follows the Japanese rules.
+		NounLanguage::Japanese | NounLanguage::Chinese => context.japanese()?,
+		NounLanguage::English => context.english()?,
+		NounLanguage::German => context.german()?,
+		NounLanguage::French => context.french()?,
+	};
+
+	Ok(text.replace("[n]", &noun.quantity.to_string()))
+}
+
+struct Context<'a, 'b> {
+	noun: &'a Noun<'a>,
+	language: Language,
+	state: &'a State<'b>,
+}
+
+impl Context<'_, '_> {
+	fn japanese(&self) -> Result<String> {
+		// Demonstrative prefix (ko-so-a-do), by quantity.
+		let column = match self.noun.quantity > 1 {
+			true => 1,
+			false => 0,
+		};
+		let mut text = self.attributive(self.noun.article_type.into(), column)?;
+		text.push_str(&self.string(SINGULAR)?);
+		Ok(text)
+	}
+
+	fn english(&self) -> Result<String> {
+		let number = self.number_column();
+		let mut text = String::new();
+
+		// Proper nouns are never given an article.
+		if self.int(ARTICLE) == 0 {
+			let starts_with_vowel = self.int(STARTS_WITH_VOWEL);
+			let column = starts_with_vowel + 2 * (starts_with_vowel + 1) + number;
+			text.push_str(&self.attributive(self.noun.article_type.into(), column)?);
+		}
+
+		text.push_str(&self.string(number)?);
+		Ok(text)
+	}
+
+	fn german(&self) -> Result<String> {
+		let singular = self.noun.quantity == 1;
+		let case_column = 4 * i64::from(self.noun.case) + 8;
+		let gender = match singular {
+			true => self.int(PRONOUN),
+			false => 3,
+		};
+		let column = case_column + gender;
+
+		let mut text = String::new();
+		let noun = self.string(self.number_column())?;
+		if !noun.is_empty() {
+			let has_article = noun.contains("[t]");
+			if self.int(ARTICLE) == 0 && !has_article {
+				text.push_str(&self.attributive(self.noun.article_type.into(), column)?);
+			}
+			text.push_str(&noun);
+
+			// Adjective declension suffix, either inline or trailing the noun.
+			let declension_row = match singular {
+				true => self.int(ADJECTIVE),
+				false => self.int(POSSESSIVE_PRONOUN),
+			} + 26;
+			let declension = self.attributive(declension_row, column)?;
+			match text.contains("[p]") {
+				true => text = text.replace("[p]", &declension),
+				false => text.push_str(&declension),
+			}
+
+			if has_article {
+				let article = self.attributive(39, column)?;
+				text = text.replace("[t]", &article);
+			}
+		}
+
+		let possessive = self.attributive(24, column)?;
+		text = text.replace("[pa]", &possessive);
+
+		let adjective_row = match self.noun.article_type {
+			// Possessive, demonstrative.
+			3 | 6 => 25,
+			// Zero article.
+			5 => 38,
+			// Definite.
+			2 => 37,
+			// Indefinite, negative, and anything else.
+			_ => 26,
+		};
+		let adjective = self.attributive(adjective_row, column)?;
+		Ok(text.replace("[a]", &adjective))
+	}
+
+	fn french(&self) -> Result<String> {
+		let starts_with_vowel = self.int(STARTS_WITH_VOWEL);
+		let pronoun = self.int(PRONOUN);
+		let column = 4 * (starts_with_vowel + 6 + 2 * pronoun);
+
+		let mut text = String::new();
+		if self.int(ARTICLE) != 0 {
+			text.push_str(&self.attributive(self.noun.article_type.into(), column)?);
+			text.push_str(&self.string(self.number_column())?);
+			return Ok(text);
+		}
+
+		let plurality = self.int(UNKNOWN_5);
+		let (article_column, noun_column) =
+			match plurality != 0 && (self.noun.quantity > 1 || plurality == 2) {
+				true => (column + 2, PLURAL),
+				false if plurality != 0 => (column + 1, SINGULAR),
+				false => (column + 3, SINGULAR),
+			};
+		text.push_str(&self.attributive(self.noun.article_type.into(), article_column)?);
+		text.push_str(&self.string(noun_column)?);
+		Ok(text)
+	}
+
+	fn number_column(&self) -> i64 {
+		match self.noun.quantity == 1 {
+			true => SINGULAR,
+			false => PLURAL,
+		}
+	}
+
+	fn int(&self, column: i64) -> i64 {
+		self.field(self.noun.sheet, self.noun.row_id.into(), column)
+			.as_ref()
+			.and_then(field_number)
+			.unwrap_or(0)
+	}
+
+	fn string(&self, column: i64) -> Result<String> {
+		self.text(self.noun.sheet, self.noun.row_id.into(), column)
+	}
+
+	fn attributive(&self, row_id: i64, column: i64) -> Result<String> {
+		self.text(ATTRIBUTIVE, row_id, column)
+	}
+
+	fn text(&self, sheet: &str, row_id: i64, column: i64) -> Result<String> {
+		let Some(Field::String(string)) = self.field(sheet, row_id, column) else {
+			return Ok(String::new());
+		};
+		Ok(evaluate_sestring(&string, self.state)?.into())
+	}
+
+	fn field(&self, sheet: &str, row_id: i64, column: i64) -> Option<Field> {
+		let row_id = u32::try_from(row_id).ok()?;
+		let column = u32::try_from(column).ok()?;
+		self.state
+			.input
+			.field(sheet, row_id, 0, column, Some(self.language))
+	}
+}
@@ -27,7 +27,7 @@ pub enum ColorUsage {
 
 /// RGBA color used in string formatting.
 #[allow(missing_docs)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Color {
 	pub r: u8,
 	pub g: u8,
@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+use crate::sestring::error::Result;
+
+use super::{
+	style::{Color, ColorUsage, Style},
+	write::Write,
+};
+
+/// Styling in effect for a run of text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextStyle {
+	/// Whether text is bold.
+	pub bold: bool,
+
+	/// Whether text is italic.
+	pub italic: bool,
+
+	/// Whether the outline style is enabled. An outline should still be drawn
+	/// when disabled if an [`edge`](Self::edge) color is set.
+	pub outline: bool,
+
+	/// Whether the drop shadow style is enabled.
+	pub shadow: bool,
+
+	/// Top-most [`ColorUsage::Foreground`] color, if any.
+	pub foreground: Option<Color>,
+
+	/// Top-most [`ColorUsage::Edge`] color, if any.
+	pub edge: Option<Color>,
+
+	/// Top-most [`ColorUsage::Shadow`] color, if any.
+	pub shadow_color: Option<Color>,
+}
+
+/// Tracker for the styles and per-usage color stacks of a writer.
+#[derive(Debug, Default)]
+pub(super) struct StyleState {
+	bold: bool,
+	italic: bool,
+	outline: bool,
+	shadow: bool,
+	colors: HashMap<ColorUsage, Vec<Color>>,
+}
+
+impl StyleState {
+	pub fn set_style(&mut self, style: Style, enabled: bool) {
+		let flag = match style {
+			Style::Bold => &mut self.bold,
+			Style::Italic => &mut self.italic,
+			Style::Outline => &mut self.outline,
+			Style::Shadow => &mut self.shadow,
+		};
+		*flag = enabled;
+	}
+
+	pub fn push_color(&mut self, usage: ColorUsage, color: Color) {
+		self.colors.entry(usage).or_default().push(color);
+	}
+
+	// Popping an empty stack is a no-op, matching the game's behavior.
+	pub fn pop_color(&mut self, usage: ColorUsage) {
+		if let Some(stack) = self.colors.get_mut(&usage) {
+			stack.pop();
+		}
+	}
+
+	pub fn style(&self) -> TextStyle {
+		let color = |usage| {
+			self.colors
+				.get(&usage)
+				.and_then(|stack| stack.last().copied())
+		};
+		TextStyle {
+			bold: self.bold,
+			italic: self.italic,
+			outline: self.outline,
+			shadow: self.shadow,
+			foreground: color(ColorUsage::Foreground),
+			edge: color(ColorUsage::Edge),
+			shadow_color: color(ColorUsage::Shadow),
+		}
+	}
+}
+
+/// A single run of content output by a [`RunWriter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StyledRun {
+	/// Text sharing a single style.
+	Text {
+		/// Text content of the run.
+		text: String,
+		/// Style of the text.
+		style: TextStyle,
+	},
+
+	/// Text annotated with a ruby.
+	Ruby {
+		/// Base text.
+		text: String,
+		/// Annotation displayed alongside the base text.
+		ruby: String,
+		/// Style of the text.
+		style: TextStyle,
+	},
+
+	/// An icon, by ID.
+	Icon(u32),
+}
+
+/// [`Write`] implementation collecting formatted content into styled runs.
+///
+/// Adjacent text with the same style is merged into a single run. This is
+/// intended for use with GUI toolkits that lay out rich text from spans.
+#[derive(Debug, Default)]
+pub struct RunWriter {
+	state: StyleState,
+	runs: Vec<StyledRun>,
+}
+
+impl RunWriter {
+	/// Creates a new `RunWriter` with no runs.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Runs written so far.
+	pub fn runs(&self) -> &[StyledRun] {
+		&self.runs
+	}
+
+	/// Consumes this writer, returning the written runs.
+	pub fn into_runs(self) -> Vec<StyledRun> {
+		self.runs
+	}
+}
+
+impl Write for RunWriter {
+	fn write_str(&mut self, str: &str) -> Result<()> {
+		if str.is_empty() {
+			return Ok(());
+		}
+
+		let style = self.state.style();
+		match self.runs.last_mut() {
+			Some(StyledRun::Text { text, style: last }) if *last == style => text.push_str(str),
+			_ => self.runs.push(StyledRun::Text {
+				text: str.into(),
+				style,
+			}),
+		}
+
+		Ok(())
+	}
+
+	fn set_style(&mut self, style: Style, enabled: bool) -> Result<()> {
+		self.state.set_style(style, enabled);
+		Ok(())
+	}
+
+	fn push_color(&mut self, usage: ColorUsage, color: Color) -> Result<()> {
+		self.state.push_color(usage, color);
+		Ok(())
+	}
+
+	fn pop_color(&mut self, usage: ColorUsage) -> Result<()> {
+		self.state.pop_color(usage);
+		Ok(())
+	}
+
+	fn write_ruby(&mut self, text: &str, ruby: &str) -> Result<()> {
+		self.runs.push(StyledRun::Ruby {
+			text: text.into(),
+			ruby: ruby.into(),
+			style: self.state.style(),
+		});
+		Ok(())
+	}
+
+	fn write_icon(&mut self, id: u32) -> Result<()> {
+		self.runs.push(StyledRun::Icon(id));
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::sestring::{
+		SeString,
+		format::{Color, Input, format},
+	};
+
+	use super::{RunWriter, StyledRun, TextStyle};
+
+	#[test]
+	fn collects_runs() {
+		let string = SeString::from_macro_string(
+			"a<color(4294901760)>b<bold(1)>c<color(stackcolor)>d<color(stackcolor)><icon(5)>e",
+		)
+		.unwrap();
+		let mut writer = RunWriter::new();
+		format(&string, &Input::new(), &mut writer).unwrap();
+
+		let red = Some(Color {
+			r: 255,
+			g: 0,
+			b: 0,
+			a: 255,
+		});
+		let text = |text: &str, style| StyledRun::Text {
+			text: text.into(),
+			style,
+		};
+		let bold = TextStyle {
+			bold: true,
+			..TextStyle::default()
+		};
+		assert_eq!(
+			writer.into_runs(),
+			[
+				text("a", TextStyle::default()),
+				text(
+					"b",
+					TextStyle {
+						foreground: red,
+						..TextStyle::default()
+					}
+				),
+				text(
+					"c",
+					TextStyle {
+						foreground: red,
+						..bold.clone()
+					}
+				),
+				text("d", bold.clone()),
+				StyledRun::Icon(5),
+				text("e", bold),
+			]
+		);
+	}
+}
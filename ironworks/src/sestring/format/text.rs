@@ -26,7 +26,7 @@ pub fn head_all<'a>(arguments: impl Arguments<'a>, state: &mut State) -> Result<
 	Ok(())
 }
 
-fn head_str<'a>(input: &str) -> String {
+fn head_str(input: &str) -> String {
 	let mut chars = input.chars();
 	match chars.next() {
 		Some(char) => char.to_uppercase().collect::<String>() + chars.as_str(),
@@ -62,7 +62,7 @@ pub fn split<'a>(arguments: impl Arguments<'a>, state: &mut State) -> Result<()>
 		.nth(index.try_into().unwrap())
 		.unwrap_or("");
 
-	state.writer.write_str(&output)?;
+	state.writer.write_str(output)?;
 
 	Ok(())
 }
@@ -70,11 +70,7 @@ pub fn split<'a>(arguments: impl Arguments<'a>, state: &mut State) -> Result<()>
 pub fn ruby<'a>(arguments: impl Arguments<'a>, state: &mut State) -> Result<()> {
 	let (string, pronounciation) = arguments.exhaustive::<(String, String)>(state)?;
 
-	// TODO: this should realistically be exposed as a discrete call to the writer
-	// so they can do the proper layout, but that can be a later problem.
-	state
-		.writer
-		.write_str(&format!("{string} ({pronounciation})"))?;
+	state.writer.write_ruby(&string, &pronounciation)?;
 
 	Ok(())
 }
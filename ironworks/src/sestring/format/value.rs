@@ -71,10 +71,7 @@ impl From<Value> for u32 {
 
 impl From<Value> for bool {
 	fn from(value: Value) -> Self {
-		match u32::from(value) {
-			0 => false,
-			_ => true,
-		}
+		u32::from(value) != 0
 	}
 }
 
@@ -37,6 +37,19 @@ pub trait Write {
 		let _ = usage;
 		Ok(())
 	}
+
+	/// Writes text annotated with a ruby, typically a pronunciation guide. By
+	/// default, the ruby is written in parentheses following the text.
+	fn write_ruby(&mut self, text: &str, ruby: &str) -> Result<()> {
+		self.write_str(&format!("{text} ({ruby})"))
+	}
+
+	/// Writes the icon with the specified ID. Icon IDs reference entries in the
+	/// game's font icon data. By default, icons are not written.
+	fn write_icon(&mut self, id: u32) -> Result<()> {
+		let _ = id;
+		Ok(())
+	}
 }
 
 pub type PlainString = PlainWriter<String>;
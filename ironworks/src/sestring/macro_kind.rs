@@ -1,4 +1,4 @@
-use num_enum::FromPrimitive;
+use num_enum::{FromPrimitive, IntoPrimitive};
 
 // Based on Lumina implementation as of 2024-08-10
 // https://github.com/NotAdam/Lumina/blob/e99b736f18b0962a28d326cd290490b121ed679b/src/Lumina/Text/Payloads/MacroCode.cs
@@ -10,7 +10,7 @@ use num_enum::FromPrimitive;
 /// [`format`](super::format) for examples of implemented behavior.
 #[allow(missing_docs)]
 #[non_exhaustive]
-#[derive(Debug, PartialEq, Eq, Clone, Copy, FromPrimitive)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, FromPrimitive, IntoPrimitive)]
 #[repr(u8)]
 pub enum MacroKind {
 	SetResetTime = 0x06,
@@ -80,7 +80,6 @@ impl MacroKind {
 		match self {
 			MacroKind::Unknown(val) => format!("payload:{:02X}", val),
 			MacroKind::NewLine => "br".to_string(),
-			MacroKind::SoftHyphen => "-".to_string(),
 			MacroKind::NonBreakingSpace => "nbsp".to_string(),
 			MacroKind::Hyphen => "-".to_string(),
 			_ => format!("{:?}", self).to_ascii_lowercase(),
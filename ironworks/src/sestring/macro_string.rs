@@ -1,59 +1,83 @@
 use std::{
-	fmt::{self, Display},
+	collections::HashMap,
+	fmt::{self, Display, Write},
 	sync::LazyLock,
 };
 
-use super::error::Result;
-use crate::sestring::{Expression, Payload, SeStr};
+use super::{
+	cursor::SliceCursor,
+	error::{Error, Result},
+	expression::{INLINE_STRING, write_u32},
+	payload::{MACRO_END, MACRO_START},
+};
+use crate::sestring::{Expression, MacroKind, Payload, SeStr};
 
 pub struct MacroString<'a>(&'a SeStr);
 
+/// Position of a string within macro string text. Determines which characters
+/// are escaped, and which terminate the string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Context {
+	Text,
+	Argument,
+	Lhs,
+	Rhs,
+}
+
 impl MacroString<'_> {
 	pub(super) fn new(str: &SeStr) -> MacroString<'_> {
 		MacroString(str)
 	}
 
-	fn fmt_string(
-		str: &SeStr,
-		inside_macro: bool,
-		formatter: &mut fmt::Formatter<'_>,
-	) -> Result<()> {
+	fn fmt_string(str: &SeStr, context: Context, writer: &mut impl Write) -> Result<()> {
 		for payload in str.payloads() {
 			match payload? {
 				Payload::Text(text) => {
-					write_escaped(formatter, text.as_utf8()?, inside_macro)?;
+					write_escaped(writer, text.as_utf8()?, context)?;
 				}
 				Payload::Macro(macro_payload) => {
-					formatter.write_str("<")?;
-					formatter.write_str(&macro_payload.kind().name())?;
+					writer.write_str("<")?;
+					writer.write_str(&macro_payload.kind().name())?;
 					let expressions = macro_payload.expressions();
 					let mut is_empty = true;
 					for (i, expr) in expressions.enumerate() {
-						formatter.write_str(if i == 0 {
+						writer.write_str(if i == 0 {
 							is_empty = false;
 							"("
 						} else {
 							","
 						})?;
-						Self::fmt_expression(&expr?, formatter)?;
+						Self::fmt_expression(&expr?, Context::Argument, writer)?;
 					}
 					if !is_empty {
-						formatter.write_str(")")?;
+						writer.write_str(")")?;
 					}
-					formatter.write_str(">")?;
+					writer.write_str(">")?;
 				}
 			}
 		}
 		Ok(())
 	}
 
-	fn fmt_expression(expr: &Expression<'_>, formatter: &mut fmt::Formatter<'_>) -> Result<()> {
+	fn fmt_expression(
+		expr: &Expression<'_>,
+		context: Context,
+		writer: &mut impl Write,
+	) -> Result<()> {
 		match expr {
 			Expression::U32(value) => {
-				value.fmt(formatter)?;
+				write!(writer, "{value}")?;
 			}
 			Expression::SeString(sestring) => {
-				Self::fmt_string(sestring, true, formatter)?;
+				let mut string = String::new();
+				Self::fmt_string(sestring, context, &mut string)?;
+				// Strings that read as another expression, i.e. "1", are escaped so
+				// they are parsed back as strings.
+				let mut parser = Parser::new(&string);
+				if !parser.expression(context)?.1 {
+					writer.write_char(ESCAPE_CHAR as char)?;
+				}
+				writer.write_str(&string)?;
 			}
 			Expression::Millisecond
 			| Expression::Second
@@ -64,14 +88,14 @@ impl MacroString<'_> {
 			| Expression::Month
 			| Expression::Year
 			| Expression::StackColor => {
-				formatter.write_str(expr.name())?;
+				writer.write_str(expr.name())?;
 			}
 			Expression::LocalNumber(e)
 			| Expression::GlobalNumber(e)
 			| Expression::LocalString(e)
 			| Expression::GlobalString(e) => {
-				formatter.write_str(expr.name())?;
-				Self::fmt_expression(e, formatter)?;
+				writer.write_str(expr.name())?;
+				Self::fmt_expression(e, context, writer)?;
 			}
 			Expression::Ge(lhs, rhs)
 			| Expression::Gt(lhs, rhs)
@@ -79,14 +103,14 @@ impl MacroString<'_> {
 			| Expression::Lt(lhs, rhs)
 			| Expression::Eq(lhs, rhs)
 			| Expression::Ne(lhs, rhs) => {
-				formatter.write_str("[")?;
-				Self::fmt_expression(lhs, formatter)?;
-				formatter.write_str(expr.name())?;
-				Self::fmt_expression(rhs, formatter)?;
-				formatter.write_str("]")?;
+				writer.write_str("[")?;
+				Self::fmt_expression(lhs, Context::Lhs, writer)?;
+				writer.write_str(expr.name())?;
+				Self::fmt_expression(rhs, Context::Rhs, writer)?;
+				writer.write_str("]")?;
 			}
 			Expression::Unknown(value) => {
-				write!(formatter, "unknown({value})")?;
+				write!(writer, "unknown({value})")?;
 			}
 		}
 		Ok(())
@@ -95,11 +119,12 @@ impl MacroString<'_> {
 
 impl Display for MacroString<'_> {
 	fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-		Self::fmt_string(self.0, false, formatter).map_err(|_| fmt::Error)
+		Self::fmt_string(self.0, Context::Text, formatter).map_err(|_| fmt::Error)
 	}
 }
 
-const ESCAPED_CHARS_IN_MACRO: &[u8] = &[b'<', b'>', b'[', b']', b'(', b')', b',', b'\\'];
+const ESCAPED_CHARS_IN_MACRO: &[u8] = b"<>[](),\\";
+const ESCAPED_CHARS_IN_OPERAND: &[u8] = b"=!";
 const ESCAPED_CHARS: [u8; 3] = [b'<', b'>', b'\\'];
 const ESCAPE_CHAR: u8 = b'\\';
 
@@ -111,11 +136,19 @@ static ESC_TABLE: LazyLock<[bool; 256]> = LazyLock::new(|| {
 	t
 });
 
-fn write_escaped(f: &mut fmt::Formatter<'_>, str: &str, inside_macro: bool) -> fmt::Result {
+static OPERAND_ESC_TABLE: LazyLock<[bool; 256]> = LazyLock::new(|| {
+	let mut t = *ESC_TABLE;
+	for &x in ESCAPED_CHARS_IN_OPERAND {
+		t[x as usize] = true;
+	}
+	t
+});
+
+fn write_escaped(f: &mut impl Write, str: &str, context: Context) -> fmt::Result {
 	let s = str;
 	let b = s.as_bytes();
 
-	if !inside_macro {
+	if context == Context::Text {
 		let (n1, n2, n3) = (ESCAPED_CHARS[0], ESCAPED_CHARS[1], ESCAPED_CHARS[2]);
 
 		let mut start = 0usize;
@@ -138,7 +171,10 @@ fn write_escaped(f: &mut fmt::Formatter<'_>, str: &str, inside_macro: bool) -> f
 		return Ok(());
 	}
 
-	let table = &*ESC_TABLE;
+	let table = match context {
+		Context::Lhs | Context::Rhs => &*OPERAND_ESC_TABLE,
+		_ => &*ESC_TABLE,
+	};
 	let mut start = 0usize;
 	while start < b.len() {
 		let mut pos = start;
@@ -160,3 +196,293 @@ fn write_escaped(f: &mut fmt::Formatter<'_>, str: &str, inside_macro: bool) -> f
 	}
 	Ok(())
 }
+
+static MACRO_KINDS: LazyLock<HashMap<String, u8>> = LazyLock::new(|| {
+	(0..=u8::MAX)
+		.map(|code| (MacroKind::from(code).name(), code))
+		.collect()
+});
+
+const NULLARY_EXPRESSIONS: &[(&str, u8)] = &[
+	("t_msec", 0xD8),
+	("t_sec", 0xD9),
+	("t_min", 0xDA),
+	("t_hour", 0xDB),
+	("t_day", 0xDC),
+	("t_wday", 0xDD),
+	("t_mon", 0xDE),
+	("t_year", 0xDF),
+	("stackcolor", 0xEC),
+];
+
+const UNARY_EXPRESSIONS: &[(&str, u8)] = &[
+	("lnum", 0xE8),
+	("gnum", 0xE9),
+	("lstr", 0xEA),
+	("gstr", 0xEB),
+];
+
+// Two-character operators must be checked before their one-character prefixes.
+const BINARY_EXPRESSIONS: &[(&str, u8)] = &[
+	(">=", 0xE0),
+	(">", 0xE1),
+	("<=", 0xE2),
+	("<", 0xE3),
+	("=", 0xE4),
+	("!=", 0xE5),
+];
+
+/// Parse macro string text, as written by [`MacroString`], into encoded
+/// SeString data.
+pub(super) fn parse(text: &str) -> Result<Vec<u8>> {
+	let mut parser = Parser::new(text);
+	let data = parser.string(Context::Text)?;
+	match parser.peek() {
+		None => Ok(data),
+		Some(_) => Err(parser.error()),
+	}
+}
+
+struct Parser<'a> {
+	text: &'a str,
+	offset: usize,
+}
+
+impl<'a> Parser<'a> {
+	fn new(text: &'a str) -> Self {
+		Self { text, offset: 0 }
+	}
+
+	fn rest(&self) -> &'a str {
+		&self.text[self.offset..]
+	}
+
+	fn peek(&self) -> Option<char> {
+		self.rest().chars().next()
+	}
+
+	fn next(&mut self) -> Option<char> {
+		let char = self.peek()?;
+		self.offset += char.len_utf8();
+		Some(char)
+	}
+
+	fn eat(&mut self, prefix: &str) -> bool {
+		let matches = self.rest().starts_with(prefix);
+		if matches {
+			self.offset += prefix.len();
+		}
+		matches
+	}
+
+	fn expect(&mut self, prefix: &str) -> Result<()> {
+		match self.eat(prefix) {
+			true => Ok(()),
+			false => Err(self.error()),
+		}
+	}
+
+	fn error(&self) -> Error {
+		Error::InvalidMacroString(self.offset)
+	}
+
+	fn length(&self, data: &[u8]) -> Result<u32> {
+		u32::try_from(data.len()).map_err(|_| self.error())
+	}
+
+	/// Check if the parser is at the end of an expression in the given context.
+	fn at_terminator(&mut self, context: Context) -> bool {
+		match (context, self.peek()) {
+			(_, None) => true,
+			(Context::Argument, Some(',' | ')')) => true,
+			(Context::Lhs, Some('>' | '=' | '!')) => true,
+			(Context::Lhs, Some('<')) => !self.is_macro(),
+			(Context::Rhs, Some(']')) => true,
+			_ => false,
+		}
+	}
+
+	/// Check if the parser is at the start of a valid macro, without consuming it.
+	fn is_macro(&mut self) -> bool {
+		let offset = self.offset;
+		let is_macro = self.macro_payload().is_ok();
+		self.offset = offset;
+		is_macro
+	}
+
+	/// Parse text and macros until the end of the string in the given context.
+	fn string(&mut self, context: Context) -> Result<Vec<u8>> {
+		let mut data = vec![];
+		while !self.at_terminator(context) {
+			let char = match self.peek() {
+				Some('<') => {
+					data.extend(self.macro_payload()?);
+					continue;
+				}
+				Some('\\') => {
+					self.next();
+					self.next().ok_or_else(|| self.error())?
+				}
+				_ => self.next().unwrap(),
+			};
+			data.extend_from_slice(char.encode_utf8(&mut [0; 4]).as_bytes());
+		}
+		Ok(data)
+	}
+
+	fn macro_payload(&mut self) -> Result<Vec<u8>> {
+		self.expect("<")?;
+
+		let length = self
+			.rest()
+			.find(|char: char| !(char.is_ascii_alphanumeric() || matches!(char, ':' | '-')))
+			.unwrap_or(self.rest().len());
+		let name = &self.rest()[..length];
+		let kind = *MACRO_KINDS.get(name).ok_or_else(|| self.error())?;
+		self.offset += length;
+
+		let mut body = vec![];
+		if self.eat("(") {
+			loop {
+				body.extend(self.expression(Context::Argument)?.0);
+				match self.next() {
+					Some(',') => continue,
+					Some(')') => break,
+					_ => return Err(self.error()),
+				}
+			}
+		}
+		self.expect(">")?;
+
+		let mut data = vec![MACRO_START, kind];
+		write_u32(&mut data, self.length(&body)?);
+		data.extend(body);
+		data.push(MACRO_END);
+		Ok(data)
+	}
+
+	/// Parse an expression, returning its encoded data, and whether it was read
+	/// as a string.
+	fn expression(&mut self, context: Context) -> Result<(Vec<u8>, bool)> {
+		let offset = self.offset;
+		if let Some(data) = self.non_string_expression(context)? {
+			if self.at_terminator(context) {
+				return Ok((data, false));
+			}
+		}
+		self.offset = offset;
+
+		let string = self.string(context)?;
+		let mut data = vec![INLINE_STRING];
+		write_u32(&mut data, self.length(&string)?);
+		data.extend(string);
+		Ok((data, true))
+	}
+
+	fn non_string_expression(&mut self, context: Context) -> Result<Option<Vec<u8>>> {
+		if self.eat("[") {
+			let mut data = vec![0];
+			data.extend(self.expression(Context::Lhs)?.0);
+			data[0] = BINARY_EXPRESSIONS
+				.iter()
+				.find(|(operator, _)| self.eat(operator))
+				.map(|&(_, code)| code)
+				.ok_or_else(|| self.error())?;
+			data.extend(self.expression(Context::Rhs)?.0);
+			self.expect("]")?;
+			return Ok(Some(data));
+		}
+
+		let digits = self.rest().find(|char: char| !char.is_ascii_digit());
+		let digits = &self.rest()[..digits.unwrap_or(self.rest().len())];
+		if !digits.is_empty() {
+			let Ok(value) = digits.parse::<u32>() else {
+				return Ok(None);
+			};
+			self.offset += digits.len();
+			let mut data = vec![];
+			write_u32(&mut data, value);
+			return Ok(Some(data));
+		}
+
+		for &(name, code) in NULLARY_EXPRESSIONS {
+			let offset = self.offset;
+			if self.eat(name) && self.at_terminator(context) {
+				return Ok(Some(vec![code]));
+			}
+			self.offset = offset;
+		}
+
+		for &(name, code) in UNARY_EXPRESSIONS {
+			if self.eat(name) {
+				let mut data = vec![code];
+				data.extend(self.expression(context)?.0);
+				return Ok(Some(data));
+			}
+		}
+
+		if self.eat("unknown(") {
+			let end = self.rest().find(')').ok_or_else(|| self.error())?;
+			let value = self.rest()[..end].parse::<u8>().map_err(|_| self.error())?;
+			// Only values that are read back as unknown expressions are accepted.
+			let bytes = [value];
+			let expression = Expression::read(&mut SliceCursor::new(&bytes));
+			if !matches!(expression, Ok(Expression::Unknown(_))) {
+				return Err(self.error());
+			}
+			self.offset += end + 1;
+			return Ok(Some(vec![value]));
+		}
+
+		Ok(None)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::sestring::{Error, SeStr, SeString};
+
+	use super::MACRO_KINDS;
+
+	#[test]
+	fn parses_macros() {
+		let string = SeString::from_macro_string("Hi <if([gnum1=0],a\\,b,)><br>\\<").unwrap();
+		assert_eq!(
+			string.as_bytes(),
+			b"Hi \x02\x08\x0C\xE4\xE9\x02\x01\xFF\x04a,b\xFF\x01\x03\x02\x10\x01\x03<"
+		);
+
+		let error = SeString::from_macro_string("a <nope>").unwrap_err();
+		assert_eq!(error, Error::InvalidMacroString(3));
+		assert_eq!(MACRO_KINDS.len(), 256, "macro names must be unique");
+	}
+
+	#[test]
+	fn round_trips() {
+		let strings: &[&[u8]] = &[
+			b"",
+			b"plain <text> \\ with, symbols",
+			// <if([gnum1=0],5,<br>)> - the string "5" must not read as a number.
+			b"\x02\x08\x0E\xE4\xE9\x02\x01\xFF\x025\xFF\x05\x02\x10\x01\x03\x03",
+			// Empty, unary, and nested string arguments.
+			b"\x02\x29\x09\xFF\x01\xEB\xFF\x01\xFF\x02a\x03",
+			// <switch([lstr=!]a=,...)> - operands containing operators.
+			b"\x02\x09\x0C\xE4\xFF\x03a=\xFF\x03!a\xFF\x01\x03",
+			// Packed integers, time expressions, unknown expressions.
+			b"\x02\x20\x07\xF4\x01\x01\xD8\xEC\xD0\x03\x02\xFF\x01\x03",
+			// Nullary macros, and strings that read as expression names.
+			b"\x02\x16\x01\x03\x02\x1F\x01\x03\x02\x29\x09\xFF\x07t_msec\x03",
+			b"\x02\x29\x07\xFF\x05gnum\x03",
+			// Comparisons with macros and operators in their operands.
+			b"\x02\x29\x09\xE0\xFF\x05\x02\x10\x01\x03\x02\x03\x02\x29\x04\xE3\x02\x03\x03",
+		];
+
+		for &bytes in strings {
+			let string: &SeStr = bytes.into();
+			let text = string.macro_string().to_string();
+			let parsed = SeString::from_macro_string(&text)
+				.unwrap_or_else(|error| panic!("failed to parse {text:?}: {error}"));
+			assert_eq!(parsed.as_bytes(), bytes, "round trip of {text:?}");
+		}
+	}
+}
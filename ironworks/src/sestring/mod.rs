@@ -1,5 +1,6 @@
 //! Types and helpers for working with the SeString rich text format.
 
+mod builder;
 mod cursor;
 mod error;
 mod expression;
@@ -14,6 +15,7 @@ mod sestring;
 pub mod format;
 
 pub use {
+	builder::SeStringBuilder,
 	error::Error,
 	expression::Expression,
 	macro_kind::MacroKind,
@@ -7,8 +7,8 @@ use super::{
 	macro_kind::MacroKind,
 };
 
-const MACRO_START: u8 = 0x02;
-const MACRO_END: u8 = 0x03;
+pub(super) const MACRO_START: u8 = 0x02;
+pub(super) const MACRO_END: u8 = 0x03;
 
 /// A single section of data within an [`SeString`][super::SeString].
 #[allow(missing_docs)]
@@ -57,7 +57,7 @@ impl<'a> TextPayload<'a> {
 	/// Tries to retrieve the underlying string representation of the payload.
 	/// Will fail if text is invalid UTF8.
 	pub fn as_utf8(&self) -> Result<&'a str> {
-		str::from_utf8(&self.0).map_err(|_error| Error::InvalidText)
+		str::from_utf8(self.0).map_err(|_error| Error::InvalidText)
 	}
 
 	/// Returns the raw bytes of the text payload.
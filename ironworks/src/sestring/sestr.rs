@@ -193,7 +193,7 @@ mod test {
 		assert_count(bytes, 1);
 	}
 
-	fn assert_count<'a>(bytes: &'a [u8], expected: usize) {
+	fn assert_count(bytes: &[u8], expected: usize) {
 		let sestring: &SeStr = bytes.into();
 		assert_eq!(sestring.payloads().count(), expected)
 	}
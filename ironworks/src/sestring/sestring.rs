@@ -8,7 +8,7 @@ use std::{
 
 use crate::sestring::sestr::SeStr;
 
-use super::error::Result;
+use super::{error::Result, macro_string};
 
 /// Square Enix rich text format.
 ///
@@ -28,6 +28,12 @@ impl SeString {
 		Self(Vec::new())
 	}
 
+	/// Parses human-readable macro string text, as written by
+	/// [`SeStr::macro_string`], into an `SeString`.
+	pub fn from_macro_string(text: &str) -> Result<Self> {
+		macro_string::parse(text).map(Self)
+	}
+
 	/// Converts to an [`SeStr`] slice.
 	#[must_use]
 	#[inline]
@@ -260,6 +266,6 @@ impl fmt::Debug for SeString {
 impl Hash for SeString {
 	#[inline]
 	fn hash<H: Hasher>(&self, state: &mut H) {
-		(&**self).hash(state)
+		(**self).hash(state)
 	}
 }
@@ -17,6 +17,17 @@ pub struct BlockHeader {
 	pub decompressed_size: u32,
 }
 
+impl BlockHeader {
+	/// Bytes the block occupies in the dat, from the start of its header to the end of its payload.
+	pub fn stored_size(&self) -> u64 {
+		let payload = match self.compressed_size > MAX_COMPRESSED_BLOCK_SIZE {
+			true => self.decompressed_size,
+			false => self.compressed_size,
+		};
+		u64::from(self.size) + u64::from(payload)
+	}
+}
+
 pub fn read_block<R: Read + Seek>(
 	reader: &'_ mut R,
 	offset: u32,
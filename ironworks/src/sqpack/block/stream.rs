@@ -122,7 +122,7 @@ where
 				// Check we read the expected size.
 				if count != meta.output_size {
 					return Err(io::Error::new(
-						io::ErrorKind::Other,
+						io::ErrorKind::UnexpectedEof,
 						format!(
 							"failed to read block: expected {} bytes, got {}",
 							meta.output_size, count
@@ -6,7 +6,7 @@ use crate::{error::Result, sqpack::block::BlockStream};
 
 use super::{
 	model,
-	shared::{FileKind, Header},
+	shared::{FileKind, Header, StoredBlock},
 	standard, texture,
 };
 
@@ -49,6 +49,16 @@ impl<R: Read + Seek> File<R> {
 	}
 }
 
+/// The blocks a file's block table records, read from just past its header.
+pub fn stored_blocks(reader: impl Read + Seek, header: &Header) -> Result<Vec<StoredBlock>> {
+	match header.kind {
+		FileKind::Empty => Ok(vec![]),
+		FileKind::Standard => standard::blocks(reader, header.size, header),
+		FileKind::Model => model::blocks(reader, header.size, header),
+		FileKind::Texture => texture::blocks(reader, header.size, header),
+	}
+}
+
 #[derive(Debug)]
 enum FileStreamKind<R> {
 	Empty(Empty),
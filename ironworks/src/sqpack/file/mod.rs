@@ -5,3 +5,8 @@ mod standard;
 mod texture;
 
 pub use {file::File, shared::FileKind};
+pub(super) use {
+	file::stored_blocks,
+	shared::{Header, StoredBlock},
+	texture::array_size,
+};
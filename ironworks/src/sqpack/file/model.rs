@@ -2,9 +2,12 @@ use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
 
 use binrw::{BinRead, BinWriterExt, VecArgs, binread};
 
-use crate::{error::Result, sqpack::block::read_block};
+use crate::{
+	error::{Error, ErrorValue, Result},
+	sqpack::block::read_block,
+};
 
-use super::shared::Header;
+use super::shared::{Header, StoredBlock};
 
 const MAX_LODS: usize = 3;
 
@@ -36,26 +39,80 @@ struct SectionInfo<T: for<'a> BinRead<Args<'a> = ()> + 'static> {
 	index_buffer: [T; MAX_LODS],
 }
 
-pub fn read(mut reader: impl Read + Seek, offset: u32, header: Header) -> Result<Cursor<Vec<u8>>> {
-	let model_header = ModelHeader::read(&mut reader)?;
+impl<T: Copy + for<'a> BinRead<Args<'a> = ()> + 'static> SectionInfo<T> {
+	/// Every section's value, in the order the table lists them.
+	fn all(&self) -> impl Iterator<Item = T> + '_ {
+		[self.stack, self.runtime]
+			.into_iter()
+			.chain(self.vertex_buffer)
+			.chain(self.edge_geometry_vertex_buffer)
+			.chain(self.index_buffer)
+	}
+}
+
+fn read_tables(reader: &mut (impl Read + Seek)) -> Result<(ModelHeader, Vec<u16>)> {
+	let model_header = ModelHeader::read(reader)?;
 
 	// Model header is followed by an array of block sizes.
-	let block_counts = &model_header.block_count;
-	let total_blocks = block_counts.stack
-		+ block_counts.runtime
-		+ block_counts.vertex_buffer.iter().sum::<u16>()
-		+ block_counts.edge_geometry_vertex_buffer.iter().sum::<u16>()
-		+ block_counts.index_buffer.iter().sum::<u16>();
+	let total_blocks = model_header
+		.block_count
+		.all()
+		.map(usize::from)
+		.sum::<usize>();
 
 	// TODO: i should probably make an impl for this it's pretty repetetive
 	let block_sizes = <Vec<u16>>::read_le_args(
-		&mut reader,
+		reader,
 		VecArgs {
-			count: total_blocks.try_into().unwrap(),
+			count: total_blocks,
 			inner: (),
 		},
 	)?;
 
+	Ok((model_header, block_sizes))
+}
+
+pub fn blocks(
+	mut reader: impl Read + Seek,
+	offset: u32,
+	_header: &Header,
+) -> Result<Vec<StoredBlock>> {
+	let (model_header, block_sizes) = read_tables(&mut reader)?;
+
+	let sections = model_header
+		.offset
+		.all()
+		.zip(model_header.block_index.all())
+		.zip(model_header.block_count.all());
+
+	let mut blocks = vec![];
+	for ((section_offset, block_index), block_count) in sections {
+		let first = usize::from(block_index);
+		let sizes = block_sizes
+			.get(first..first + usize::from(block_count))
+			.ok_or_else(|| {
+				Error::Invalid(
+					ErrorValue::Other("model block table".into()),
+					format!("section blocks {first}+{block_count} are not in the table"),
+				)
+			})?;
+
+		let mut block_offset = u64::from(offset) + u64::from(section_offset);
+		for &size in sizes {
+			blocks.push(StoredBlock {
+				offset: block_offset,
+				size: size.into(),
+			});
+			block_offset += u64::from(size);
+		}
+	}
+
+	Ok(blocks)
+}
+
+pub fn read(mut reader: impl Read + Seek, offset: u32, header: Header) -> Result<Cursor<Vec<u8>>> {
+	let (model_header, block_sizes) = read_tables(&mut reader)?;
+
 	// Build a writer for the output file.
 	let mut writer = Cursor::new(Vec::<u8>::with_capacity(
 		header.raw_file_size.try_into().unwrap(),
@@ -13,6 +13,15 @@ pub struct Header {
 	pub block_count: u32,
 }
 
+/// Where a block of a file's data is stored, as recorded by the file's block table.
+#[derive(Debug)]
+pub struct StoredBlock {
+	/// Offset of the block header from the start of the file's entry.
+	pub offset: u64,
+	/// Space set aside for the block, including any trailing padding.
+	pub size: u64,
+}
+
 /// How sqpack stores a file's data.
 #[binread]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
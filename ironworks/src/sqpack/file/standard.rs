@@ -7,26 +7,46 @@ use crate::{
 	sqpack::block::{BlockHeader, BlockMetadata, BlockStream},
 };
 
-use super::shared::Header;
+use super::shared::{Header, StoredBlock};
 
 #[binread]
 #[derive(Debug)]
 #[br(little)]
 struct BlockInfo {
 	offset: u32,
-	_input_size: u16,
+	input_size: u16,
 	output_size: u16,
 }
 
-pub fn read<R: Read + Seek>(mut reader: R, offset: u32, header: Header) -> Result<BlockStream<R>> {
-	// Eagerly read the block info.
-	let blocks = <Vec<BlockInfo>>::read_args(
-		&mut reader,
+fn read_block_info(reader: &mut (impl Read + Seek), header: &Header) -> Result<Vec<BlockInfo>> {
+	Ok(<Vec<BlockInfo>>::read_args(
+		reader,
 		VecArgs {
 			count: header.block_count.try_into().unwrap(),
 			inner: (),
 		},
-	)?;
+	)?)
+}
+
+pub fn blocks(
+	mut reader: impl Read + Seek,
+	offset: u32,
+	header: &Header,
+) -> Result<Vec<StoredBlock>> {
+	let blocks = read_block_info(&mut reader, header)?
+		.iter()
+		.map(|info| StoredBlock {
+			offset: u64::from(offset) + u64::from(info.offset),
+			size: info.input_size.into(),
+		})
+		.collect();
+
+	Ok(blocks)
+}
+
+pub fn read<R: Read + Seek>(mut reader: R, offset: u32, header: Header) -> Result<BlockStream<R>> {
+	// Eagerly read the block info.
+	let blocks = read_block_info(&mut reader, &header)?;
 
 	// Closure for subsequent scan to allow cleaner error handling.
 	let mut read_block_metadata = |previous: &mut usize, info: &BlockInfo| -> Result<_> {
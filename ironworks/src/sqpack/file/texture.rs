@@ -4,7 +4,7 @@ use binrw::{BinRead, VecArgs, binread};
 
 use crate::{error::Result, sqpack::block::read_block};
 
-use super::shared::Header;
+use super::shared::{Header, StoredBlock};
 
 #[binread]
 #[br(little)]
@@ -34,10 +34,13 @@ struct TexHeader {
 	surface_offsets: [u32; 13],
 }
 
-pub fn read(mut reader: impl Read + Seek, offset: u32, header: Header) -> Result<Cursor<Vec<u8>>> {
+fn read_tables(
+	reader: &mut (impl Read + Seek),
+	header: &Header,
+) -> Result<(Vec<SurfaceBlockInfo>, Vec<u16>)> {
 	// Eagerly read the block info.
 	let blocks = <Vec<SurfaceBlockInfo>>::read_args(
-		&mut reader,
+		reader,
 		VecArgs {
 			count: header.block_count.try_into().unwrap(),
 			inner: (),
@@ -47,16 +50,50 @@ pub fn read(mut reader: impl Read + Seek, offset: u32, header: Header) -> Result
 	// Directly after the block info, texture files have a table of sub-block offsets.
 	let sub_block_count = blocks
 		.iter()
-		.fold(0, |total, block| total + block.block_count);
+		.map(|block| usize::try_from(block.block_count).unwrap())
+		.sum::<usize>();
 
 	let sub_block_offsets = <Vec<u16>>::read_le_args(
-		&mut reader,
+		reader,
 		VecArgs {
-			count: sub_block_count.try_into().unwrap(),
+			count: sub_block_count,
 			inner: (),
 		},
 	)?;
 
+	Ok((blocks, sub_block_offsets))
+}
+
+pub fn blocks(
+	mut reader: impl Read + Seek,
+	offset: u32,
+	header: &Header,
+) -> Result<Vec<StoredBlock>> {
+	let (surfaces, sub_block_sizes) = read_tables(&mut reader, header)?;
+
+	let mut blocks = vec![];
+	for surface in &surfaces {
+		let sizes = sub_block_sizes
+			.iter()
+			.skip(usize::try_from(surface.block_offset).unwrap())
+			.take(usize::try_from(surface.block_count).unwrap());
+
+		let mut block_offset = u64::from(offset) + u64::from(surface.compressed_offset);
+		for &size in sizes {
+			blocks.push(StoredBlock {
+				offset: block_offset,
+				size: size.into(),
+			});
+			block_offset += u64::from(size);
+		}
+	}
+
+	Ok(blocks)
+}
+
+pub fn read(mut reader: impl Read + Seek, offset: u32, header: Header) -> Result<Cursor<Vec<u8>>> {
+	let (blocks, sub_block_offsets) = read_tables(&mut reader, &header)?;
+
 	// Create a writer with capacity for the full file.
 	let mut writer = Cursor::new(Vec::<u8>::with_capacity(
 		header.raw_file_size.try_into().unwrap(),
@@ -83,20 +120,9 @@ pub fn read(mut reader: impl Read + Seek, offset: u32, header: Header) -> Result
 	// are in the array to accurately distribute the blocks across the expected
 	// mip level offsets. Check `file/tex` for the full definition of the bitset
 	// being queried in this block.
-	let array_size = match texture_header {
-		// Cube textures always have precisely 6 array items.
-		Some(TexHeader { attribute, .. }) if (attribute >> 25) & 1 == 1 => 6,
-
-		// 2D texture arrays have N array items, as specified by the header.
-		Some(TexHeader {
-			attribute,
-			array_size,
-			..
-		}) if (attribute >> 28) & 1 == 1 => usize::from(array_size),
-
-		// All other texture kinds do not utilise arrays (have 1 entry).
-		_ => 1,
-	};
+	let array_size = texture_header
+		.as_ref()
+		.map_or(1, |header| array_size(header.attribute, header.array_size));
 
 	for (index, block) in blocks.iter().enumerate() {
 		// Move to the expected start position of the block.
@@ -122,3 +148,20 @@ pub fn read(mut reader: impl Read + Seek, offset: u32, header: Header) -> Result
 	writer.rewind()?;
 	Ok(writer)
 }
+
+/// Number of surfaces stored for each mip level of a texture with the given
+/// attributes.
+pub fn array_size(attribute: u32, array_size: u8) -> usize {
+	// Cube textures always have precisely 6 array items.
+	if (attribute >> 25) & 1 == 1 {
+		return 6;
+	}
+
+	// 2D texture arrays have N array items, as specified by the header.
+	if (attribute >> 28) & 1 == 1 {
+		return usize::from(array_size);
+	}
+
+	// All other texture kinds do not utilise arrays (have 1 entry).
+	1
+}
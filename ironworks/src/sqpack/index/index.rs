@@ -69,10 +69,15 @@ impl Location {
 			size,
 		}
 	}
+
+	/// This location, extended to run to the end of its data file.
+	pub(crate) fn unbounded(self) -> Self {
+		Self { size: None, ..self }
+	}
 }
 
 /// The chunk a path is stored in.
-fn path_chunk(repository: u8, path: &str) -> u8 {
+pub fn path_chunk(repository: u8, path: &str) -> u8 {
 	if repository == 0 {
 		return 0;
 	}
@@ -194,6 +199,28 @@ impl<R: Resource> Index<R> {
 		found.ok_or_else(not_found)
 	}
 
+	/// Every file a chunk records under a hash. Unlike [`find_hash`](Self::find_hash), a hash
+	/// shared by colliding files names each of them.
+	pub fn find_recorded(&self, chunk: u8, hash: IndexHash) -> Result<Vec<Location>> {
+		let Some(index_chunk) = self.chunk(chunk)? else {
+			return Ok(vec![]);
+		};
+
+		let located = match (&*index_chunk, hash) {
+			(IndexChunk::Index1(index1), IndexHash::Split(split)) => index1.find_all(split),
+			(IndexChunk::Index2(index2), IndexHash::Whole(whole)) => index2.find_all(whole),
+			(IndexChunk::Index1(_), IndexHash::Whole(whole)) => self
+				.whole_chunk(chunk)?
+				.map_or_else(Vec::new, |index2| index2.find_all(whole)),
+			(IndexChunk::Index2(_), IndexHash::Split(_)) => vec![],
+		};
+
+		Ok(located
+			.into_iter()
+			.map(|located| Location::new(chunk, located))
+			.collect())
+	}
+
 	pub fn find(&self, path: &str) -> Result<Location> {
 		let expected = path_chunk(self.repository, path);
 		if let Some(chunk) = self.chunk(expected)? {
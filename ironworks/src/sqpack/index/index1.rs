@@ -65,7 +65,7 @@ impl Index1 {
 			.rsplitn(2, '/')
 			.map(|segment| crc32(segment.as_bytes()));
 		match (segments.next(), segments.next()) {
-			(Some(file), Some(directory)) => Some(u64::from(directory) << 32 | u64::from(file)),
+			(Some(file), Some(directory)) => Some((u64::from(directory) << 32) | u64::from(file)),
 			_ => None,
 		}
 	}
@@ -86,13 +86,27 @@ impl Index1 {
 		self.locate(hash, None)
 	}
 
-	fn locate(&self, hash: u64, path: Option<&str>) -> Option<(FileMetadata, Option<u64>)> {
-		let entry = self
-			.indexes
+	/// Every file recorded under the hash, including each file sharing it.
+	pub fn find_all(&self, hash: u64) -> Vec<(FileMetadata, Option<u64>)> {
+		self.entry(hash).map_or_else(Vec::new, |entry| {
+			shared::resolve_all(
+				&entry.file_metadata,
+				&self.synonyms,
+				&self.offsets,
+				|recorded| recorded == hash,
+			)
+		})
+	}
+
+	fn entry(&self, hash: u64) -> Option<&Entry> {
+		self.indexes
 			.binary_search_by_key(&hash, |entry| entry.hash)
 			.map(|found| &self.indexes[found])
-			.ok()?;
+			.ok()
+	}
 
+	fn locate(&self, hash: u64, path: Option<&str>) -> Option<(FileMetadata, Option<u64>)> {
+		let entry = self.entry(hash)?;
 		shared::resolve(&entry.file_metadata, &self.synonyms, &self.offsets, path)
 	}
 }
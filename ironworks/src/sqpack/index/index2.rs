@@ -74,18 +74,31 @@ impl Index2 {
 	/// Whether the chunk records the hash as shared between colliding files, which is the one case
 	/// a lookup by hash alone cannot answer even though the file is present.
 	pub fn is_shared(&self, hash: u32) -> bool {
-		self.indexes
-			.binary_search_by_key(&hash, |entry| entry.hash)
-			.is_ok_and(|found| self.indexes[found].file_metadata.is_synonym)
+		self.entry(hash)
+			.is_some_and(|entry| entry.file_metadata.is_synonym)
 	}
 
-	fn locate(&self, hash: u32, path: Option<&str>) -> Option<(FileMetadata, Option<u64>)> {
-		let entry = self
-			.indexes
+	/// Every file recorded under the hash, including each file sharing it.
+	pub fn find_all(&self, hash: u32) -> Vec<(FileMetadata, Option<u64>)> {
+		self.entry(hash).map_or_else(Vec::new, |entry| {
+			shared::resolve_all(
+				&entry.file_metadata,
+				&self.synonyms,
+				&self.offsets,
+				|recorded| recorded & u64::from(u32::MAX) == u64::from(hash),
+			)
+		})
+	}
+
+	fn entry(&self, hash: u32) -> Option<&Entry> {
+		self.indexes
 			.binary_search_by_key(&hash, |entry| entry.hash)
 			.map(|found| &self.indexes[found])
-			.ok()?;
+			.ok()
+	}
 
+	fn locate(&self, hash: u32, path: Option<&str>) -> Option<(FileMetadata, Option<u64>)> {
+		let entry = self.entry(hash)?;
 		shared::resolve(&entry.file_metadata, &self.synonyms, &self.offsets, path)
 	}
 }
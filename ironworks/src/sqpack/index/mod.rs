@@ -4,4 +4,5 @@ mod index1;
 mod index2;
 mod shared;
 
+pub(super) use index::path_chunk;
 pub use index::{Index, IndexEntry, IndexHash, Location};
@@ -70,7 +70,9 @@ impl FileMetadata {
 #[derive(BinRead, Debug)]
 #[br(little)]
 pub struct Synonym {
-	#[br(pad_before = 8)]
+	// `.index2` records a 32-bit hash followed by 4 bytes of padding.
+	hash: u64,
+
 	pub file_metadata: FileMetadata,
 
 	conflict_index: u32,
@@ -120,6 +122,28 @@ pub fn resolve(
 		false => metadata,
 	};
 
+	Some(sized(metadata, offsets))
+}
+
+/// Every file a located entry names. A shared hash names each file its synonym records list under
+/// that hash, as selected by `recorded`.
+pub fn resolve_all(
+	metadata: &FileMetadata,
+	synonyms: &[Synonym],
+	offsets: &BTreeSet<(u8, u64)>,
+	recorded: impl Fn(u64) -> bool,
+) -> Vec<(FileMetadata, Option<u64>)> {
+	match metadata.is_synonym {
+		true => synonyms
+			.iter()
+			.filter(|synonym| recorded(synonym.hash))
+			.map(|synonym| sized(&synonym.file_metadata, offsets))
+			.collect(),
+		false => vec![sized(metadata, offsets)],
+	}
+}
+
+fn sized(metadata: &FileMetadata, offsets: &BTreeSet<(u8, u64)>) -> (FileMetadata, Option<u64>) {
 	// Look up the offset after this meta, if any exists. The result's data
 	// file ID is double checked to ensure we don't return cross-dat offsets,
 	// which can occur if the requested file is the last file in a dat, but
@@ -132,7 +156,7 @@ pub fn resolve(
 			false => None,
 		});
 
-	Some((metadata.clone(), size))
+	(metadata.clone(), size)
 }
 
 #[cfg(test)]
@@ -4,18 +4,24 @@ mod block;
 mod file;
 mod index;
 mod install;
+mod path_database;
 mod resource;
 mod sqpack;
+mod verify;
 mod vinstall;
+mod write;
 
 pub use {
 	block::{BlockMetadata, BlockPayload, BlockStream},
 	file::{File, FileKind},
 	index::{IndexEntry, IndexHash, Location},
 	install::Install,
+	path_database::{CategoryListing, PathDatabase},
 	resource::Resource,
 	sqpack::SqPack,
+	verify::{Issue, Problem, Progress},
 	vinstall::{VInstall, Vfs},
+	write::{Package, Writer},
 };
 
 #[cfg(test)]
@@ -27,6 +33,9 @@ mod test {
 		fn assert_send<T: Send>() {}
 		assert_send::<File<()>>();
 		assert_send::<SqPack<()>>();
+		assert_send::<Writer>();
+		assert_send::<Package>();
+		assert_send::<PathDatabase>();
 	}
 
 	#[test]
@@ -34,5 +43,8 @@ mod test {
 		fn assert_sync<T: Sync>() {}
 		assert_sync::<File<()>>();
 		assert_sync::<SqPack<()>>();
+		assert_sync::<Writer>();
+		assert_sync::<Package>();
+		assert_sync::<PathDatabase>();
 	}
 }
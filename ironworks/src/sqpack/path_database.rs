@@ -0,0 +1,216 @@
+use std::{
+	collections::{BTreeMap, HashMap},
+	io::BufRead,
+};
+
+use crate::error::Result;
+
+use super::{
+	index::{IndexEntry, IndexHash},
+	sqpack::path_metadata,
+};
+
+/// Database of known file paths, used to resolve index hashes back to the
+/// paths they were built from.
+#[derive(Debug, Default)]
+pub struct PathDatabase {
+	paths: HashMap<(u8, u8, IndexHash), String>,
+	directories: HashMap<(u8, u8, u32), String>,
+}
+
+/// Resolved listing of the entries within a single repository and category.
+#[derive(Debug, Default)]
+pub struct CategoryListing {
+	/// Entries with a known path, keyed by that path.
+	pub known: BTreeMap<String, IndexEntry>,
+	/// Unknown entries whose directory is known, keyed by directory path.
+	pub directories: BTreeMap<String, Vec<IndexEntry>>,
+	/// Unknown entries whose directory is also unknown, keyed by directory hash.
+	pub unknown_directories: BTreeMap<u32, Vec<IndexEntry>>,
+	/// Unknown entries that carry no directory information, i.e. those from
+	/// `.index2` files.
+	pub unknown: Vec<IndexEntry>,
+}
+
+impl PathDatabase {
+	/// Build an empty path database.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Build a path database from a newline-separated list of paths. CSV dumps
+	/// with the path as their final column are also accepted.
+	pub fn read(reader: impl BufRead) -> Result<Self> {
+		let mut database = Self::new();
+		for line in reader.lines() {
+			let line = line?;
+			let path = line.rsplit(',').next().unwrap_or_default();
+			database.add_path(path);
+		}
+		Ok(database)
+	}
+
+	/// Add a path to the database.
+	#[must_use]
+	pub fn with_path(mut self, path: &str) -> Self {
+		self.add_path(path);
+		self
+	}
+
+	/// Add a path to the database. Blank lines and paths outside of any known
+	/// category are ignored.
+	pub fn add_path(&mut self, path: &str) {
+		let path = path.trim().to_lowercase();
+		let Ok((repository, category)) = path_metadata(&path) else {
+			return;
+		};
+
+		let (split, whole) = IndexHash::of(&path);
+		if let Some(split) = split {
+			self.paths
+				.insert((repository, category, split), path.clone());
+		}
+		self.paths
+			.insert((repository, category, whole), path.clone());
+
+		if let Some((directory, _)) = path.rsplit_once('/') {
+			self.directories.insert(
+				(repository, category, IndexHash::directory(directory)),
+				directory.to_string(),
+			);
+		}
+	}
+
+	/// Number of distinct paths in the database.
+	pub fn len(&self) -> usize {
+		// Every path is recorded under its whole hash.
+		self.paths
+			.keys()
+			.filter(|(_, _, hash)| matches!(hash, IndexHash::Whole(_)))
+			.count()
+	}
+
+	/// Check if the database contains no paths.
+	pub fn is_empty(&self) -> bool {
+		self.paths.is_empty()
+	}
+
+	/// Get the path for an index entry, if known.
+	pub fn path(&self, entry: &IndexEntry) -> Option<&str> {
+		self.paths
+			.get(&(entry.repository, entry.category, entry.hash))
+			.map(String::as_str)
+	}
+
+	/// Get the directory path for an index entry, if known. Only `.index`
+	/// entries record their directory.
+	pub fn directory(&self, entry: &IndexEntry) -> Option<&str> {
+		let IndexHash::Split(hash) = entry.hash else {
+			return None;
+		};
+
+		self.directories
+			.get(&(entry.repository, entry.category, directory_hash(hash)))
+			.map(String::as_str)
+	}
+
+	/// Resolve a set of index entries, i.e. those from [`SqPack::entries`](super::SqPack::entries),
+	/// into a listing per repository and category.
+	pub fn resolve(
+		&self,
+		entries: impl IntoIterator<Item = IndexEntry>,
+	) -> BTreeMap<(u8, u8), CategoryListing> {
+		let mut listings = BTreeMap::<_, CategoryListing>::new();
+
+		for entry in entries {
+			let listing = listings
+				.entry((entry.repository, entry.category))
+				.or_default();
+
+			if let Some(path) = self.path(&entry) {
+				listing.known.insert(path.to_string(), entry);
+				continue;
+			}
+
+			match (entry.hash, self.directory(&entry)) {
+				(_, Some(directory)) => listing
+					.directories
+					.entry(directory.to_string())
+					.or_default()
+					.push(entry),
+				(IndexHash::Split(hash), None) => listing
+					.unknown_directories
+					.entry(directory_hash(hash))
+					.or_default()
+					.push(entry),
+				(IndexHash::Whole(_), None) => listing.unknown.push(entry),
+			}
+		}
+
+		listings
+	}
+}
+
+fn directory_hash(hash: u64) -> u32 {
+	(hash >> 32) as u32
+}
+
+#[cfg(test)]
+mod test {
+	use crate::sqpack::{IndexEntry, IndexHash};
+
+	use super::PathDatabase;
+
+	fn entries(path: &str) -> [IndexEntry; 2] {
+		let (split, whole) = IndexHash::of(path);
+		[split.unwrap(), whole].map(|hash| IndexEntry {
+			repository: 0,
+			category: 0x0a,
+			chunk: 0,
+			hash,
+		})
+	}
+
+	#[test]
+	fn reads_path_lists() {
+		let list = "exd/root.exl\r\n\nEXD/Item.exh\nnot/a/category.txt\n10,1,2,3,exd/action.exh\n";
+		let database = PathDatabase::read(list.as_bytes()).unwrap();
+		assert_eq!(database.len(), 3);
+
+		let [split, whole] = entries("exd/item.exh");
+		assert_eq!(database.path(&split), Some("exd/item.exh"));
+		assert_eq!(database.path(&whole), Some("exd/item.exh"));
+	}
+
+	#[test]
+	fn resolves_known_and_unknown_entries() {
+		let database = PathDatabase::new().with_path("exd/root.exl");
+
+		let listings = database.resolve(
+			entries("exd/root.exl")
+				.into_iter()
+				.chain(entries("exd/item.exh")),
+		);
+		let listing = &listings[&(0, 0x0a)];
+
+		assert_eq!(listing.known.len(), 1);
+		assert!(listing.known.contains_key("exd/root.exl"));
+		assert_eq!(listing.directories["exd"], [entries("exd/item.exh")[0]]);
+		assert_eq!(listing.unknown, [entries("exd/item.exh")[1]]);
+		assert!(listing.unknown_directories.is_empty());
+	}
+
+	#[test]
+	fn groups_unknown_directories_by_hash() {
+		let database = PathDatabase::new();
+		let [split, _] = entries("exd/sub/item.exh");
+
+		let listings = database.resolve([split]);
+		let listing = &listings[&(0, 0x0a)];
+
+		assert_eq!(
+			listing.unknown_directories[&IndexHash::directory("exd/sub")],
+			[split]
+		);
+	}
+}
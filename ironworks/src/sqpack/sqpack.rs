@@ -11,6 +11,7 @@ use crate::{
 use super::{
 	file::File,
 	index::{Index, IndexEntry, IndexHash},
+	verify::{self, Issue, Problem, Progress},
 };
 
 const CATEGORIES: &[Option<&str>] = &[
@@ -37,7 +38,7 @@ const CATEGORIES: &[Option<&str>] = &[
 ];
 
 // While this is pretty trivially computed, even just going to ex9 gives us a lead time of a good 10 years or so.
-const REPOSITORIES: &[&str] = &[
+pub(super) const REPOSITORIES: &[&str] = &[
 	"ffxiv", "ex1", "ex2", "ex3", "ex4", "ex5", "ex6", "ex7", "ex8", "ex9",
 ];
 
@@ -84,7 +85,7 @@ impl<R: sqpack::Resource> SqPack<R> {
 		// Build a File representation.
 		let dat = self.resource.file(repository, category, location)?;
 
-		// TODO: Cache files? Tempted to say it's the IW struct's responsibility. Is it even possible here with streams?
+		// Decoded file data is cached at the Ironworks level, see `Ironworks::with_cache`.
 		File::new(dat)
 	}
 
@@ -153,36 +154,102 @@ impl<R: sqpack::Resource> SqPack<R> {
 		Ok(entries)
 	}
 
+	/// Check every file the install records for corruption, comparing each
+	/// file's block table against its dat before decoding it in full. `progress`
+	/// is called after each file is checked.
+	pub fn verify(&self, mut progress: impl FnMut(Progress)) -> Result<Vec<Issue>> {
+		let entries = self.entries()?;
+		let total = entries.len();
+
+		let mut issues = Vec::new();
+		for (index, entry) in entries.into_iter().enumerate() {
+			if let Some(problem) = self.verify_entry(&entry)? {
+				issues.push(Issue { entry, problem });
+			}
+
+			progress(Progress {
+				checked: index + 1,
+				total,
+			});
+		}
+
+		Ok(issues)
+	}
+
+	fn verify_entry(&self, entry: &IndexEntry) -> Result<Option<Problem>> {
+		let locations = match self
+			.indexes
+			.try_get_or_insert((entry.repository, entry.category), || {
+				Index::new(entry.repository, entry.category, self.resource.clone())
+			})?
+			.find_recorded(entry.chunk, entry.hash)
+		{
+			Ok(locations) if locations.is_empty() => {
+				return Ok(Some(Problem::Unlocatable(format!(
+					"no file is recorded under hash {:?}",
+					entry.hash
+				))));
+			}
+			Ok(locations) => locations,
+			Err(error) => return Ok(Some(Problem::Unlocatable(error.to_string()))),
+		};
+
+		// A hash shared between colliding files names each of them, and every one is checked.
+		for location in locations {
+			// The file is opened through to the end of its dat, so that data running past the
+			// next file can be told apart from data running out of the dat entirely.
+			let next = location.size();
+			let dat =
+				match self
+					.resource
+					.file(entry.repository, entry.category, location.unbounded())
+				{
+					Ok(dat) => dat,
+					Err(error) => return Ok(Some(verify::open_problem(error))),
+				};
+
+			if let Some(problem) = verify::check(dat, next) {
+				return Ok(Some(problem));
+			}
+		}
+
+		Ok(None)
+	}
+
 	/// Which repository and category a path resolves to, without touching any index.
 	pub fn locate(&self, path: &str) -> Result<(u8, u8)> {
 		self.path_metadata(&path.to_lowercase())
 	}
 
 	fn path_metadata(&self, path: &str) -> Result<(u8, u8)> {
-		// NOTE: This could be technically-faster by doing that cursed logic the
-		// game does, checking the first 3 characters for category and such - but I
-		// think this is cleaner; especially to read.
+		path_metadata(path)
+	}
+}
 
-		let path_not_found = || Error::NotFound(ErrorValue::Path(path.to_string()));
+/// The repository and category a path is stored in.
+pub(super) fn path_metadata(path: &str) -> Result<(u8, u8)> {
+	// NOTE: This could be technically-faster by doing that cursed logic the
+	// game does, checking the first 3 characters for category and such - but I
+	// think this is cleaner; especially to read.
 
-		let mut split = path.split('/');
-		let (Some(category_segment), Some(repository_segment)) = (split.next(), split.next())
-		else {
-			return Err(path_not_found());
-		};
+	let path_not_found = || Error::NotFound(ErrorValue::Path(path.to_string()));
 
-		let repository = REPOSITORIES
-			.iter()
-			.position(|&repository| repository == repository_segment)
-			.unwrap_or(0);
+	let mut split = path.split('/');
+	let (Some(category_segment), Some(repository_segment)) = (split.next(), split.next()) else {
+		return Err(path_not_found());
+	};
 
-		let category = CATEGORIES
-			.iter()
-			.position(|&category| category == Some(category_segment))
-			.ok_or_else(path_not_found)?;
+	let repository = REPOSITORIES
+		.iter()
+		.position(|&repository| repository == repository_segment)
+		.unwrap_or(0);
 
-		Ok((repository.try_into().unwrap(), category.try_into().unwrap()))
-	}
+	let category = CATEGORIES
+		.iter()
+		.position(|&category| category == Some(category_segment))
+		.ok_or_else(path_not_found)?;
+
+	Ok((repository.try_into().unwrap(), category.try_into().unwrap()))
 }
 
 // TODO: work out the resource story for this because it's gonna get cluttery if im not careful
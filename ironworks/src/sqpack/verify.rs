@@ -0,0 +1,429 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+use binrw::BinRead;
+
+use crate::error::{Error, Result};
+
+use super::{
+	block::BlockHeader,
+	file::{File, FileKind, Header, StoredBlock, stored_blocks},
+	index::IndexEntry,
+};
+
+/// A problem found with a file while verifying an install.
+#[derive(Debug)]
+pub struct Issue {
+	/// The index entry of the affected file.
+	pub entry: IndexEntry,
+	/// What is wrong with the file.
+	pub problem: Problem,
+}
+
+/// Kinds of corruption that can be found while verifying an install.
+#[derive(Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Problem {
+	/// The index entry could not be resolved to a location in a dat file.
+	Unlocatable(String),
+	/// The dat file holding the file's data could not be opened.
+	MissingData(String),
+	/// The file's data runs past the end of its dat file.
+	Truncated,
+	/// The file's data runs into the next file stored in its dat.
+	Overlapping {
+		/// End of the file's data, relative to the start of the file.
+		end: u64,
+		/// Start of the next file, relative to the start of the file.
+		next: u64,
+	},
+	/// A block starts inside the file's header or the block before it.
+	OverlappingBlocks {
+		/// Index of the block in the file's block table.
+		block: usize,
+	},
+	/// A block holds more data than the file's block table sets aside for it.
+	OversizedBlock {
+		/// Index of the block in the file's block table.
+		block: usize,
+		/// Space the block table sets aside for the block.
+		recorded: u64,
+		/// Space the block's own header says it occupies.
+		stored: u64,
+	},
+	/// The file's headers or blocks could not be decoded.
+	Undecodable(String),
+	/// The file decoded to a different size than its header records.
+	SizeMismatch {
+		/// Size recorded in the file header.
+		expected: u64,
+		/// Size of the decoded data.
+		actual: u64,
+	},
+}
+
+/// Progress of an ongoing verification.
+#[derive(Debug, Clone, Copy)]
+pub struct Progress {
+	/// Number of files checked so far.
+	pub checked: usize,
+	/// Total number of files that will be checked.
+	pub total: usize,
+}
+
+/// Check a file stored at the start of `reader`, reporting any problem found.
+/// `reader` runs to the end of the file's dat, and `next` is the start of the
+/// following file in that dat, if any, relative to the start of this one.
+pub fn check(mut reader: impl Read + Seek, next: Option<u64>) -> Option<Problem> {
+	check_file(&mut reader, next).unwrap_or_else(|error| Some(problem(error)))
+}
+
+fn check_file(reader: &mut (impl Read + Seek), next: Option<u64>) -> Result<Option<Problem>> {
+	let available = reader.seek(SeekFrom::End(0))?;
+	reader.seek(SeekFrom::Start(0))?;
+
+	let header = Header::read(reader)?;
+	let blocks = stored_blocks(&mut *reader, &header)?;
+
+	// The block table is checked against the dat before any block is read, so that
+	// a bad table is reported as such rather than as whatever it leads to.
+	if let Some(problem) = check_layout(&header, &blocks, available, next) {
+		return Ok(Some(problem));
+	}
+
+	for (index, block) in blocks.iter().enumerate() {
+		reader.seek(SeekFrom::Start(block.offset))?;
+		let stored = BlockHeader::read(reader)?.stored_size();
+		if stored > block.size {
+			return Ok(Some(Problem::OversizedBlock {
+				block: index,
+				recorded: block.size,
+				stored,
+			}));
+		}
+	}
+
+	reader.seek(SeekFrom::Start(0))?;
+	let actual = io::copy(&mut File::new(&mut *reader)?, &mut io::sink())?;
+
+	// Empty files have no data to measure, whatever their header claims.
+	let expected = u64::from(header.raw_file_size);
+	if header.kind != FileKind::Empty && actual != expected {
+		return Ok(Some(Problem::SizeMismatch { expected, actual }));
+	}
+
+	Ok(None)
+}
+
+fn check_layout(
+	header: &Header,
+	blocks: &[StoredBlock],
+	available: u64,
+	next: Option<u64>,
+) -> Option<Problem> {
+	// Tables are not guaranteed to list blocks in the order they are stored.
+	let mut order = (0..blocks.len()).collect::<Vec<_>>();
+	order.sort_by_key(|&index| blocks[index].offset);
+
+	let mut end = u64::from(header.size);
+	for index in order {
+		let block = &blocks[index];
+		if block.offset < end {
+			return Some(Problem::OverlappingBlocks { block: index });
+		}
+		end = block.offset + block.size;
+	}
+
+	if end > available {
+		return Some(Problem::Truncated);
+	}
+
+	match next {
+		Some(next) if end > next => Some(Problem::Overlapping { end, next }),
+		_ => None,
+	}
+}
+
+/// Classify an error opening the dat file holding a file's data.
+pub fn open_problem(error: Error) -> Problem {
+	match problem(error) {
+		Problem::Undecodable(message) => Problem::MissingData(message),
+		other => other,
+	}
+}
+
+fn problem(error: Error) -> Problem {
+	let truncated = match &error {
+		Error::Resource(inner) => {
+			inner
+				.downcast_ref::<io::Error>()
+				.is_some_and(|error| error.kind() == io::ErrorKind::UnexpectedEof)
+				|| inner
+					.downcast_ref::<binrw::Error>()
+					.is_some_and(binrw::Error::is_eof)
+		}
+		_ => false,
+	};
+
+	match truncated {
+		true => Problem::Truncated,
+		false => Problem::Undecodable(error.to_string()),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::{
+		io::{self, Cursor},
+		path::Path,
+	};
+
+	use crate::sqpack::{FileKind, Package, SqPack, VInstall, Vfs, Writer};
+
+	use super::{Issue, Problem};
+
+	/// Start of the first entry in a written dat, following its two headers.
+	const FIRST_ENTRY: usize = 0x800;
+
+	/// Package with its dat files altered as they are read.
+	struct Altered<F> {
+		package: Package,
+		alter: F,
+	}
+
+	impl<F: Fn(&mut Vec<u8>)> Vfs for Altered<F> {
+		type File = Cursor<Vec<u8>>;
+
+		fn exists(&self, path: impl AsRef<Path>) -> bool {
+			self.package.exists(path)
+		}
+
+		fn open(&self, path: impl AsRef<Path>) -> io::Result<Self::File> {
+			let mut data = self.package.read(&path)?;
+			if path.as_ref().extension().is_some_and(|ext| ext == "dat0") {
+				(self.alter)(&mut data);
+			}
+			Ok(Cursor::new(data))
+		}
+	}
+
+	/// Files without any `.index`, so that they are read through `.index2`.
+	struct WholeOnly<V>(V);
+
+	impl<V: Vfs> Vfs for WholeOnly<V> {
+		type File = V::File;
+
+		fn exists(&self, path: impl AsRef<Path>) -> bool {
+			self.0.exists(path)
+		}
+
+		fn open(&self, path: impl AsRef<Path>) -> io::Result<Self::File> {
+			match path.as_ref().extension().is_some_and(|ext| ext == "index") {
+				true => Err(io::ErrorKind::NotFound.into()),
+				false => self.0.open(path),
+			}
+		}
+	}
+
+	// Incompressible data.
+	fn noise(length: usize) -> Vec<u8> {
+		let mut state = 1u32;
+		(0..length)
+			.map(|_| {
+				state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+				(state >> 16) as u8
+			})
+			.collect()
+	}
+
+	fn package() -> Package {
+		// Spans several blocks.
+		let mut writer = Writer::new();
+		writer.add_file("exd/noise.bin", &noise(40_000)).unwrap();
+		writer.build().unwrap()
+	}
+
+	fn verify(package: Package, alter: impl Fn(&mut Vec<u8>)) -> Vec<Issue> {
+		SqPack::new(VInstall::at_sqpack(Altered { package, alter }))
+			.verify(|_| {})
+			.unwrap()
+	}
+
+	fn problems(issues: Vec<Issue>) -> Vec<Problem> {
+		issues.into_iter().map(|issue| issue.problem).collect()
+	}
+
+	/// Offset of a field of a block's entry in a standard file's block table.
+	fn block_info(entry: usize, block: usize, field: usize) -> usize {
+		entry + 24 + block * 8 + field
+	}
+
+	fn read_u16(data: &[u8], offset: usize) -> u16 {
+		u16::from_le_bytes([data[offset], data[offset + 1]])
+	}
+
+	fn write_u16(data: &mut [u8], offset: usize, value: u16) {
+		data[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+	}
+
+	#[test]
+	fn intact_installs_have_no_issues() {
+		let sqpack = SqPack::new(VInstall::at_sqpack(package()));
+
+		let mut progress = vec![];
+		let issues = sqpack.verify(|update| progress.push(update)).unwrap();
+
+		assert!(issues.is_empty(), "{issues:?}");
+		assert_eq!(progress.len(), 1);
+		assert_eq!(progress[0].checked, 1);
+		assert_eq!(progress[0].total, 1);
+	}
+
+	#[test]
+	fn intact_files_of_every_kind_have_no_issues() {
+		let mut model = Vec::new();
+		model.extend(0x0100_0005u32.to_le_bytes());
+		model.extend([16u32, 16].iter().flat_map(|size| size.to_le_bytes()));
+		model.extend([1u16, 1].iter().flat_map(|count| count.to_le_bytes()));
+		model.extend([0u8; 48]);
+		model.extend([1, 0, 0, 0]);
+		model.extend(noise(32));
+
+		let mut texture = Vec::new();
+		texture.extend(0x0080_0000u32.to_le_bytes());
+		texture.extend(0x1450u32.to_le_bytes());
+		texture.extend([32u16, 32, 1].iter().flat_map(|size| size.to_le_bytes()));
+		texture.extend([1, 1]);
+		texture.extend([0u8; 12]);
+		texture.extend(80u32.to_le_bytes());
+		texture.extend([0u8; 48]);
+		texture.extend(noise(4_096));
+
+		let mut writer = Writer::new();
+		writer.add_file("exd/noise.bin", &noise(40_000)).unwrap();
+		writer.add_file("exd/empty.bin", &[]).unwrap();
+		writer
+			.add_file_as("exd/model.mdl", FileKind::Model, &model)
+			.unwrap();
+		writer
+			.add_file_as("exd/texture.tex", FileKind::Texture, &texture)
+			.unwrap();
+
+		let issues = verify(writer.build().unwrap(), |_| {});
+		assert!(issues.is_empty(), "{issues:?}");
+	}
+
+	#[test]
+	fn truncated_files_are_reported() {
+		let dat_size = package().read("ffxiv/0a0000.win32.dat0").unwrap().len();
+
+		// Cut into the file's blocks, and then off entirely.
+		for dat_size in [dat_size - 1_000, 0x800] {
+			let issues = verify(package(), |data| data.truncate(dat_size));
+			assert_eq!(problems(issues), [Problem::Truncated]);
+		}
+	}
+
+	#[test]
+	fn block_tables_running_past_the_dat_are_reported() {
+		// The last block of the last file is recorded as running on past the dat's end.
+		let issues = verify(package(), |data| {
+			let offset = block_info(FIRST_ENTRY, 2, 4);
+			let size = read_u16(data, offset);
+			write_u16(data, offset, size + 0x80);
+		});
+		assert_eq!(problems(issues), [Problem::Truncated]);
+	}
+
+	#[test]
+	fn files_running_into_the_next_are_reported() {
+		let mut writer = Writer::new();
+		writer.add_file("exd/a.bin", &noise(1_000)).unwrap();
+		writer.add_file("exd/b.bin", &noise(1_000)).unwrap();
+		let package = writer.build().unwrap();
+
+		// The first file's only block is recorded as running on into the second file.
+		let issues = verify(package, |data| {
+			let offset = block_info(FIRST_ENTRY, 0, 4);
+			let size = read_u16(data, offset);
+			write_u16(data, offset, size + 0x80);
+		});
+
+		let [Issue { problem, .. }] = &issues[..] else {
+			panic!("expected one issue, got {issues:?}");
+		};
+		let Problem::Overlapping { end, next } = *problem else {
+			panic!("expected an overlap, got {problem:?}");
+		};
+		assert_eq!(end, next + 0x80);
+	}
+
+	#[test]
+	fn overlapping_blocks_are_reported() {
+		// The second block is recorded as starting inside the first.
+		let issues = verify(package(), |data| {
+			let offset = block_info(FIRST_ENTRY, 1, 0);
+			data[offset..offset + 4].copy_from_slice(&0x80u32.to_le_bytes());
+		});
+		assert_eq!(problems(issues), [Problem::OverlappingBlocks { block: 1 }]);
+	}
+
+	#[test]
+	fn oversized_blocks_are_reported() {
+		// The first block is recorded as smaller than the data it holds.
+		let issues = verify(package(), |data| {
+			let offset = block_info(FIRST_ENTRY, 0, 4);
+			let size = read_u16(data, offset);
+			write_u16(data, offset, size - 0x80);
+		});
+
+		let [Issue { problem, .. }] = &issues[..] else {
+			panic!("expected one issue, got {issues:?}");
+		};
+		let Problem::OversizedBlock {
+			block,
+			recorded,
+			stored,
+		} = *problem
+		else {
+			panic!("expected an oversized block, got {problem:?}");
+		};
+		assert_eq!(block, 0);
+		assert!(stored > recorded, "{problem:?}");
+	}
+
+	#[test]
+	fn every_file_sharing_a_hash_is_checked() {
+		// These paths collide in `.index2`, where they are recorded as synonyms.
+		let icon = "ui/icon/150000/de/150751_hr1.tex";
+		let uld = "ui/uld/turnbreaktitle.uld";
+
+		let verify = |alter: fn(&mut Vec<u8>)| {
+			let mut writer = Writer::new();
+			writer
+				.add_file_as(icon, FileKind::Standard, b"icon")
+				.unwrap();
+			writer.add_file(uld, b"uld").unwrap();
+			let package = writer.build().unwrap();
+
+			SqPack::new(VInstall::at_sqpack(WholeOnly(Altered { package, alter })))
+				.verify(|_| {})
+				.unwrap()
+		};
+
+		assert!(verify(|_| {}).is_empty());
+
+		// Each file is a 128-byte header followed by a single 128-byte block, so the second
+		// file's raw size is recorded 8 bytes into the dat's third 128-byte unit of entries.
+		let issues = verify(|data| {
+			let offset = FIRST_ENTRY + 0x100 + 8;
+			data[offset..offset + 4].copy_from_slice(&99u32.to_le_bytes());
+		});
+		assert_eq!(
+			problems(issues),
+			[Problem::SizeMismatch {
+				expected: 99,
+				actual: 3
+			}]
+		);
+	}
+}
@@ -132,12 +132,20 @@ impl<V: Vfs> Resource for VInstall<V> {
 		)?;
 		let mut file = io::BufReader::new(self.vfs().open(path)?);
 
-		let offset = u64::from(location.offset());
+		let offset = location.offset();
 		// Resolve the size early in case we need to seek to find the end. Using
 		// longhand here so I can shortcut seek failures.
 		let size = match location.size() {
-			Some(size) => u64::from(size),
-			None => file.seek(io::SeekFrom::End(0))? - offset,
+			Some(size) => size,
+			None => file
+				.seek(io::SeekFrom::End(0))?
+				.checked_sub(offset)
+				.ok_or_else(|| {
+					io::Error::new(
+						io::ErrorKind::UnexpectedEof,
+						"file offset is past the end of the dat",
+					)
+				})?,
 		};
 
 		file.seek(io::SeekFrom::Start(offset))?;
@@ -0,0 +1,63 @@
+use std::io::{Cursor, Write};
+
+use binrw::BinWriterExt;
+use flate2::{Compression, write::DeflateEncoder};
+
+use crate::error::Result;
+
+/// Largest amount of file data stored in a single block.
+const MAX_BLOCK_SIZE: usize = 16_000;
+
+/// Compressed size recorded for blocks that are stored as-is.
+const UNCOMPRESSED_MARKER: u32 = 32_000;
+
+const BLOCK_HEADER_SIZE: u32 = 16;
+
+/// Blocks, file entries, and file headers all start on 128-byte boundaries.
+pub const ALIGNMENT: usize = 128;
+
+#[derive(Debug)]
+pub struct Block {
+	/// The block as stored, including its header and trailing padding.
+	pub data: Vec<u8>,
+	pub decompressed_size: usize,
+}
+
+/// Round a size up to the next alignment boundary.
+pub fn align(size: usize) -> usize {
+	size.next_multiple_of(ALIGNMENT)
+}
+
+/// Split data into blocks, compressing each where it is worth doing so.
+pub fn write_blocks(input: &[u8]) -> Result<Vec<Block>> {
+	input.chunks(MAX_BLOCK_SIZE).map(write_block).collect()
+}
+
+fn write_block(input: &[u8]) -> Result<Block> {
+	let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+	encoder.write_all(input)?;
+	let compressed = encoder.finish()?;
+
+	// Data that doesn't shrink is stored raw. This also keeps every compressed
+	// size under the threshold the reader uses to spot uncompressed blocks.
+	let (compressed_size, payload) = match compressed.len() < input.len() {
+		true => (u32::try_from(compressed.len()).unwrap(), &compressed[..]),
+		false => (UNCOMPRESSED_MARKER, input),
+	};
+
+	let size = align(BLOCK_HEADER_SIZE as usize + payload.len());
+	let mut writer = Cursor::new(Vec::with_capacity(size));
+	writer.write_le(&BLOCK_HEADER_SIZE)?;
+	writer.write_le(&0u32)?;
+	writer.write_le(&compressed_size)?;
+	writer.write_le(&u32::try_from(input.len()).unwrap())?;
+	writer.write_all(payload)?;
+
+	let mut data = writer.into_inner();
+	data.resize(size, 0);
+
+	Ok(Block {
+		data,
+		decompressed_size: input.len(),
+	})
+}
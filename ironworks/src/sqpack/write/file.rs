@@ -0,0 +1,373 @@
+use std::io::{Cursor, Write};
+
+use binrw::{BinRead, BinWriterExt, binread};
+
+use crate::{
+	error::{Error, ErrorValue, Result},
+	sqpack::{FileKind, file::array_size},
+};
+
+use super::block::{Block, align, write_blocks};
+
+const HEADER_SIZE: usize = 24;
+const MAX_LODS: usize = 3;
+const MAX_MIP_LEVELS: usize = 13;
+
+/// The kind a file is stored as when not otherwise specified.
+pub fn infer_kind(path: &str, data: &[u8]) -> FileKind {
+	if data.is_empty() {
+		return FileKind::Empty;
+	}
+
+	match path.rsplit_once('.').map(|(_, extension)| extension) {
+		Some("mdl") => FileKind::Model,
+		Some("tex" | "atex") => FileKind::Texture,
+		_ => FileKind::Standard,
+	}
+}
+
+/// Encode file data into a dat entry of the given kind.
+pub fn write_file(path: &str, kind: FileKind, data: &[u8]) -> Result<Vec<u8>> {
+	if u32::try_from(data.len()).is_err() {
+		return Err(invalid(path, "File is too large to store in SqPack."));
+	}
+
+	let entry = match kind {
+		FileKind::Empty => match data.is_empty() {
+			true => empty()?,
+			false => return Err(invalid(path, "Empty files cannot contain data.")),
+		},
+		FileKind::Standard => standard(data)?,
+		FileKind::Model => model(path, data)?,
+		FileKind::Texture => texture(path, data)?,
+	};
+
+	Ok(entry)
+}
+
+fn invalid(path: &str, message: impl Into<String>) -> Error {
+	Error::Invalid(ErrorValue::Path(path.into()), message.into())
+}
+
+/// Header shared by all file entries.
+struct EntryHeader {
+	size: usize,
+	kind: FileKind,
+	raw_file_size: usize,
+	stored_size: usize,
+	// Model entries store their version in this slot instead.
+	block_count: u32,
+}
+
+impl EntryHeader {
+	fn write(&self, writer: &mut Cursor<Vec<u8>>) -> Result<()> {
+		// ASSUMPTION: Both fields following the raw size are the stored data size
+		// in 128-byte units. The reader skips them, and so does the game.
+		let stored_units = u32::try_from(self.stored_size / 128).unwrap();
+
+		writer.write_le(&u32::try_from(self.size).unwrap())?;
+		writer.write_le(&(self.kind as u32))?;
+		writer.write_le(&u32::try_from(self.raw_file_size).unwrap())?;
+		writer.write_le(&stored_units)?;
+		writer.write_le(&stored_units)?;
+		writer.write_le(&self.block_count)?;
+		Ok(())
+	}
+}
+
+/// Pad the header out to its declared size and append the stored data.
+fn finish<'a>(
+	mut writer: Cursor<Vec<u8>>,
+	header_size: usize,
+	data: impl IntoIterator<Item = &'a [u8]>,
+) -> Result<Vec<u8>> {
+	let entry = writer.get_mut();
+	entry.resize(header_size, 0);
+	for bytes in data {
+		entry.write_all(bytes)?;
+	}
+	entry.resize(align(entry.len()), 0);
+	Ok(writer.into_inner())
+}
+
+fn blocks_size(blocks: &[Block]) -> usize {
+	blocks.iter().map(|block| block.data.len()).sum()
+}
+
+fn empty() -> Result<Vec<u8>> {
+	let header_size = align(HEADER_SIZE);
+	let mut writer = Cursor::new(Vec::with_capacity(header_size));
+	EntryHeader {
+		size: header_size,
+		kind: FileKind::Empty,
+		raw_file_size: 0,
+		stored_size: 0,
+		block_count: 0,
+	}
+	.write(&mut writer)?;
+
+	finish(writer, header_size, [])
+}
+
+fn standard(data: &[u8]) -> Result<Vec<u8>> {
+	let blocks = write_blocks(data)?;
+	let header_size = align(HEADER_SIZE + blocks.len() * 8);
+	let stored_size = blocks_size(&blocks);
+
+	let mut writer = Cursor::new(Vec::with_capacity(header_size + stored_size));
+	EntryHeader {
+		size: header_size,
+		kind: FileKind::Standard,
+		raw_file_size: data.len(),
+		stored_size,
+		block_count: u32::try_from(blocks.len()).unwrap(),
+	}
+	.write(&mut writer)?;
+
+	// Block info offsets are relative to the end of the header.
+	let mut offset = 0usize;
+	for block in &blocks {
+		writer.write_le(&u32::try_from(offset).unwrap())?;
+		writer.write_le(&u16::try_from(block.data.len()).unwrap())?;
+		writer.write_le(&u16::try_from(block.decompressed_size).unwrap())?;
+		offset += block.data.len();
+	}
+
+	finish(
+		writer,
+		header_size,
+		blocks.iter().map(|block| &block.data[..]),
+	)
+}
+
+#[binread]
+#[br(little)]
+#[derive(Debug)]
+struct MdlHeader {
+	version: u32,
+	stack_size: u32,
+	runtime_size: u32,
+	vertex_declaration_count: u16,
+	material_count: u16,
+	vertex_offsets: [u32; MAX_LODS],
+	index_offsets: [u32; MAX_LODS],
+	vertex_buffer_sizes: [u32; MAX_LODS],
+	index_buffer_sizes: [u32; MAX_LODS],
+	lod_count: u8,
+	index_buffer_streaming_enabled: u8,
+	edge_geometry_enabled: u8,
+}
+
+const MDL_HEADER_SIZE: usize = 0x44;
+
+fn model(path: &str, data: &[u8]) -> Result<Vec<u8>> {
+	let header = MdlHeader::read(&mut Cursor::new(data))
+		.map_err(|_| invalid(path, "Model header could not be read."))?;
+
+	let slice = |start: usize, size: u32| {
+		let end = start + usize::try_from(size).unwrap();
+		data.get(start..end)
+			.ok_or_else(|| invalid(path, "Model section lies outside the file."))
+	};
+	let lod_slices = |offsets: &[u32; MAX_LODS], sizes: &[u32; MAX_LODS]| {
+		(0..MAX_LODS)
+			.map(|lod| match sizes[lod] {
+				0 => Ok(&data[..0]),
+				size => slice(usize::try_from(offsets[lod]).unwrap(), size),
+			})
+			.collect::<Result<Vec<_>>>()
+	};
+
+	let stack = slice(MDL_HEADER_SIZE, header.stack_size)?;
+	let runtime = slice(MDL_HEADER_SIZE + stack.len(), header.runtime_size)?;
+	let vertex_buffers = lod_slices(&header.vertex_offsets, &header.vertex_buffer_sizes)?;
+	let index_buffers = lod_slices(&header.index_offsets, &header.index_buffer_sizes)?;
+
+	// The .mdl header doesn't record edge geometry directly; it occupies any gap
+	// between a LOD's vertex and index buffers.
+	let edge_geometry_buffers = (0..MAX_LODS)
+		.map(|lod| {
+			let start = header.vertex_offsets[lod] + header.vertex_buffer_sizes[lod];
+			match header.index_offsets[lod].checked_sub(start) {
+				Some(size) if !vertex_buffers[lod].is_empty() && !index_buffers[lod].is_empty() => {
+					slice(usize::try_from(start).unwrap(), size)
+				}
+				_ => Ok(&data[..0]),
+			}
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	// Sections in the order the sqpack model header lists them.
+	let sections = [stack, runtime]
+		.into_iter()
+		.chain(vertex_buffers)
+		.chain(edge_geometry_buffers)
+		.chain(index_buffers)
+		.collect::<Vec<_>>();
+
+	let blocks = sections
+		.iter()
+		.map(|section| write_blocks(section))
+		.collect::<Result<Vec<_>>>()?;
+
+	let block_count = blocks.iter().map(Vec::len).sum::<usize>();
+	let Ok(total_blocks) = u16::try_from(block_count) else {
+		return Err(invalid(
+			path,
+			format!("Model requires {block_count} blocks, more than can be indexed."),
+		));
+	};
+
+	// Header, model header (5 section tables and trailing fields), block sizes.
+	let header_size = align(HEADER_SIZE + 11 * (4 * 3 + 2 * 2) + 8 + usize::from(total_blocks) * 2);
+	let stored_size = blocks.iter().map(|blocks| blocks_size(blocks)).sum();
+
+	let mut writer = Cursor::new(Vec::with_capacity(header_size + stored_size));
+	EntryHeader {
+		size: header_size,
+		kind: FileKind::Model,
+		raw_file_size: data.len(),
+		stored_size,
+		block_count: header.version,
+	}
+	.write(&mut writer)?;
+
+	let u32_of = |size: usize| u32::try_from(size).unwrap();
+	let u16_of = |size: usize| u16::try_from(size).unwrap();
+
+	for section in &sections {
+		writer.write_le(&u32_of(section.len()))?;
+	}
+	for blocks in &blocks {
+		writer.write_le(&u32_of(blocks_size(blocks)))?;
+	}
+	let mut offset = 0;
+	for blocks in &blocks {
+		writer.write_le(&u32_of(offset))?;
+		offset += blocks_size(blocks);
+	}
+	let mut block_index = 0;
+	for blocks in &blocks {
+		writer.write_le(&u16_of(block_index))?;
+		block_index += blocks.len();
+	}
+	for blocks in &blocks {
+		writer.write_le(&u16_of(blocks.len()))?;
+	}
+
+	writer.write_le(&header.vertex_declaration_count)?;
+	writer.write_le(&header.material_count)?;
+	writer.write_le(&header.lod_count)?;
+	writer.write_le(&header.index_buffer_streaming_enabled)?;
+	writer.write_le(&header.edge_geometry_enabled)?;
+	writer.write_le(&0u8)?;
+
+	for block in blocks.iter().flatten() {
+		writer.write_le(&u16_of(block.data.len()))?;
+	}
+
+	finish(
+		writer,
+		header_size,
+		blocks.iter().flatten().map(|block| &block.data[..]),
+	)
+}
+
+#[binread]
+#[br(little)]
+#[derive(Debug)]
+struct TexHeader {
+	attribute: u32,
+	// format: u32,
+	// width: u16,
+	// height: u16,
+	// depth: u16,
+	#[br(pad_before = 10)]
+	mip_levels: u8,
+	array_size: u8,
+	// lod_offsets: [u32; 3],
+	#[br(pad_before = 12)]
+	surface_offsets: [u32; MAX_MIP_LEVELS],
+}
+
+fn texture(path: &str, data: &[u8]) -> Result<Vec<u8>> {
+	let header = TexHeader::read(&mut Cursor::new(data))
+		.map_err(|_| invalid(path, "Texture header could not be read."))?;
+
+	let mip_levels = usize::from(header.mip_levels & 0x7F).clamp(1, MAX_MIP_LEVELS);
+	let offsets = header.surface_offsets[..mip_levels]
+		.iter()
+		.map(|&offset| usize::try_from(offset).unwrap())
+		.chain([data.len()])
+		.collect::<Vec<_>>();
+
+	let in_order = offsets.windows(2).all(|pair| pair[0] <= pair[1]);
+	if offsets[0] < 80 || !in_order {
+		return Err(invalid(path, "Texture surface offsets are out of order."));
+	}
+
+	// The reader expects one surface block per array entry for each mip level,
+	// each of which are written back-to-back. Splitting a mip level's data
+	// evenly satisfies that regardless of the texture's format.
+	let layers = array_size(header.attribute, header.array_size).max(1);
+	let surfaces = offsets
+		.windows(2)
+		.flat_map(|pair| {
+			let (start, size) = (pair[0], pair[1] - pair[0]);
+			(0..layers).map(move |layer| {
+				&data[start + size * layer / layers..start + size * (layer + 1) / layers]
+			})
+		})
+		.collect::<Vec<_>>();
+
+	// The raw .tex header is stored ahead of the first surface's blocks.
+	let raw_header = &data[..offsets[0]];
+	let blocks = surfaces
+		.iter()
+		.map(|surface| write_blocks(surface))
+		.collect::<Result<Vec<_>>>()?;
+
+	let sub_block_count = blocks.iter().map(Vec::len).sum::<usize>();
+	let header_size = align(HEADER_SIZE + surfaces.len() * 20 + sub_block_count * 2);
+	let stored_size = raw_header.len()
+		+ blocks
+			.iter()
+			.map(|blocks| blocks_size(blocks))
+			.sum::<usize>();
+
+	let mut writer = Cursor::new(Vec::with_capacity(header_size + stored_size));
+	EntryHeader {
+		size: header_size,
+		kind: FileKind::Texture,
+		raw_file_size: data.len(),
+		stored_size: align(stored_size),
+		block_count: u32::try_from(surfaces.len()).unwrap(),
+	}
+	.write(&mut writer)?;
+
+	let u32_of = |size: usize| u32::try_from(size).unwrap();
+	let mut offset = raw_header.len();
+	let mut block_offset = 0;
+	for (surface, blocks) in surfaces.iter().zip(&blocks) {
+		let size = blocks_size(blocks);
+		writer.write_le(&u32_of(offset))?;
+		writer.write_le(&u32_of(size))?;
+		writer.write_le(&u32_of(surface.len()))?;
+		writer.write_le(&u32_of(block_offset))?;
+		writer.write_le(&u32_of(blocks.len()))?;
+		offset += size;
+		block_offset += blocks.len();
+	}
+
+	for block in blocks.iter().flatten() {
+		writer.write_le(&u16::try_from(block.data.len()).unwrap())?;
+	}
+
+	finish(
+		writer,
+		header_size,
+		[raw_header]
+			.into_iter()
+			.chain(blocks.iter().flatten().map(|block| &block.data[..])),
+	)
+}
@@ -0,0 +1,56 @@
+use std::io::{Cursor, Write};
+
+use binrw::BinWriterExt;
+
+use crate::error::Result;
+
+/// Size of both the SqPack header, and the index or dat header following it.
+pub const HEADER_SIZE: usize = 0x400;
+
+const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy)]
+pub enum SqPackKind {
+	Data = 1,
+	Index = 2,
+}
+
+/// Write the header shared by all SqPack package files, padded to its full size.
+pub fn write_sqpack_header(writer: &mut Cursor<Vec<u8>>, kind: SqPackKind) -> Result<()> {
+	let start = writer.get_ref().len();
+
+	writer.write_all(b"SqPack\0\0")?;
+	// Platform ID (win32), followed by 3 unknown bytes.
+	writer.write_all(&[0; 4])?;
+	writer.write_le(&u32::try_from(HEADER_SIZE).unwrap())?;
+	writer.write_le(&VERSION)?;
+	writer.write_le(&(kind as u32))?;
+
+	writer.get_mut().resize(start + HEADER_SIZE, 0);
+	writer.set_position((start + HEADER_SIZE).try_into().unwrap());
+	Ok(())
+}
+
+/// Write the header of a `.datN` file holding `data_size` bytes of entries.
+pub fn write_dat_header(
+	writer: &mut Cursor<Vec<u8>>,
+	data_file_id: u8,
+	data_size: u64,
+	max_size: u64,
+) -> Result<()> {
+	let start = writer.get_ref().len();
+
+	// ASSUMPTION: Beyond the size fields, this layout is as observed in live
+	// files. Nothing in ironworks reads it back.
+	writer.write_le(&u32::try_from(HEADER_SIZE).unwrap())?;
+	writer.write_le(&0u32)?;
+	writer.write_le(&0x10u32)?;
+	writer.write_le(&u32::try_from(data_size / 0x80).unwrap())?;
+	writer.write_le(&(u32::from(data_file_id) + 1))?;
+	writer.write_le(&0u32)?;
+	writer.write_le(&max_size)?;
+
+	writer.get_mut().resize(start + HEADER_SIZE, 0);
+	writer.set_position((start + HEADER_SIZE).try_into().unwrap());
+	Ok(())
+}
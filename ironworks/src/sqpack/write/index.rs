@@ -0,0 +1,176 @@
+use std::{
+	collections::BTreeMap,
+	io::{Cursor, Write},
+};
+
+use binrw::BinWriterExt;
+
+use crate::{
+	error::{Error, ErrorValue, Result},
+	sqpack::IndexHash,
+};
+
+use super::header::{HEADER_SIZE, SqPackKind, write_sqpack_header};
+
+const SYNONYM_PATH_SIZE: usize = 240;
+const SYNONYM_FLAG: u32 = 0b1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexKind {
+	/// `.index`, keyed by separate directory and file name hashes.
+	Split,
+	/// `.index2`, keyed by a hash of the full path.
+	Whole,
+}
+
+impl IndexKind {
+	fn entry_size(self) -> usize {
+		match self {
+			Self::Split => 16,
+			Self::Whole => 8,
+		}
+	}
+}
+
+/// Build an index file for the given paths and their packed file metadata.
+pub fn write_index(
+	kind: IndexKind,
+	files: &[(&str, u32)],
+	data_file_count: u32,
+) -> Result<Vec<u8>> {
+	// Group paths by hash, so collisions can be recorded as synonyms.
+	let mut hashes = BTreeMap::<u64, Vec<(&str, u32)>>::new();
+	for &(path, metadata) in files {
+		let hash = match (kind, IndexHash::of(path)) {
+			(IndexKind::Split, (Some(IndexHash::Split(hash)), _)) => hash,
+			(IndexKind::Whole, (_, IndexHash::Whole(hash))) => hash.into(),
+			_ => {
+				return Err(Error::Invalid(
+					ErrorValue::Path(path.into()),
+					"Paths must contain at least two segments.".into(),
+				));
+			}
+		};
+		hashes.entry(hash).or_default().push((path, metadata));
+	}
+
+	let index_offset = HEADER_SIZE * 2;
+	let mut entries = Cursor::new(Vec::new());
+	let mut synonyms = Cursor::new(Vec::new());
+	let mut directories = Cursor::new(Vec::new());
+
+	for (&hash, files) in &hashes {
+		let metadata = match files[..] {
+			[(_, metadata)] => metadata,
+			_ => SYNONYM_FLAG,
+		};
+
+		match kind {
+			IndexKind::Split => {
+				entries.write_le(&hash)?;
+				entries.write_le(&metadata)?;
+				entries.write_le(&0u32)?;
+			}
+			IndexKind::Whole => {
+				entries.write_le(&u32::try_from(hash).unwrap())?;
+				entries.write_le(&metadata)?;
+			}
+		}
+
+		if files.len() > 1 {
+			for (conflict_index, &(path, metadata)) in files.iter().enumerate() {
+				write_synonym(
+					&mut synonyms,
+					hash,
+					metadata,
+					conflict_index.try_into().unwrap(),
+					path,
+				)?;
+			}
+		}
+	}
+
+	// Synonym tables are always closed off by a terminating record.
+	write_synonym(&mut synonyms, 0, 0, u32::MAX, "")?;
+
+	// The split index additionally lists the range of entries in each directory.
+	// Entries are sorted by hash, so each directory's entries are contiguous.
+	if kind == IndexKind::Split {
+		let mut start = 0;
+		let keys = hashes.keys().collect::<Vec<_>>();
+		for group in keys.chunk_by(|a, b| *a >> 32 == *b >> 32) {
+			let offset = index_offset + start * kind.entry_size();
+			directories.write_le(&u32::try_from(group[0] >> 32).unwrap())?;
+			directories.write_le(&u32::try_from(offset).unwrap())?;
+			directories.write_le(&u32::try_from(group.len() * kind.entry_size()).unwrap())?;
+			directories.write_le(&0u32)?;
+			start += group.len();
+		}
+	}
+
+	let entries = entries.into_inner();
+	let synonyms = synonyms.into_inner();
+	let directories = directories.into_inner();
+
+	let synonym_offset = index_offset + entries.len();
+	// No free space is tracked, so the empty block section is always empty.
+	let empty_block_offset = synonym_offset + synonyms.len();
+	let directory_offset = empty_block_offset;
+
+	let mut writer = Cursor::new(Vec::with_capacity(directory_offset + directories.len()));
+	write_sqpack_header(&mut writer, SqPackKind::Index)?;
+
+	// ASSUMPTION: Digests are left zeroed; nothing reading these files verifies them.
+	let section = |writer: &mut Cursor<Vec<u8>>, offset: usize, size: usize| -> Result<()> {
+		writer.write_le(&u32::try_from(offset).unwrap())?;
+		writer.write_le(&u32::try_from(size).unwrap())?;
+		writer.write_all(&[0; 64])?;
+		Ok(())
+	};
+
+	writer.write_le(&u32::try_from(HEADER_SIZE).unwrap())?;
+	writer.write_le(&1u32)?;
+	section(&mut writer, index_offset, entries.len())?;
+	writer.write_le(&data_file_count)?;
+	section(&mut writer, synonym_offset, synonyms.len())?;
+	section(&mut writer, empty_block_offset, 0)?;
+	section(&mut writer, directory_offset, directories.len())?;
+	writer.write_le(&match kind {
+		IndexKind::Split => 0u32,
+		IndexKind::Whole => 2u32,
+	})?;
+
+	let mut index = writer.into_inner();
+	index.resize(index_offset, 0);
+	index.extend_from_slice(&entries);
+	index.extend_from_slice(&synonyms);
+	index.extend_from_slice(&directories);
+
+	Ok(index)
+}
+
+fn write_synonym(
+	writer: &mut Cursor<Vec<u8>>,
+	hash: u64,
+	metadata: u32,
+	conflict_index: u32,
+	path: &str,
+) -> Result<()> {
+	// Paths are stored null-terminated.
+	if path.len() >= SYNONYM_PATH_SIZE {
+		return Err(Error::Invalid(
+			ErrorValue::Path(path.into()),
+			"Path is too long to be recorded as a synonym.".into(),
+		));
+	}
+
+	writer.write_le(&hash)?;
+	writer.write_le(&metadata)?;
+	writer.write_le(&conflict_index)?;
+
+	let mut path_bytes = [0u8; SYNONYM_PATH_SIZE];
+	path_bytes[..path.len()].copy_from_slice(path.as_bytes());
+	writer.write_all(&path_bytes)?;
+
+	Ok(())
+}
@@ -0,0 +1,8 @@
+mod block;
+mod file;
+mod header;
+mod index;
+mod package;
+mod writer;
+
+pub use {package::Package, writer::Writer};
@@ -0,0 +1,65 @@
+use std::{
+	collections::BTreeMap,
+	fs,
+	io::{self, Cursor},
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+
+use derivative::Derivative;
+
+use crate::{error::Result, sqpack::Vfs};
+
+/// A set of SqPack package files held in memory, laid out as they would be
+/// within a `sqpack` directory.
+///
+/// Packages implement [`Vfs`], and can be read directly with
+/// [`VInstall`](crate::sqpack::VInstall).
+#[derive(Derivative, Default)]
+#[derivative(Debug)]
+pub struct Package {
+	#[derivative(Debug = "ignore")]
+	files: BTreeMap<PathBuf, Arc<[u8]>>,
+}
+
+impl Package {
+	pub(super) fn insert(&mut self, path: PathBuf, data: Vec<u8>) {
+		self.files.insert(path, data.into());
+	}
+
+	/// Paths of the files in this package, relative to the `sqpack` directory.
+	pub fn paths(&self) -> impl Iterator<Item = &Path> {
+		self.files.keys().map(PathBuf::as_path)
+	}
+
+	/// Write the package's files to disk, with the provided path acting as the
+	/// `sqpack` directory. Existing files will be overwritten.
+	pub fn write_to(&self, sqpack_path: impl AsRef<Path>) -> Result<()> {
+		let sqpack_path = sqpack_path.as_ref();
+		for (path, data) in &self.files {
+			let target = sqpack_path.join(path);
+			if let Some(parent) = target.parent() {
+				fs::create_dir_all(parent)?;
+			}
+			fs::write(target, data)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl Vfs for Package {
+	type File = Cursor<Arc<[u8]>>;
+
+	fn exists(&self, path: impl AsRef<Path>) -> bool {
+		let path = path.as_ref();
+		self.files.keys().any(|file| file.starts_with(path))
+	}
+
+	fn open(&self, path: impl AsRef<Path>) -> io::Result<Self::File> {
+		self.files
+			.get(path.as_ref())
+			.map(|data| Cursor::new(data.clone()))
+			.ok_or_else(|| io::ErrorKind::NotFound.into())
+	}
+}
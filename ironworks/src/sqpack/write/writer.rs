@@ -0,0 +1,423 @@
+use std::{
+	collections::BTreeMap,
+	io::{Cursor, Write},
+	path::PathBuf,
+};
+
+use derivative::Derivative;
+
+use crate::{
+	error::{Error, ErrorValue, Result},
+	sqpack::{
+		FileKind,
+		index::path_chunk,
+		sqpack::{REPOSITORIES, path_metadata},
+	},
+};
+
+use super::{
+	file::{infer_kind, write_file},
+	header::{HEADER_SIZE, SqPackKind, write_dat_header, write_sqpack_header},
+	index::{IndexKind, write_index},
+	package::Package,
+};
+
+/// Largest dat file the game itself will write.
+const DEFAULT_MAX_DAT_SIZE: u64 = 2_000_000_000;
+
+/// Data file IDs are stored in 3 bits of each index entry.
+const MAX_DAT_COUNT: usize = 8;
+
+/// Builder for SqPack packages from loose file data.
+///
+/// Files are compressed as they are added, and laid out into index and dat
+/// files when the package is built.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct Writer {
+	max_dat_size: u64,
+
+	// (repository, category, chunk) -> path -> encoded entry
+	#[derivative(Debug = "ignore")]
+	chunks: BTreeMap<(u8, u8, u8), BTreeMap<String, Vec<u8>>>,
+}
+
+impl Default for Writer {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Writer {
+	/// Build a writer with no files.
+	pub fn new() -> Self {
+		Self {
+			max_dat_size: DEFAULT_MAX_DAT_SIZE,
+			chunks: Default::default(),
+		}
+	}
+
+	/// Set the size at which dat files are split.
+	#[must_use]
+	pub fn with_max_dat_size(mut self, size: u64) -> Self {
+		self.set_max_dat_size(size);
+		self
+	}
+
+	/// Set the size at which dat files are split.
+	pub fn set_max_dat_size(&mut self, size: u64) {
+		self.max_dat_size = size;
+	}
+
+	/// Add a file to the package, choosing how it is stored from its path.
+	/// Adding a path that is already present will replace it.
+	pub fn add_file(&mut self, path: &str, data: &[u8]) -> Result<()> {
+		self.add_file_as(path, infer_kind(&path.to_lowercase(), data), data)
+	}
+
+	/// Add a file to the package, stored as the specified kind. Model and
+	/// texture files are expected to be in their standard `.mdl` and `.tex`
+	/// formats respectively.
+	pub fn add_file_as(&mut self, path: &str, kind: FileKind, data: &[u8]) -> Result<()> {
+		let path = path.to_lowercase();
+		let (repository, category) = path_metadata(&path)?;
+		let chunk = path_chunk(repository, &path);
+
+		let entry = write_file(&path, kind, data)?;
+		self.chunks
+			.entry((repository, category, chunk))
+			.or_default()
+			.insert(path, entry);
+
+		Ok(())
+	}
+
+	/// Lay out the added files into a package.
+	pub fn build(self) -> Result<Package> {
+		let mut package = Package::default();
+
+		for ((repository, category, chunk), files) in self.chunks {
+			let repository_name = REPOSITORIES[usize::from(repository)];
+			let file_path = |extension: &str| {
+				PathBuf::from(repository_name).join(format!(
+					"{category:02x}{repository:02x}{chunk:02x}.win32.{extension}"
+				))
+			};
+
+			let Layout { dats, locations } = lay_out(&files, self.max_dat_size)?;
+			let data_file_count = u32::try_from(dats.len()).unwrap();
+
+			package.insert(
+				file_path("index"),
+				write_index(IndexKind::Split, &locations, data_file_count)?,
+			);
+			package.insert(
+				file_path("index2"),
+				write_index(IndexKind::Whole, &locations, data_file_count)?,
+			);
+			for (data_file_id, dat) in dats.into_iter().enumerate() {
+				package.insert(file_path(&format!("dat{data_file_id}")), dat);
+			}
+		}
+
+		Ok(package)
+	}
+}
+
+struct Layout<'a> {
+	dats: Vec<Vec<u8>>,
+	/// Each path alongside its packed file metadata.
+	locations: Vec<(&'a str, u32)>,
+}
+
+/// Distribute entries across as many dat files as required to keep each under
+/// the maximum size.
+fn lay_out(files: &BTreeMap<String, Vec<u8>>, max_size: u64) -> Result<Layout<'_>> {
+	let data_offset = HEADER_SIZE * 2;
+
+	let mut dats = Vec::<Cursor<Vec<u8>>>::new();
+	let mut locations = Vec::with_capacity(files.len());
+
+	for (path, entry) in files {
+		let full = dats.last().is_none_or(|dat| {
+			let size = dat.get_ref().len();
+			size > data_offset && u64::try_from(size + entry.len()).unwrap() > max_size
+		});
+		if full {
+			if dats.len() == MAX_DAT_COUNT {
+				return Err(Error::Invalid(
+					ErrorValue::Path(path.clone()),
+					format!("Chunk requires more than {MAX_DAT_COUNT} dat files."),
+				));
+			}
+			let mut dat = Cursor::new(Vec::new());
+			write_sqpack_header(&mut dat, SqPackKind::Data)?;
+			dat.get_mut().resize(data_offset, 0);
+			dat.set_position(data_offset.try_into().unwrap());
+			dats.push(dat);
+		}
+
+		let data_file_id = dats.len() - 1;
+		let dat = dats.last_mut().unwrap();
+		let offset = dat.get_ref().len();
+
+		// Offsets are stored in 8-byte units, with the low bits holding the data file ID.
+		let metadata = u32::try_from(offset / 8)
+			.ok()
+			.filter(|metadata| metadata & 0xF == 0)
+			.ok_or_else(|| {
+				Error::Invalid(
+					ErrorValue::Path(path.clone()),
+					"File offset cannot be represented in an index.".into(),
+				)
+			})?;
+		locations.push((
+			&path[..],
+			metadata | (u32::try_from(data_file_id).unwrap() << 1),
+		));
+
+		dat.write_all(entry)?;
+	}
+
+	let dats = dats
+		.into_iter()
+		.enumerate()
+		.map(|(data_file_id, dat)| {
+			let mut dat = dat.into_inner();
+			let data_size = u64::try_from(dat.len() - data_offset).unwrap();
+
+			let mut header = Cursor::new(Vec::with_capacity(HEADER_SIZE));
+			write_dat_header(
+				&mut header,
+				data_file_id.try_into().unwrap(),
+				data_size,
+				max_size,
+			)?;
+			dat[HEADER_SIZE..data_offset].copy_from_slice(header.get_ref());
+
+			Ok(dat)
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	Ok(Layout { dats, locations })
+}
+
+#[cfg(test)]
+mod test {
+	use std::io::Read;
+
+	use crate::{
+		error::Error,
+		sqpack::{FileKind, IndexHash, SqPack, VInstall, Vfs},
+	};
+
+	use super::{Package, Writer};
+
+	fn sqpack(writer: Writer) -> SqPack<VInstall<Package>> {
+		SqPack::new(VInstall::at_sqpack(writer.build().unwrap()))
+	}
+
+	fn read(sqpack: &SqPack<VInstall<Package>>, path: &str) -> (FileKind, Vec<u8>) {
+		let mut file = sqpack.file(path).unwrap();
+		let mut buffer = Vec::new();
+		file.read_to_end(&mut buffer).unwrap();
+		(file.kind(), buffer)
+	}
+
+	// Deterministic data that deflate cannot shrink.
+	fn noise(length: usize) -> Vec<u8> {
+		let mut state = 0x2545_f491u32;
+		(0..length)
+			.map(|_| {
+				state ^= state << 13;
+				state ^= state >> 17;
+				state ^= state << 5;
+				state as u8
+			})
+			.collect()
+	}
+
+	#[test]
+	fn standard_files_read_back_unchanged() {
+		let files = [
+			("exd/root.exl", b"EXLT,2\nAction,4\n".to_vec()),
+			("common/font/font1.tex.dat", vec![7; 100_000]),
+			("ui/uld/random.uld", noise(40_000)),
+		];
+
+		let mut writer = Writer::new();
+		for (path, data) in &files {
+			writer.add_file(path, data).unwrap();
+		}
+		let sqpack = sqpack(writer);
+
+		for (path, data) in &files {
+			assert_eq!(
+				read(&sqpack, path),
+				(FileKind::Standard, data.clone()),
+				"{path}"
+			);
+		}
+	}
+
+	#[test]
+	fn paths_are_stored_lower_case() {
+		let mut writer = Writer::new();
+		writer.add_file("EXD/Action.exh", b"EXHF").unwrap();
+		let sqpack = sqpack(writer);
+
+		assert_eq!(read(&sqpack, "exd/action.exh").1, b"EXHF");
+	}
+
+	#[test]
+	fn empty_files_are_stored_without_data() {
+		let mut writer = Writer::new();
+		writer.add_file("common/empty.dat", &[]).unwrap();
+		let sqpack = sqpack(writer);
+
+		assert_eq!(read(&sqpack, "common/empty.dat"), (FileKind::Empty, vec![]));
+	}
+
+	#[test]
+	fn models_read_back_unchanged() {
+		let (stack, runtime, vertices, indices) = (100u32, 20_000u32, 30_000u32, 5_000u32);
+		let vertex_offset = 0x44 + stack + runtime;
+		let index_offset = vertex_offset + vertices;
+
+		let mut model = Vec::new();
+		model.extend(0x0100_0005u32.to_le_bytes());
+		model.extend(stack.to_le_bytes());
+		model.extend(runtime.to_le_bytes());
+		model.extend(3u16.to_le_bytes());
+		model.extend(2u16.to_le_bytes());
+		for values in [
+			[vertex_offset, 0, 0],
+			[index_offset, 0, 0],
+			[vertices, 0, 0],
+			[indices, 0, 0],
+		] {
+			model.extend(values.iter().flat_map(|value| value.to_le_bytes()));
+		}
+		model.extend([1, 0, 0, 0]);
+		model.extend(noise((stack + runtime + vertices + indices) as usize));
+
+		let mut writer = Writer::new();
+		writer
+			.add_file("chara/equipment/e0001/model/c0101e0001_top.mdl", &model)
+			.unwrap();
+		let sqpack = sqpack(writer);
+
+		assert_eq!(
+			read(&sqpack, "chara/equipment/e0001/model/c0101e0001_top.mdl"),
+			(FileKind::Model, model)
+		);
+	}
+
+	#[test]
+	fn textures_read_back_unchanged() {
+		// 2D, and a 2D array of 2.
+		for (attribute, array_size) in [(0x0080_0000u32, 1u8), (0x1080_0000, 2)] {
+			let mips = [32_768u32, 8_192, 2_048];
+
+			let mut texture = Vec::new();
+			texture.extend(attribute.to_le_bytes());
+			texture.extend(0x1450u32.to_le_bytes());
+			texture.extend([64u16, 64, 1].iter().flat_map(|value| value.to_le_bytes()));
+			texture.extend([mips.len() as u8, array_size]);
+			texture.extend([0u32, 1, 2].iter().flat_map(|value| value.to_le_bytes()));
+			let mut offset = 80u32;
+			for index in 0..13 {
+				let surface_offset = mips.get(index).map_or(0, |_| offset);
+				texture.extend(surface_offset.to_le_bytes());
+				offset += mips.get(index).copied().unwrap_or(0);
+			}
+			texture.extend(noise(mips.iter().sum::<u32>() as usize));
+
+			let mut writer = Writer::new();
+			writer
+				.add_file("ui/icon/000000/000001.tex", &texture)
+				.unwrap();
+			let sqpack = sqpack(writer);
+
+			assert_eq!(
+				read(&sqpack, "ui/icon/000000/000001.tex"),
+				(FileKind::Texture, texture),
+				"attribute {attribute:#x}"
+			);
+		}
+	}
+
+	#[test]
+	fn malformed_models_are_rejected() {
+		let mut writer = Writer::new();
+		let error = writer.add_file("chara/broken.mdl", &[1, 2, 3]).unwrap_err();
+		assert!(matches!(error, Error::Invalid(..)), "{error:?}");
+	}
+
+	#[test]
+	fn dats_are_split_at_the_size_limit() {
+		let files = (0..6)
+			.map(|index| (format!("bg/ffxiv/file{index}.bin"), noise(3_000 + index)))
+			.collect::<Vec<_>>();
+
+		let mut writer = Writer::new().with_max_dat_size(10_000);
+		for (path, data) in &files {
+			writer.add_file(path, data).unwrap();
+		}
+		let package = writer.build().unwrap();
+		assert!(package.exists("ffxiv/020000.win32.dat2"));
+
+		let sqpack = SqPack::new(VInstall::at_sqpack(package));
+		for (path, data) in &files {
+			assert_eq!(&read(&sqpack, path).1, data, "{path}");
+		}
+	}
+
+	#[test]
+	fn too_many_dats_are_rejected() {
+		let mut writer = Writer::new().with_max_dat_size(1);
+		for index in 0..9 {
+			writer
+				.add_file(&format!("bg/ffxiv/file{index}.bin"), &[1])
+				.unwrap();
+		}
+		assert!(matches!(writer.build(), Err(Error::Invalid(..))));
+	}
+
+	#[test]
+	fn expansion_files_are_written_to_their_chunk() {
+		let path = "bg/ex2/01_gyr_g3/fld/g3f2/collision/g3f2_t1_nat03.pcb";
+		let mut writer = Writer::new();
+		writer.add_file(path, b"pcb").unwrap();
+		let package = writer.build().unwrap();
+		assert!(package.exists("ex2/020201.win32.index"));
+
+		let sqpack = SqPack::new(VInstall::at_sqpack(package));
+		assert_eq!(read(&sqpack, path).1, b"pcb");
+	}
+
+	#[test]
+	fn colliding_hashes_are_recorded_as_synonyms() {
+		let icon = "ui/icon/150000/de/150751_hr1.tex";
+		let uld = "ui/uld/turnbreaktitle.uld";
+
+		let mut writer = Writer::new();
+		writer
+			.add_file_as(icon, FileKind::Standard, b"icon")
+			.unwrap();
+		writer.add_file(uld, b"uld").unwrap();
+		let sqpack = sqpack(writer);
+
+		assert_eq!(read(&sqpack, icon).1, b"icon");
+		assert_eq!(read(&sqpack, uld).1, b"uld");
+
+		let (Some(split), whole) = IndexHash::of(uld) else {
+			panic!("no split hash for {uld}");
+		};
+		assert!(sqpack.file_by_hash(0, 6, split).is_ok());
+		assert!(matches!(
+			sqpack.file_by_hash(0, 6, whole),
+			Err(Error::Invalid(..))
+		));
+	}
+}
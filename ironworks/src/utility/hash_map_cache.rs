@@ -1,11 +1,14 @@
 use std::{
-	collections::{HashMap, hash_map::Entry},
-	hash::Hash,
+	collections::HashMap,
 	sync::{Arc, Mutex},
 };
 
+#[cfg(feature = "sqpack")]
+use std::{collections::hash_map::Entry, hash::Hash};
+
 pub type HashMapCache<K, V> = Mutex<HashMap<K, Arc<V>>>;
 
+#[cfg(feature = "sqpack")]
 pub trait HashMapCacheExt<K, V> {
 	fn try_get_or_insert<E>(
 		&self,
@@ -14,6 +17,7 @@ pub trait HashMapCacheExt<K, V> {
 	) -> Result<Arc<V>, E>;
 }
 
+#[cfg(feature = "sqpack")]
 impl<K, V> HashMapCacheExt<K, V> for HashMapCache<K, V>
 where
 	K: Eq + Hash,
@@ -30,7 +34,7 @@ where
 	}
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "sqpack"))]
 mod test {
 	use std::convert::Infallible;
 
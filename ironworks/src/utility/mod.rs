@@ -1,7 +1,13 @@
 mod hash_map_cache;
+#[cfg(feature = "sqpack")]
 mod take_seekable;
+#[cfg(all(test, any(feature = "loose", feature = "zipatch")))]
+mod temp_directory;
 
-pub use {
-	hash_map_cache::{HashMapCache, HashMapCacheExt},
-	take_seekable::{TakeSeekable, TakeSeekableExt},
-};
+pub use hash_map_cache::HashMapCache;
+#[cfg(feature = "sqpack")]
+pub use hash_map_cache::HashMapCacheExt;
+#[cfg(feature = "sqpack")]
+pub use take_seekable::{TakeSeekable, TakeSeekableExt};
+#[cfg(all(test, any(feature = "loose", feature = "zipatch")))]
+pub use temp_directory::TempDirectory;
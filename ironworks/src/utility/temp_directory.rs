@@ -0,0 +1,48 @@
+use std::{
+	fs,
+	path::{Path, PathBuf},
+	sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Uniquely named directory within the system temporary directory. The
+/// directory and its contents are removed when dropped, including when a test
+/// panics.
+pub struct TempDirectory(PathBuf);
+
+impl TempDirectory {
+	/// Create an empty directory.
+	pub fn new() -> Self {
+		Self::with_files::<&[u8]>(&[])
+	}
+
+	/// Create a directory holding the given files, as pairs of paths relative to
+	/// the directory and their contents.
+	pub fn with_files<C: AsRef<[u8]>>(files: &[(&str, C)]) -> Self {
+		static COUNTER: AtomicUsize = AtomicUsize::new(0);
+		let root = std::env::temp_dir().join(format!(
+			"ironworks-{}-{}",
+			std::process::id(),
+			COUNTER.fetch_add(1, Ordering::Relaxed)
+		));
+		fs::create_dir_all(&root).unwrap();
+
+		for (path, contents) in files {
+			let path = root.join(path);
+			fs::create_dir_all(path.parent().unwrap()).unwrap();
+			fs::write(path, contents).unwrap();
+		}
+
+		Self(root)
+	}
+
+	/// Path of the directory.
+	pub fn path(&self) -> &Path {
+		&self.0
+	}
+}
+
+impl Drop for TempDirectory {
+	fn drop(&mut self) {
+		let _ = fs::remove_dir_all(&self.0);
+	}
+}
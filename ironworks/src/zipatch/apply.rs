@@ -0,0 +1,692 @@
+use std::{
+	fs,
+	io::{self, BufReader, Read, Seek, SeekFrom, Write},
+	path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	error::{Error, ErrorValue, Result},
+	file::{
+		File,
+		patch::{
+			Chunk, FileOperation, FileOperationCommand, HeaderFileKind, HeaderKind, OptionKind,
+			SqPackChunk, SqPackFile, TargetPlatform, ZiPatch as ZiPatchFile,
+		},
+	},
+	sqpack,
+};
+
+use super::repository::{Patch, PatchRepository, patch_version};
+
+/// Applier that executes the commands within patch files against a game
+/// install, i.e. the `game/` directory of a client.
+#[derive(Debug)]
+pub struct Applier {
+	game: PathBuf,
+	dry_run: bool,
+	checkpoint: Option<Checkpoint>,
+}
+
+/// Position within a chain of patches that application can be resumed from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+	/// Name of the patch being applied.
+	pub patch: String,
+	/// Index of the next chunk within the patch to be applied.
+	pub chunk: usize,
+}
+
+/// Progress reported after each chunk of a patch has been applied.
+#[derive(Debug)]
+pub struct Progress {
+	/// Checkpoint to resume from should application be interrupted.
+	pub checkpoint: Checkpoint,
+	/// Changes made by the chunk. In dry-run mode, these are the changes that
+	/// would have been made.
+	pub actions: Vec<Action>,
+}
+
+/// A change made to a game install. Paths are relative to the game directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Action {
+	/// Create a file, truncating it if it already exists.
+	Create(PathBuf),
+	/// Write data to a file.
+	Write {
+		/// File written to.
+		path: PathBuf,
+		/// Offset within the file the data starts at, in bytes.
+		offset: u64,
+		/// Size of the data, in bytes.
+		size: u64,
+	},
+	/// Blank a region of a file.
+	Erase {
+		/// File written to.
+		path: PathBuf,
+		/// Offset within the file the region starts at, in bytes.
+		offset: u64,
+		/// Size of the region, in bytes.
+		size: u64,
+	},
+	/// Delete a file.
+	Delete(PathBuf),
+	/// Create a directory, along with any missing parents.
+	CreateDirectory(PathBuf),
+	/// Delete an empty directory.
+	DeleteDirectory(PathBuf),
+	/// Record the version of a repository in its `.ver` file.
+	Version {
+		/// Version file written to.
+		path: PathBuf,
+		/// Version recorded.
+		version: String,
+	},
+}
+
+/// Options set by earlier chunks within a patch.
+#[derive(Debug)]
+struct State {
+	platform: TargetPlatform,
+	ignore_missing: bool,
+}
+
+impl Default for State {
+	fn default() -> Self {
+		Self {
+			platform: TargetPlatform::Win32,
+			ignore_missing: false,
+		}
+	}
+}
+
+impl State {
+	fn update(&mut self, chunk: &Chunk) {
+		match chunk {
+			Chunk::Apply(apply) => {
+				if let OptionKind::IgnoreMissing = apply.option() {
+					self.ignore_missing = apply.value() != 0;
+				}
+			}
+			Chunk::SqPack(SqPackChunk::TargetInfo(info)) => self.platform = info.platform(),
+			_ => {}
+		}
+	}
+
+	fn sqpack_path(&self, file: SqPackFile, kind: HeaderFileKind) -> Result<PathBuf> {
+		let platform = match self.platform {
+			TargetPlatform::Win32 => "win32",
+			TargetPlatform::Ps3 => "ps3",
+			TargetPlatform::Ps4 => "ps4",
+			TargetPlatform::Unknown => {
+				return Err(Error::Invalid(
+					ErrorValue::Other("patch target platform".into()),
+					"unknown platform".into(),
+				));
+			}
+		};
+
+		let extension = match (kind, file.file_id()) {
+			(HeaderFileKind::Dat, id) => format!("dat{id}"),
+			(HeaderFileKind::Index, 0) => "index".into(),
+			(HeaderFileKind::Index, id) => format!("index{id}"),
+		};
+
+		Ok(PathBuf::from("sqpack")
+			.join(repository_name(file.sub_id() >> 8))
+			.join(format!(
+				"{:02x}{:04x}.{platform}.{extension}",
+				file.main_id(),
+				file.sub_id()
+			)))
+	}
+}
+
+impl Applier {
+	/// Create an applier targeting the game directory at `game`.
+	pub fn new(game: impl Into<PathBuf>) -> Self {
+		Self {
+			game: game.into(),
+			dry_run: false,
+			checkpoint: None,
+		}
+	}
+
+	/// Report the changes patches would make, without touching the install.
+	pub fn with_dry_run(mut self) -> Self {
+		self.set_dry_run(true);
+		self
+	}
+
+	/// Set whether patches should only report the changes they would make,
+	/// without touching the install.
+	pub fn set_dry_run(&mut self, dry_run: bool) {
+		self.dry_run = dry_run;
+	}
+
+	/// Resume application from a checkpoint previously reported via [`Progress`].
+	pub fn with_checkpoint(mut self, checkpoint: Checkpoint) -> Self {
+		self.set_checkpoint(checkpoint);
+		self
+	}
+
+	/// Resume application from a checkpoint previously reported via [`Progress`].
+	pub fn set_checkpoint(&mut self, checkpoint: Checkpoint) {
+		self.checkpoint = Some(checkpoint);
+	}
+
+	/// Apply every patch in a repository, in order, to the install. If a
+	/// checkpoint is set, patches prior to the checkpoint are skipped.
+	pub fn apply_repository(
+		&mut self,
+		repository_id: u8,
+		repository: &PatchRepository,
+		mut progress: impl FnMut(Progress),
+	) -> Result<()> {
+		let start = match &self.checkpoint {
+			None => 0,
+			Some(checkpoint) => repository
+				.patches
+				.iter()
+				.position(|patch| patch.name == checkpoint.patch)
+				.ok_or_else(|| {
+					Error::NotFound(ErrorValue::Other(format!(
+						"checkpoint patch {}",
+						checkpoint.patch
+					)))
+				})?,
+		};
+
+		for patch in &repository.patches[start..] {
+			self.apply_patch(repository_id, patch, &mut progress)?;
+		}
+
+		Ok(())
+	}
+
+	/// Apply a single patch to the install. Once the patch is complete, the
+	/// repository's `.ver` file will be updated to the patch's version.
+	pub fn apply_patch(
+		&mut self,
+		repository_id: u8,
+		patch: &Patch,
+		mut progress: impl FnMut(Progress),
+	) -> Result<()> {
+		let start = self
+			.checkpoint
+			.take_if(|checkpoint| checkpoint.patch == patch.name)
+			.map_or(0, |checkpoint| checkpoint.chunk);
+
+		let zipatch = ZiPatchFile::read(BufReader::new(fs::File::open(&patch.path)?))?;
+		let mut source = BufReader::new(fs::File::open(&patch.path)?);
+		let mut state = State::default();
+
+		for (index, chunk) in zipatch.chunks().enumerate() {
+			// Skipped chunks still configure the state of later chunks.
+			let chunk = chunk?;
+			state.update(&chunk);
+			if index < start {
+				continue;
+			}
+
+			let actions = match chunk {
+				Chunk::AddDirectory(chunk) => {
+					let path = PathBuf::from(chunk.path());
+					vec![self.create_directory(path)?]
+				}
+				Chunk::DeleteDirectory(chunk) => {
+					let path = PathBuf::from(chunk.path());
+					if !self.dry_run {
+						ignore_missing(&state, fs::remove_dir(self.game.join(&path)))?;
+					}
+					vec![Action::DeleteDirectory(path)]
+				}
+				Chunk::SqPack(command) => self.sqpack(&state, command, &mut source)?,
+				Chunk::EndOfFile => vec![self.version(repository_id, patch)?],
+				Chunk::FileHeader(_) | Chunk::Apply(_) => vec![],
+			};
+
+			progress(Progress {
+				checkpoint: Checkpoint {
+					patch: patch.name.clone(),
+					chunk: index + 1,
+				},
+				actions,
+			});
+		}
+
+		Ok(())
+	}
+
+	fn sqpack(
+		&self,
+		state: &State,
+		command: SqPackChunk,
+		source: &mut (impl Read + Seek),
+	) -> Result<Vec<Action>> {
+		let actions = match command {
+			SqPackChunk::Add(command) => {
+				let path = state.sqpack_path(command.file(), HeaderFileKind::Dat)?;
+				let (offset, size) = (command.target_offset(), command.data_size());
+				let delete_size = command.delete_size();
+
+				self.write(&path, offset, false, |file| {
+					source.seek(SeekFrom::Start(command.source_offset()))?;
+					copy_exact(&mut source.take(size), file, size)?;
+					erase(file, delete_size)
+				})?;
+
+				let mut actions = vec![Action::Write {
+					path: path.clone(),
+					offset,
+					size,
+				}];
+				if delete_size > 0 {
+					actions.push(Action::Erase {
+						path,
+						offset: offset + size,
+						size: delete_size,
+					});
+				}
+				actions
+			}
+
+			// Deletions and expansions both leave an empty block behind.
+			SqPackChunk::Delete(command) => vec![self.empty_block(
+				state.sqpack_path(command.file(), HeaderFileKind::Dat)?,
+				command.target_offset(),
+				command.delete_size(),
+			)?],
+			SqPackChunk::Expand(command) => vec![self.empty_block(
+				state.sqpack_path(command.file(), HeaderFileKind::Dat)?,
+				command.target_offset(),
+				command.delete_size(),
+			)?],
+
+			SqPackChunk::HeaderUpdate(command) => {
+				let path = state.sqpack_path(command.file(), command.file_kind())?;
+				let offset = match command.header_kind() {
+					HeaderKind::Version => 0,
+					HeaderKind::Data | HeaderKind::Index => 1024,
+				};
+				let size = u64::from(command.size());
+
+				self.write(&path, offset, false, |file| {
+					source.seek(SeekFrom::Start(command.offset()))?;
+					copy_exact(&mut source.take(size), file, size)
+				})?;
+
+				vec![Action::Write { path, offset, size }]
+			}
+
+			SqPackChunk::FileOperation(command) => self.file_operation(state, command, source)?,
+
+			// Target info is handled by the state, and the remaining commands have no
+			// effect on the install.
+			SqPackChunk::TargetInfo(_)
+			| SqPackChunk::PatchInfo(_)
+			| SqPackChunk::IndexUpdate(_) => {
+				vec![]
+			}
+		};
+
+		Ok(actions)
+	}
+
+	fn file_operation(
+		&self,
+		state: &State,
+		command: FileOperationCommand,
+		source: &mut (impl Read + Seek),
+	) -> Result<Vec<Action>> {
+		let path = PathBuf::from(command.path().to_string());
+
+		let actions = match command.operation() {
+			FileOperation::AddFile(blocks) => {
+				// Writes to the start of a file replace it entirely.
+				let offset = command.target_offset();
+				self.write(&path, offset, offset == 0, |file| {
+					for block in blocks {
+						source.seek(SeekFrom::Start(block.offset()))?;
+						let mut payload = sqpack::BlockPayload::new(
+							source,
+							block.compressed_size(),
+							block.decompressed_size(),
+						);
+						copy_exact(&mut payload, file, block.decompressed_size().into())?;
+					}
+					Ok(())
+				})?;
+
+				let write = Action::Write {
+					path: path.clone(),
+					offset,
+					size: command.target_size(),
+				};
+				match offset {
+					0 => vec![Action::Create(path), write],
+					_ => vec![write],
+				}
+			}
+
+			FileOperation::DeleteFile => vec![self.delete(state, path)?],
+
+			FileOperation::MakeDirTree => vec![self.create_directory(path)?],
+
+			// Only the repository's SqPack files are removed - version files and
+			// anything unrelated are left intact.
+			FileOperation::RemoveAll => {
+				let directory =
+					PathBuf::from("sqpack").join(repository_name(command.repository_id()));
+				let entries = match fs::read_dir(self.game.join(&directory)) {
+					Ok(entries) => entries,
+					Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+					Err(error) => Err(error)?,
+				};
+
+				let mut paths = vec![];
+				for entry in entries {
+					let entry = entry?;
+					let name = entry.file_name().to_string_lossy().into_owned();
+					let is_sqpack = name.rsplit_once('.').is_some_and(|(_, extension)| {
+						extension.starts_with("dat") || extension.starts_with("index")
+					});
+					if is_sqpack && entry.file_type()?.is_file() {
+						paths.push(directory.join(name));
+					}
+				}
+				paths.sort();
+
+				paths
+					.into_iter()
+					.map(|path| self.delete(state, path))
+					.collect::<Result<_>>()?
+			}
+		};
+
+		Ok(actions)
+	}
+
+	fn version(&self, repository_id: u8, patch: &Patch) -> Result<Action> {
+		let path = match repository_id {
+			0 => PathBuf::from("ffxivgame.ver"),
+			id => PathBuf::from("sqpack")
+				.join(format!("ex{id}"))
+				.join(format!("ex{id}.ver")),
+		};
+
+		let version = patch_version(&patch.name).to_string();
+		self.write(&path, 0, true, |file| {
+			Ok(file.write_all(version.as_bytes())?)
+		})?;
+
+		Ok(Action::Version { path, version })
+	}
+
+	fn empty_block(&self, path: PathBuf, offset: u64, size: u64) -> Result<Action> {
+		self.write(&path, offset, false, |file| {
+			if size == 0 {
+				return Ok(());
+			}
+
+			erase(file, size)?;
+			file.seek(SeekFrom::Start(offset))?;
+
+			// Header of an empty block, recording the number of 128 byte units that
+			// follow it.
+			let blocks = u32::try_from((size >> 7) - 1).map_err(|error| {
+				Error::Invalid(ErrorValue::Other("empty block".into()), error.to_string())
+			})?;
+			for value in [128, 0, 0, blocks, 0] {
+				file.write_all(&u32::to_le_bytes(value))?;
+			}
+			Ok(())
+		})?;
+
+		Ok(Action::Erase { path, offset, size })
+	}
+
+	fn create_directory(&self, path: PathBuf) -> Result<Action> {
+		if !self.dry_run {
+			fs::create_dir_all(self.game.join(&path))?;
+		}
+		Ok(Action::CreateDirectory(path))
+	}
+
+	fn delete(&self, state: &State, path: PathBuf) -> Result<Action> {
+		if !self.dry_run {
+			ignore_missing(state, fs::remove_file(self.game.join(&path)))?;
+		}
+		Ok(Action::Delete(path))
+	}
+
+	fn write(
+		&self,
+		path: &Path,
+		offset: u64,
+		truncate: bool,
+		contents: impl FnOnce(&mut fs::File) -> Result<()>,
+	) -> Result<()> {
+		if self.dry_run {
+			return Ok(());
+		}
+
+		let path = self.game.join(path);
+		if let Some(parent) = path.parent() {
+			fs::create_dir_all(parent)?;
+		}
+
+		let mut file = fs::OpenOptions::new()
+			.write(true)
+			.create(true)
+			.truncate(truncate)
+			.open(path)?;
+		file.seek(SeekFrom::Start(offset))?;
+
+		contents(&mut file)
+	}
+}
+
+fn repository_name(id: u16) -> String {
+	match id {
+		0 => "ffxiv".into(),
+		id => format!("ex{id}"),
+	}
+}
+
+fn ignore_missing(state: &State, result: io::Result<()>) -> Result<()> {
+	match result {
+		Err(error) if error.kind() == io::ErrorKind::NotFound && state.ignore_missing => Ok(()),
+		other => Ok(other?),
+	}
+}
+
+fn copy_exact(reader: &mut impl Read, writer: &mut impl Write, size: u64) -> Result<()> {
+	let copied = io::copy(reader, writer)?;
+	if copied != size {
+		Err(io::Error::new(
+			io::ErrorKind::UnexpectedEof,
+			format!("expected {size} bytes of patch data, found {copied}"),
+		))?;
+	}
+	Ok(())
+}
+
+fn erase(writer: &mut impl Write, size: u64) -> Result<()> {
+	io::copy(&mut io::repeat(0).take(size), writer)?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use std::{fs, path::PathBuf};
+
+	use crate::{
+		utility::TempDirectory,
+		zipatch::{Patch, PatchRepository},
+	};
+
+	use super::{Action, Applier, Checkpoint};
+
+	const NAME: &str = "D2024.01.01.0000.0000";
+
+	fn chunk(magic: &[u8; 4], data: &[u8]) -> Vec<u8> {
+		let size = u32::try_from(data.len()).unwrap();
+		[&size.to_be_bytes(), magic, data, &[0; 4]].concat()
+	}
+
+	fn sqpack(command: u8, body: &[u8]) -> Vec<u8> {
+		let size = u32::try_from(body.len() + 5).unwrap();
+		chunk(
+			b"SQPK",
+			&[&size.to_be_bytes()[..], &[command], body].concat(),
+		)
+	}
+
+	fn dat0() -> Vec<u8> {
+		[0u16.to_be_bytes(), 0u16.to_be_bytes(), [0; 2], [0; 2]].concat()
+	}
+
+	fn write_patch(directory: &TempDirectory) -> Patch {
+		let target_info = [
+			&[0; 3][..],
+			&0u16.to_be_bytes(),
+			&(-1i16).to_be_bytes(),
+			&[0; 20],
+		]
+		.concat();
+
+		// Single uncompressed block, padded out to 128 bytes.
+		let path = b"sqpack/ffxiv/000000.win32.index\0";
+		let block = [
+			&16u32.to_le_bytes()[..],
+			&[0; 4],
+			&32_000u32.to_le_bytes(),
+			&5u32.to_le_bytes(),
+			b"hello",
+			&[0; 107],
+		]
+		.concat();
+		let add_file = [
+			&[b'A', 0, 0][..],
+			&0u64.to_be_bytes(),
+			&5u64.to_be_bytes(),
+			&u32::try_from(path.len()).unwrap().to_be_bytes(),
+			&0u16.to_be_bytes(),
+			&[0; 2],
+			path,
+			&block,
+		]
+		.concat();
+
+		let add_data = [
+			&[0; 3][..],
+			&dat0(),
+			&1u32.to_be_bytes(),
+			&1u32.to_be_bytes(),
+			&1u32.to_be_bytes(),
+			&[0xAA; 128],
+		]
+		.concat();
+
+		let patch = [
+			&b"\x91ZIPATCH\x0D\x0A\x1A\x0A"[..],
+			&sqpack(b'T', &target_info),
+			&sqpack(b'F', &add_file),
+			&sqpack(b'A', &add_data),
+			&chunk(b"EOF_", &[]),
+		]
+		.concat();
+
+		let path = directory.path().join(format!("{NAME}.patch"));
+		fs::write(&path, patch).unwrap();
+		Patch {
+			name: NAME.into(),
+			path,
+		}
+	}
+
+	#[test]
+	fn applies_patch_commands() {
+		let (patches, game) = (TempDirectory::new(), TempDirectory::new());
+		let repository = PatchRepository {
+			patches: vec![write_patch(&patches)],
+		};
+
+		let mut checkpoints = vec![];
+		Applier::new(game.path())
+			.apply_repository(0, &repository, |progress| {
+				checkpoints.push(progress.checkpoint.chunk)
+			})
+			.unwrap();
+		assert_eq!(checkpoints, [1, 2, 3, 4]);
+
+		let sqpack = game.path().join("sqpack/ffxiv");
+		assert_eq!(
+			fs::read(sqpack.join("000000.win32.index")).unwrap(),
+			b"hello"
+		);
+
+		let dat = fs::read(sqpack.join("000000.win32.dat0")).unwrap();
+		assert_eq!(dat.len(), 384);
+		assert!(dat[128..256].iter().all(|byte| *byte == 0xAA));
+		assert!(dat[256..].iter().all(|byte| *byte == 0));
+
+		assert_eq!(
+			fs::read_to_string(game.path().join("ffxivgame.ver")).unwrap(),
+			"2024.01.01.0000.0000"
+		);
+	}
+
+	#[test]
+	fn dry_runs_leave_the_install_untouched() {
+		let (patches, game) = (TempDirectory::new(), TempDirectory::new());
+		let patch = write_patch(&patches);
+
+		let mut actions = vec![];
+		Applier::new(game.path())
+			.with_dry_run()
+			.apply_patch(1, &patch, |progress| actions.extend(progress.actions))
+			.unwrap();
+
+		let index = PathBuf::from("sqpack/ffxiv/000000.win32.index");
+		assert_eq!(actions[0], Action::Create(index.clone()));
+		assert_eq!(
+			actions[1],
+			Action::Write {
+				path: index,
+				offset: 0,
+				size: 5
+			}
+		);
+		assert_eq!(
+			actions.last(),
+			Some(&Action::Version {
+				path: PathBuf::from("sqpack/ex1/ex1.ver"),
+				version: "2024.01.01.0000.0000".into(),
+			})
+		);
+		assert_eq!(fs::read_dir(game.path()).unwrap().count(), 0);
+	}
+
+	#[test]
+	fn resumes_from_checkpoints() {
+		let (patches, game) = (TempDirectory::new(), TempDirectory::new());
+		let patch = write_patch(&patches);
+
+		Applier::new(game.path())
+			.with_checkpoint(Checkpoint {
+				patch: NAME.into(),
+				chunk: 2,
+			})
+			.apply_patch(0, &patch, |_| {})
+			.unwrap();
+
+		let sqpack = game.path().join("sqpack/ffxiv");
+		assert!(!sqpack.join("000000.win32.index").exists());
+		assert!(sqpack.join("000000.win32.dat0").exists());
+	}
+}
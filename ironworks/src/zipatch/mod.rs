@@ -1,5 +1,6 @@
 //! Adapters to allow working with game data directly out of ZiPatch files.
 
+mod apply;
 mod lookup;
 mod repository;
 mod utility;
@@ -7,6 +8,7 @@ mod view;
 mod zipatch;
 
 pub use {
+	apply::{Action, Applier, Checkpoint, Progress},
 	repository::{Patch, PatchRepository},
 	view::View,
 	zipatch::ZiPatch,
@@ -7,7 +7,7 @@ use std::{
 use crate::error::Result;
 
 /// Representation of a single patch file.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Patch {
 	/// Canonical name of the patch. Typically conforms to the format Y.M.D.P.Rp,
 	/// where \[Y]ear, \[M]onth, \[D]ay, \[P]art, \[R]evision, \[p]art-but-for-HISTs
@@ -17,7 +17,7 @@ pub struct Patch {
 }
 
 /// Representation of a folder containing patch files.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PatchRepository {
 	/// List of patches in this repository
 	pub patches: Vec<Patch>,
@@ -55,7 +55,45 @@ impl PatchRepository {
 		Ok(Self { patches })
 	}
 
-	// TODO: fn before - so i.e. a simple use case can `.at().before()` to get a repo of a folder containing patches before a particular point.
+	/// Retain only patches prior to the specified version. Versions are
+	/// compared with their patch kind prefix and HIST part suffix removed, i.e.
+	/// `2023.09.05.0000.0000`.
+	pub fn before(self, version: &str) -> Self {
+		let version = patch_version(version);
+		self.filter(|patch| patch < version)
+	}
+
+	/// Retain only patches following the specified version.
+	pub fn after(self, version: &str) -> Self {
+		let version = patch_version(version);
+		self.filter(|patch| patch > version)
+	}
+
+	/// Retain only patches between the specified versions, inclusive.
+	pub fn between(self, start: &str, end: &str) -> Self {
+		let (start, end) = (patch_version(start), patch_version(end));
+		self.filter(|patch| (start..=end).contains(&patch))
+	}
+
+	/// Retain only patches up to and including the specified version.
+	pub(super) fn until(self, version: &str) -> Self {
+		let version = patch_version(version);
+		self.filter(|patch| patch <= version)
+	}
+
+	fn filter(mut self, predicate: impl Fn(&str) -> bool) -> Self {
+		self.patches
+			.retain(|patch| predicate(patch_version(&patch.name)));
+		self
+	}
+}
+
+/// Version of a patch, without the leading [D]IFF or [H]IST kind character,
+/// or the trailing part letter of HIST patches.
+pub(super) fn patch_version(name: &str) -> &str {
+	name.strip_prefix(['D', 'H'])
+		.unwrap_or(name)
+		.trim_end_matches(|char: char| char.is_ascii_alphabetic())
 }
 
 fn sort_patches(Patch { name: a, .. }: &Patch, Patch { name: b, .. }: &Patch) -> Ordering {
@@ -67,3 +105,68 @@ fn sort_patches(Patch { name: a, .. }: &Patch, Patch { name: b, .. }: &Patch) ->
 		order => order,
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::{Patch, PatchRepository};
+
+	fn repository() -> PatchRepository {
+		let names = [
+			"H2017.06.06.0000.0001a",
+			"D2017.06.06.0000.0001",
+			"D2017.07.11.0000.0000",
+			"D2017.08.01.0000.0000",
+		];
+		PatchRepository {
+			patches: names
+				.into_iter()
+				.map(|name| Patch {
+					name: name.into(),
+					path: format!("{name}.patch").into(),
+				})
+				.collect(),
+		}
+	}
+
+	fn names(repository: PatchRepository) -> Vec<String> {
+		repository
+			.patches
+			.into_iter()
+			.map(|patch| patch.name)
+			.collect()
+	}
+
+	#[test]
+	fn filters_by_version() {
+		assert_eq!(
+			names(repository().before("2017.07.11.0000.0000")),
+			["H2017.06.06.0000.0001a", "D2017.06.06.0000.0001"]
+		);
+		assert_eq!(
+			names(repository().after("D2017.07.11.0000.0000")),
+			["D2017.08.01.0000.0000"]
+		);
+		assert_eq!(
+			names(repository().between("2017.06.06.0000.0001", "2017.07.11.0000.0000")),
+			[
+				"H2017.06.06.0000.0001a",
+				"D2017.06.06.0000.0001",
+				"D2017.07.11.0000.0000"
+			]
+		);
+
+		// HIST patches share the version of the DIFF they precede.
+		assert_eq!(
+			names(repository().until("2017.06.06.0000.0001")),
+			["H2017.06.06.0000.0001a", "D2017.06.06.0000.0001"]
+		);
+		assert_eq!(
+			names(repository().after("2017.06.06.0000.0001")),
+			["D2017.07.11.0000.0000", "D2017.08.01.0000.0000"]
+		);
+		assert_eq!(
+			names(repository().before("2017.06.06.0000.0001")),
+			Vec::<String>::new()
+		);
+	}
+}
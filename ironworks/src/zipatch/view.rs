@@ -2,6 +2,8 @@ use std::{
 	collections::HashMap,
 	fs,
 	io::{self, BufReader, Cursor, Seek, SeekFrom},
+	mem,
+	path::Path,
 	sync::Arc,
 };
 
@@ -47,6 +49,61 @@ impl ViewBuilder {
 		self.repositories.insert(id, Arc::new(repository));
 	}
 
+	/// Add the patch repositories within a patch root, laid out as `game/` for
+	/// the base game and `ex1/`..`exN/` for expansions. Other directories, such
+	/// as `boot/`, are ignored.
+	pub fn with_patch_root(mut self, root: &Path) -> Result<Self> {
+		self.add_patch_root(root)?;
+		Ok(self)
+	}
+
+	/// Add the patch repositories within a patch root, laid out as `game/` for
+	/// the base game and `ex1/`..`exN/` for expansions. Other directories, such
+	/// as `boot/`, are ignored.
+	pub fn add_patch_root(&mut self, root: &Path) -> Result<()> {
+		for entry in fs::read_dir(root)? {
+			let entry = entry?;
+			if !entry.file_type()?.is_dir() {
+				continue;
+			}
+
+			let name = entry.file_name();
+			let id = match name.to_str() {
+				Some("game") => 0,
+				Some(name) => match name.strip_prefix("ex").and_then(|id| id.parse().ok()) {
+					Some(id) => id,
+					None => continue,
+				},
+				None => continue,
+			};
+
+			self.add_repository(id, PatchRepository::at(&entry.path())?);
+		}
+
+		Ok(())
+	}
+
+	/// Limit the view to the game as of the specified version, excluding any
+	/// later patches. Repositories without patches as of the version, i.e.
+	/// unreleased expansions, are removed.
+	pub fn with_version(mut self, version: &str) -> Self {
+		self.set_version(version);
+		self
+	}
+
+	/// Limit the view to the game as of the specified version, excluding any
+	/// later patches. Repositories without patches as of the version, i.e.
+	/// unreleased expansions, are removed.
+	pub fn set_version(&mut self, version: &str) {
+		self.repositories = mem::take(&mut self.repositories)
+			.into_iter()
+			.filter_map(|(id, repository)| {
+				let repository = Arc::unwrap_or_clone(repository).until(version);
+				(!repository.patches.is_empty()).then(|| (id, Arc::new(repository)))
+			})
+			.collect();
+	}
+
 	pub fn build(self) -> View {
 		View::new(self.repositories, self.cache)
 	}
@@ -307,3 +364,31 @@ fn read_file_chunks(
 
 	Ok(Either::Right(block_stream))
 }
+
+#[cfg(test)]
+mod test {
+	use crate::{sqpack::Resource, utility::TempDirectory, zipatch::ZiPatch};
+
+	#[test]
+	fn discovers_patch_roots() {
+		let root = TempDirectory::with_files(&[
+			("boot/D2017.06.01.0000.0000.patch", ""),
+			("game/D2017.06.06.0000.0000.patch", ""),
+			("game/D2017.08.01.0000.0000.patch", ""),
+			("ex1/D2017.07.11.0000.0000.patch", ""),
+			("ex2/D2019.07.02.0000.0000.patch", ""),
+		]);
+
+		let view = ZiPatch::new()
+			.view()
+			.with_patch_root(root.path())
+			.unwrap()
+			.with_version("2017.07.11.0000.0000")
+			.build();
+
+		let mut ids = view.repositories.keys().copied().collect::<Vec<_>>();
+		ids.sort();
+		assert_eq!(ids, [0, 1]);
+		assert_eq!(view.version(0).unwrap(), "D2017.06.06.0000.0000");
+	}
+}
@@ -1,6 +1,6 @@
 use std::{
 	collections::{HashMap, hash_map::Entry},
-	path::PathBuf,
+	path::{Path, PathBuf},
 	sync::{
 		Arc, Condvar, Mutex,
 		atomic::{AtomicBool, Ordering},
@@ -9,7 +9,11 @@ use std::{
 
 use crate::error::Result;
 
-use super::{lookup::PatchLookup, repository::Patch, view::ViewBuilder};
+use super::{
+	lookup::PatchLookup,
+	repository::Patch,
+	view::{View, ViewBuilder},
+};
 
 /// A struct providing access to data contained in ZiPatch-formatted patch files.
 #[derive(Debug)]
@@ -42,6 +46,16 @@ impl ZiPatch {
 	pub fn view(&self) -> ViewBuilder {
 		ViewBuilder::new(self.cache.clone())
 	}
+
+	/// Build a view of the game as of the specified version, from a patch root
+	/// laid out as `game/` and `ex1/`..`exN/` patch repositories.
+	pub fn view_at(&self, root: &Path, version: &str) -> Result<View> {
+		Ok(self
+			.view()
+			.with_patch_root(root)?
+			.with_version(version)
+			.build())
+	}
 }
 
 impl Default for ZiPatch {
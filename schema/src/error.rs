@@ -11,6 +11,16 @@ pub enum Error {
 	#[error("Schema error: {0}.")]
 	Schema(String),
 
+	/// An error occured while reading Excel data.
+	#[cfg(feature = "excel")]
+	#[error("{0}")]
+	Excel(String),
+
+	/// An error occured while writing output.
+	#[cfg(feature = "excel")]
+	#[error("{0}")]
+	Io(String),
+
 	/// An error occured while working with a git repository.
 	#[cfg(feature = "git2")]
 	#[error("{0}")]
@@ -24,6 +34,28 @@ impl From<git2::Error> for Error {
 	}
 }
 
+#[cfg(feature = "excel")]
+impl From<ironworks::Error> for Error {
+	fn from(error: ironworks::Error) -> Self {
+		match error {
+			ironworks::Error::NotFound(ironworks::ErrorValue::Sheet(name)) => {
+				Error::NotFound(ErrorValue::Sheet(name))
+			}
+			ironworks::Error::NotFound(value) => {
+				Error::NotFound(ErrorValue::Other(value.to_string()))
+			}
+			error => Error::Excel(error.to_string()),
+		}
+	}
+}
+
+#[cfg(feature = "excel")]
+impl From<std::io::Error> for Error {
+	fn from(error: std::io::Error) -> Self {
+		Error::Io(error.to_string())
+	}
+}
+
 /// A value associated with an error.
 #[derive(Debug, Clone)]
 #[non_exhaustive]
@@ -56,3 +88,27 @@ impl fmt::Display for ErrorValue {
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[cfg(all(test, feature = "excel"))]
+mod test {
+	use super::{Error, ErrorValue};
+
+	#[test]
+	fn keeps_excel_not_found() {
+		let error = Error::from(ironworks::Error::NotFound(ironworks::ErrorValue::Sheet(
+			"Item".into(),
+		)));
+		assert!(matches!(error, Error::NotFound(ErrorValue::Sheet(name)) if name == "Item"));
+
+		let error = Error::from(ironworks::Error::NotFound(ironworks::ErrorValue::Path(
+			"exd/item.exh".into(),
+		)));
+		assert!(matches!(error, Error::NotFound(ErrorValue::Other(_))));
+
+		let error = Error::from(ironworks::Error::Invalid(
+			ironworks::ErrorValue::Path("exd/item.exh".into()),
+			"truncated".into(),
+		));
+		assert!(matches!(error, Error::Excel(_)));
+	}
+}
@@ -0,0 +1,407 @@
+use std::{collections::BTreeSet, fmt};
+
+use ironworks::{
+	Resource,
+	excel::{Excel, Field, Row, Sheet},
+	file::exh,
+};
+
+use crate::{
+	error::{Error, Result},
+	schema::Schema,
+};
+
+use super::value::column_paths;
+
+/// A difference between two versions of an Excel database.
+#[derive(Debug)]
+pub enum Change {
+	/// A sheet present only in the new database.
+	SheetAdded(String),
+
+	/// A sheet present only in the old database.
+	SheetRemoved(String),
+
+	/// The column layout of a sheet differs between the databases.
+	Columns {
+		/// Name of the sheet.
+		sheet: String,
+		/// Columns of the sheet in the old database.
+		old: Vec<exh::ColumnDefinition>,
+		/// Columns of the sheet in the new database.
+		new: Vec<exh::ColumnDefinition>,
+	},
+
+	/// A (sub)row present only in the new database.
+	RowAdded(RowKey),
+
+	/// A (sub)row present only in the old database.
+	RowRemoved(RowKey),
+
+	/// A (sub)row with fields that differ between the databases.
+	RowChanged {
+		/// The (sub)row that changed.
+		row: RowKey,
+		/// The fields that differ, in column order.
+		fields: Vec<FieldChange>,
+	},
+}
+
+/// Location of a (sub)row within a database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowKey {
+	/// Name of the sheet containing the row.
+	pub sheet: String,
+	/// ID of the row.
+	pub row_id: u32,
+	/// Subrow ID of the row.
+	pub subrow_id: u16,
+}
+
+/// A field that differs between two versions of a row.
+#[derive(Debug)]
+pub struct FieldChange {
+	/// Index of the column containing the field.
+	pub column: usize,
+	/// Path of the schema field reading the column, if a schema was provided and
+	/// describes it.
+	pub name: Option<String>,
+	/// Value of the field in the old database, if the column exists there.
+	pub old: Option<Field>,
+	/// Value of the field in the new database, if the column exists there.
+	pub new: Option<Field>,
+}
+
+/// Engine comparing the sheets of two Excel databases.
+///
+/// Rows are read from both databases through their sheet caches, and any
+/// failure to read a row is returned rather than reported as a change. Changes
+/// are reported in sheet name order, then row order.
+pub struct ExcelDiff<'a, A, B> {
+	old: &'a Excel<A>,
+	new: &'a Excel<B>,
+	schema: Option<&'a dyn Schema>,
+}
+
+impl<'a, A: Resource, B: Resource> ExcelDiff<'a, A, B> {
+	/// Create a diff between an old and new database.
+	pub fn new(old: &'a Excel<A>, new: &'a Excel<B>) -> Self {
+		Self {
+			old,
+			new,
+			schema: None,
+		}
+	}
+
+	/// Set the schema used to name changed fields.
+	pub fn with_schema(mut self, schema: &'a dyn Schema) -> Self {
+		self.set_schema(schema);
+		self
+	}
+
+	/// Set the schema used to name changed fields.
+	pub fn set_schema(&mut self, schema: &'a dyn Schema) {
+		self.schema = Some(schema);
+	}
+
+	/// Compare every sheet in the databases, passing each change found to
+	/// `visit`.
+	pub fn diff(&self, mut visit: impl FnMut(Change)) -> Result<()> {
+		let names = |list: &ironworks::file::exl::ExcelList| {
+			list.iter()
+				.map(|(name, _)| name.into_owned())
+				.collect::<BTreeSet<_>>()
+		};
+		let old = names(self.old.list()?);
+		let new = names(self.new.list()?);
+
+		for sheet in old.union(&new) {
+			match (old.contains(sheet), new.contains(sheet)) {
+				(true, false) => visit(Change::SheetRemoved(sheet.clone())),
+				(false, true) => visit(Change::SheetAdded(sheet.clone())),
+				_ => self.diff_sheet(sheet, &mut visit)?,
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Compare a sheet present in both databases, passing each change found to
+	/// `visit`. Every row ID covered by either sheet's pages is compared, along
+	/// with each of its subrows.
+	pub fn diff_sheet(&self, sheet: &str, mut visit: impl FnMut(Change)) -> Result<()> {
+		let old_sheet = self.old.sheet(sheet)?;
+		let new_sheet = self.new.sheet(sheet)?;
+
+		let old_columns = old_sheet.columns()?;
+		let new_columns = new_sheet.columns()?;
+		if layout(&old_columns) != layout(&new_columns) {
+			visit(Change::Columns {
+				sheet: sheet.to_string(),
+				old: old_columns.clone(),
+				new: new_columns.clone(),
+			});
+		}
+
+		let names = self.column_names(sheet, &new_columns)?;
+		let column_count = old_columns.len().max(new_columns.len());
+
+		let mut ranges = [&old_sheet.pages()?, &new_sheet.pages()?]
+			.into_iter()
+			.flatten()
+			.map(|page| {
+				(
+					page.start_id(),
+					page.start_id().saturating_add(page.row_count()),
+				)
+			})
+			.collect::<Vec<_>>();
+		ranges.sort_unstable();
+
+		// Pages of the two sheets may overlap, so each row ID is visited once by
+		// continuing past any that have been compared already.
+		let mut next_id = 0;
+		for (start, end) in ranges {
+			for row_id in start.max(next_id)..end {
+				// Subrow IDs are contiguous, so a row ends at the first subrow missing
+				// from both sheets.
+				for subrow_id in 0..=u16::MAX {
+					let old = read_row(&old_sheet, row_id, subrow_id)?;
+					let new = read_row(&new_sheet, row_id, subrow_id)?;
+
+					let (old, new) = match (old, new) {
+						(None, None) => break,
+						(Some(old), None) => {
+							visit(Change::RowRemoved(row_key(sheet, &old)));
+							continue;
+						}
+						(None, Some(new)) => {
+							visit(Change::RowAdded(row_key(sheet, &new)));
+							continue;
+						}
+						(Some(old), Some(new)) => (old, new),
+					};
+
+					let mut fields = vec![];
+					for column in 0..column_count {
+						let old_field = read_field(&old, column)?;
+						let new_field = read_field(&new, column)?;
+						if !fields_equal(old_field.as_ref(), new_field.as_ref()) {
+							fields.push(FieldChange {
+								column,
+								name: names.get(column).cloned().flatten(),
+								old: old_field,
+								new: new_field,
+							});
+						}
+					}
+
+					if !fields.is_empty() {
+						visit(Change::RowChanged {
+							row: row_key(sheet, &new),
+							fields,
+						});
+					}
+				}
+			}
+			next_id = next_id.max(end);
+		}
+
+		Ok(())
+	}
+
+	fn column_names(
+		&self,
+		sheet: &str,
+		columns: &[exh::ColumnDefinition],
+	) -> Result<Vec<Option<String>>> {
+		let schema = match self.schema.map(|schema| schema.sheet(sheet)) {
+			None | Some(Err(Error::NotFound(_))) => return Ok(vec![]),
+			Some(other) => other?,
+		};
+
+		Ok(column_paths(&schema, columns))
+	}
+}
+
+impl<A, B> fmt::Debug for ExcelDiff<'_, A, B> {
+	fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		formatter.debug_struct("ExcelDiff").finish_non_exhaustive()
+	}
+}
+
+/// Read a (sub)row, or `None` if the sheet does not contain it.
+fn read_row<R: Resource>(
+	sheet: &Sheet<&str, R>,
+	row_id: u32,
+	subrow_id: u16,
+) -> Result<Option<Row>> {
+	match sheet.subrow(row_id, subrow_id) {
+		Ok(row) => Ok(Some(row)),
+		Err(ironworks::Error::NotFound(ironworks::ErrorValue::Row { .. })) => Ok(None),
+		Err(error) => Err(error.into()),
+	}
+}
+
+fn layout(columns: &[exh::ColumnDefinition]) -> Vec<(exh::ColumnKind, u16)> {
+	columns
+		.iter()
+		.map(|column| (column.kind(), column.offset()))
+		.collect()
+}
+
+fn row_key(sheet: &str, row: &Row) -> RowKey {
+	RowKey {
+		sheet: sheet.to_string(),
+		row_id: row.row_id(),
+		subrow_id: row.subrow_id(),
+	}
+}
+
+fn read_field(row: &Row, column: usize) -> Result<Option<Field>> {
+	match row.columns().get(column) {
+		Some(definition) => Ok(Some(row.field(definition)?)),
+		None => Ok(None),
+	}
+}
+
+fn fields_equal(old: Option<&Field>, new: Option<&Field>) -> bool {
+	use Field as F;
+	match (old, new) {
+		(None, None) => true,
+		(Some(F::String(old)), Some(F::String(new))) => old.as_bytes() == new.as_bytes(),
+		(Some(F::Bool(old)), Some(F::Bool(new))) => old == new,
+		(Some(F::I8(old)), Some(F::I8(new))) => old == new,
+		(Some(F::I16(old)), Some(F::I16(new))) => old == new,
+		(Some(F::I32(old)), Some(F::I32(new))) => old == new,
+		(Some(F::I64(old)), Some(F::I64(new))) => old == new,
+		(Some(F::U8(old)), Some(F::U8(new))) => old == new,
+		(Some(F::U16(old)), Some(F::U16(new))) => old == new,
+		(Some(F::U32(old)), Some(F::U32(new))) => old == new,
+		(Some(F::U64(old)), Some(F::U64(new))) => old == new,
+		// Compare bitwise, such that identical NaNs are not reported as changes.
+		(Some(F::F32(old)), Some(F::F32(new))) => old.to_bits() == new.to_bits(),
+		_ => false,
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use ironworks::{
+		excel::{Field, Language, path},
+		file::exh::ColumnKind,
+	};
+
+	use crate::{
+		error::Error,
+		excel::fixture::{Fixture, reference_schema},
+	};
+
+	use super::{Change, ExcelDiff};
+
+	#[test]
+	fn reports_changes() {
+		let u16 = (ColumnKind::UInt16, 0);
+		let old = Fixture::new()
+			.with_sheet(
+				"Quest",
+				&[u16],
+				[(1, vec![Field::U16(7)]), (2, vec![Field::U16(8)])],
+			)
+			.with_sheet("Action", &[u16], [(1, vec![Field::U16(1)])])
+			.excel();
+		let new = Fixture::new()
+			.with_sheet(
+				"Quest",
+				&[u16],
+				[(2, vec![Field::U16(9)]), (3, vec![Field::U16(1)])],
+			)
+			.with_sheet(
+				"Item",
+				&[u16, (ColumnKind::UInt8, 2)],
+				[(1, vec![Field::U16(1), Field::U8(2)])],
+			)
+			.excel();
+
+		let schema = reference_schema();
+		let mut changes = vec![];
+		ExcelDiff::new(&old, &new)
+			.with_schema(&schema)
+			.diff(|change| changes.push(change))
+			.unwrap();
+
+		assert_eq!(changes.len(), 5);
+		assert!(matches!(&changes[0], Change::SheetRemoved(sheet) if sheet == "Action"));
+		assert!(matches!(&changes[1], Change::SheetAdded(sheet) if sheet == "Item"));
+		assert!(matches!(&changes[2], Change::RowRemoved(row) if row.row_id == 1));
+
+		let Change::RowChanged { row, fields } = &changes[3] else {
+			panic!("expected row change, got {:?}", changes[3]);
+		};
+		assert_eq!((row.sheet.as_str(), row.row_id), ("Quest", 2));
+		assert_eq!(fields.len(), 1);
+		assert_eq!(fields[0].name.as_deref(), Some("Key"));
+		assert!(matches!(fields[0].old, Some(Field::U16(8))));
+		assert!(matches!(fields[0].new, Some(Field::U16(9))));
+
+		assert!(matches!(&changes[4], Change::RowAdded(row) if row.row_id == 3));
+	}
+
+	#[test]
+	fn reports_column_changes() {
+		let old = Fixture::new()
+			.with_sheet(
+				"Quest",
+				&[(ColumnKind::UInt16, 0)],
+				[(1, vec![Field::U16(7)])],
+			)
+			.excel();
+		let new = Fixture::new()
+			.with_sheet(
+				"Quest",
+				&[(ColumnKind::UInt16, 0), (ColumnKind::UInt8, 2)],
+				[(1, vec![Field::U16(7), Field::U8(3)])],
+			)
+			.excel();
+
+		let mut changes = vec![];
+		ExcelDiff::new(&old, &new)
+			.diff_sheet("Quest", |change| changes.push(change))
+			.unwrap();
+
+		assert_eq!(changes.len(), 2);
+		assert!(
+			matches!(&changes[0], Change::Columns { old, new, .. } if old.len() == 1 && new.len() == 2)
+		);
+		let Change::RowChanged { fields, .. } = &changes[1] else {
+			panic!("expected row change, got {:?}", changes[1]);
+		};
+		assert_eq!(fields[0].column, 1);
+		assert!(fields[0].name.is_none());
+		assert!(matches!(
+			(&fields[0].old, &fields[0].new),
+			(None, Some(Field::U8(3)))
+		));
+	}
+
+	#[test]
+	fn returns_read_errors() {
+		let quest = |fixture: Fixture| {
+			fixture.with_sheet(
+				"Quest",
+				&[(ColumnKind::UInt16, 0)],
+				[(1, vec![Field::U16(7)]), (2, vec![Field::U16(8)])],
+			)
+		};
+		let old = quest(Fixture::new()).excel();
+		let new = quest(Fixture::new())
+			.with_file(&path::exd("Quest", 1, Language::None), b"EXDF")
+			.excel();
+
+		let mut changes = vec![];
+		let result = ExcelDiff::new(&old, &new).diff_sheet("Quest", |change| changes.push(change));
+
+		assert!(matches!(result, Err(Error::Excel(_))));
+		assert!(changes.is_empty());
+	}
+}
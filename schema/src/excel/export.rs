@@ -0,0 +1,329 @@
+use std::{
+	borrow::Cow,
+	fmt::{self, Write as _},
+	io::Write,
+};
+
+use ironworks::{
+	Resource,
+	excel::{Excel, Field, Row},
+	file::exh,
+	sestring::SeString,
+};
+
+use crate::{
+	error::{Error, Result},
+	schema::Schema,
+};
+
+use super::value::column_paths;
+
+/// Representation used when exporting string fields.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StringFormat {
+	/// Plain text, with macros evaluated without any input.
+	#[default]
+	Plain,
+
+	/// Human-readable text preserving macros, i.e. `<If(...)>`.
+	Macro,
+}
+
+/// Exporter writing the rows of Excel sheets as CSV or JSON Lines.
+///
+/// Columns are named by the path of the schema field reading them, if a schema
+/// is provided and describes the column, and by their index otherwise. Rows in
+/// subrow sheets are keyed as `row.subrow`.
+pub struct Exporter<'a> {
+	schema: Option<&'a dyn Schema>,
+	string_format: StringFormat,
+}
+
+impl Default for Exporter<'_> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<'a> Exporter<'a> {
+	/// Create an exporter with no schema, writing strings as plain text.
+	pub fn new() -> Self {
+		Self {
+			schema: None,
+			string_format: StringFormat::Plain,
+		}
+	}
+
+	/// Set the schema used to name columns.
+	pub fn with_schema(mut self, schema: &'a dyn Schema) -> Self {
+		self.set_schema(schema);
+		self
+	}
+
+	/// Set the schema used to name columns.
+	pub fn set_schema(&mut self, schema: &'a dyn Schema) {
+		self.schema = Some(schema);
+	}
+
+	/// Set the representation used when exporting string fields.
+	pub fn with_string_format(mut self, string_format: StringFormat) -> Self {
+		self.set_string_format(string_format);
+		self
+	}
+
+	/// Set the representation used when exporting string fields.
+	pub fn set_string_format(&mut self, string_format: StringFormat) {
+		self.string_format = string_format;
+	}
+
+	/// Write the rows of a sheet as CSV. Following SaintCoinach, the rows are
+	/// preceded by rows of column indices, names, and types.
+	pub fn csv<R: Resource>(
+		&self,
+		excel: &Excel<R>,
+		sheet: &str,
+		mut writer: impl Write,
+	) -> Result<()> {
+		let source = excel.sheet(sheet)?;
+		let kind = source.kind()?;
+		let columns = source.columns()?;
+		let names = self.column_names(sheet, &columns)?;
+
+		let indices = (0..columns.len()).map(|index| index.to_string());
+		write_record(&mut writer, ["key".to_string()].into_iter().chain(indices))?;
+
+		let names = names.into_iter().map(Option::unwrap_or_default);
+		write_record(&mut writer, ["#".to_string()].into_iter().chain(names))?;
+
+		let types = columns.iter().map(|column| type_name(column.kind()));
+		write_record(&mut writer, ["int32".into()].into_iter().chain(types))?;
+
+		for row in source {
+			let mut record = vec![row_key(kind, &row)];
+			for column in &columns {
+				record.push(match row.field(column)? {
+					Field::String(string) => self.string(&string)?,
+					Field::Bool(value) => (if value { "True" } else { "False" }).into(),
+					Field::I8(value) => value.to_string(),
+					Field::I16(value) => value.to_string(),
+					Field::I32(value) => value.to_string(),
+					Field::I64(value) => value.to_string(),
+					Field::U8(value) => value.to_string(),
+					Field::U16(value) => value.to_string(),
+					Field::U32(value) => value.to_string(),
+					Field::U64(value) => value.to_string(),
+					Field::F32(value) => value.to_string(),
+				});
+			}
+			write_record(&mut writer, record)?;
+		}
+
+		Ok(())
+	}
+
+	/// Write the rows of a sheet as JSON Lines, one object per row. Each object
+	/// contains the row's `key` - a number, or a `row.subrow` string in subrow
+	/// sheets - followed by its fields in column order.
+	pub fn jsonl<R: Resource>(
+		&self,
+		excel: &Excel<R>,
+		sheet: &str,
+		mut writer: impl Write,
+	) -> Result<()> {
+		use serde_json::Value as J;
+
+		let source = excel.sheet(sheet)?;
+		let kind = source.kind()?;
+		let columns = source.columns()?;
+		let names = self
+			.column_names(sheet, &columns)?
+			.into_iter()
+			.enumerate()
+			.map(|(index, name)| J::String(name.unwrap_or_else(|| index.to_string())))
+			.collect::<Vec<_>>();
+
+		for row in source {
+			let key = match kind {
+				exh::SheetKind::Subrows => J::String(row_key(kind, &row)),
+				_ => J::from(row.row_id()),
+			};
+
+			// Objects are written by hand to keep keys in column order.
+			let mut line = format!("{{\"key\":{key}");
+			for (column, name) in columns.iter().zip(&names) {
+				let value = match row.field(column)? {
+					Field::String(string) => J::String(self.string(&string)?),
+					Field::Bool(value) => J::from(value),
+					Field::I8(value) => J::from(value),
+					Field::I16(value) => J::from(value),
+					Field::I32(value) => J::from(value),
+					Field::I64(value) => J::from(value),
+					Field::U8(value) => J::from(value),
+					Field::U16(value) => J::from(value),
+					Field::U32(value) => J::from(value),
+					Field::U64(value) => J::from(value),
+					Field::F32(value) => J::from(value),
+				};
+				write!(line, ",{name}:{value}").map_err(format_error)?;
+			}
+			writeln!(writer, "{line}}}")?;
+		}
+
+		Ok(())
+	}
+
+	fn column_names(
+		&self,
+		sheet: &str,
+		columns: &[exh::ColumnDefinition],
+	) -> Result<Vec<Option<String>>> {
+		let schema = match self.schema.map(|schema| schema.sheet(sheet)) {
+			None | Some(Err(Error::NotFound(_))) => return Ok(vec![None; columns.len()]),
+			Some(other) => other?,
+		};
+
+		Ok(column_paths(&schema, columns))
+	}
+
+	fn string(&self, string: &SeString) -> Result<String> {
+		let mut output = String::new();
+		match self.string_format {
+			StringFormat::Plain => write!(output, "{}", string.format()),
+			StringFormat::Macro => write!(output, "{}", string.macro_string()),
+		}
+		.map_err(format_error)?;
+		Ok(output)
+	}
+}
+
+impl fmt::Debug for Exporter<'_> {
+	fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		formatter
+			.debug_struct("Exporter")
+			.field("string_format", &self.string_format)
+			.finish_non_exhaustive()
+	}
+}
+
+fn row_key(kind: exh::SheetKind, row: &Row) -> String {
+	match kind {
+		exh::SheetKind::Subrows => format!("{}.{}", row.row_id(), row.subrow_id()),
+		_ => row.row_id().to_string(),
+	}
+}
+
+fn type_name(kind: exh::ColumnKind) -> String {
+	use exh::ColumnKind as K;
+	let name = match kind {
+		K::String => "str",
+		K::Bool => "bool",
+		K::Int8 => "sbyte",
+		K::UInt8 => "byte",
+		K::Int16 => "int16",
+		K::UInt16 => "uint16",
+		K::Int32 => "int32",
+		K::UInt32 => "uint32",
+		K::Float32 => "single",
+		K::Int64 => "int64",
+		K::UInt64 => "uint64",
+		packed => {
+			let bit = u16::from(packed) - u16::from(K::PackedBool0);
+			return format!("bit&{:02X}", 1u8 << bit);
+		}
+	};
+	name.into()
+}
+
+fn write_record(writer: &mut impl Write, record: impl IntoIterator<Item = String>) -> Result<()> {
+	let record = record
+		.into_iter()
+		.map(|field| escape(&field).into_owned())
+		.collect::<Vec<_>>();
+	writeln!(writer, "{}", record.join(","))?;
+	Ok(())
+}
+
+fn escape(field: &str) -> Cow<'_, str> {
+	match field.contains([',', '"', '\n', '\r']) {
+		true => format!("\"{}\"", field.replace('"', "\"\"")).into(),
+		false => field.into(),
+	}
+}
+
+fn format_error(_: fmt::Error) -> Error {
+	Error::Excel("failed to format string".into())
+}
+
+#[cfg(test)]
+mod test {
+	use ironworks::{
+		excel::{Field, Language, SheetWriter},
+		file::exh::{ColumnDefinition, ColumnKind, SheetKind},
+		sestring::SeString,
+	};
+
+	use crate::excel::fixture::{Fixture, reference_excel, reference_schema};
+
+	use super::Exporter;
+
+	#[test]
+	fn names_columns_from_schema() {
+		let (excel, schema) = (reference_excel(), reference_schema());
+		let mut output = vec![];
+		Exporter::new()
+			.with_schema(&schema)
+			.csv(&excel, "Item", &mut output)
+			.unwrap();
+
+		assert_eq!(
+			String::from_utf8(output).unwrap(),
+			"key,0,1,2\n#,Category,Kind,Link\nint32,uint16,byte,uint16\n1,5,1,7\n2,5,2,7\n"
+		);
+	}
+
+	#[test]
+	fn exports_strings_and_subrows() {
+		let mut recipe = SheetWriter::new(
+			"Recipe",
+			SheetKind::Subrows,
+			vec![
+				ColumnDefinition::new(ColumnKind::UInt8, 0),
+				ColumnDefinition::new(ColumnKind::PackedBool1, 1),
+			],
+		);
+		for subrow_id in [0, 1] {
+			let fields = vec![Field::U8(subrow_id as u8 + 1), Field::Bool(subrow_id == 0)];
+			recipe
+				.set_row(Language::None, 3, subrow_id, fields)
+				.unwrap();
+		}
+
+		let excel = Fixture::new()
+			.with_sheet(
+				"Addon",
+				&[(ColumnKind::String, 0)],
+				[(
+					1,
+					vec![Field::String(SeString::from(b"Hi, \"you\"".to_vec()))],
+				)],
+			)
+			.with_writer("Recipe", &recipe)
+			.excel();
+
+		let mut output = vec![];
+		Exporter::new().csv(&excel, "Addon", &mut output).unwrap();
+		assert_eq!(
+			String::from_utf8(output).unwrap(),
+			"key,0\n#,\nint32,str\n1,\"Hi, \"\"you\"\"\"\n"
+		);
+
+		let mut output = vec![];
+		Exporter::new()
+			.jsonl(&excel, "Recipe", &mut output)
+			.unwrap();
+		assert_eq!(
+			String::from_utf8(output).unwrap(),
+			"{\"key\":\"3.0\",\"0\":1,\"1\":true}\n{\"key\":\"3.1\",\"0\":2,\"1\":false}\n"
+		);
+	}
+}
@@ -0,0 +1,176 @@
+use std::{collections::HashMap, io::Cursor};
+
+use ironworks::{
+	FileStream, Ironworks, Resource,
+	excel::{Excel, Field, Language, SheetWriter},
+	file::exh::{ColumnDefinition, ColumnKind, SheetKind},
+};
+
+use crate::{
+	error::{Error, ErrorValue, Result},
+	schema::{
+		Node, Order, ReferenceCondition, ReferenceTarget, Scalar, Schema, Sheet, StructField,
+	},
+};
+
+/// In-memory Excel database, built from sheet writers.
+pub struct Fixture {
+	sheets: Vec<String>,
+	files: HashMap<String, Vec<u8>>,
+}
+
+impl Fixture {
+	pub fn new() -> Self {
+		Self {
+			sheets: vec![],
+			files: HashMap::new(),
+		}
+	}
+
+	/// Add a sheet with the given `(kind, offset)` columns, and rows of fields
+	/// in column order.
+	pub fn with_sheet(
+		self,
+		name: &str,
+		columns: &[(ColumnKind, u16)],
+		rows: impl IntoIterator<Item = (u32, Vec<Field>)>,
+	) -> Self {
+		let columns = columns
+			.iter()
+			.map(|(kind, offset)| ColumnDefinition::new(*kind, *offset))
+			.collect();
+		let mut writer = SheetWriter::new(name, SheetKind::Default, columns);
+		for (row_id, fields) in rows {
+			writer.set_row(Language::None, row_id, 0, fields).unwrap();
+		}
+		self.with_writer(name, &writer)
+	}
+
+	/// Add a sheet built by a writer.
+	pub fn with_writer(mut self, name: &str, writer: &SheetWriter) -> Self {
+		self.sheets.push(name.to_string());
+		self.files.extend(writer.write().unwrap());
+		self
+	}
+
+	/// Replace the contents of a file, such as a page written by an earlier
+	/// sheet.
+	pub fn with_file(mut self, path: &str, data: &[u8]) -> Self {
+		self.files.insert(path.into(), data.to_vec());
+		self
+	}
+
+	pub fn excel(mut self) -> Excel<Fixture> {
+		let mut list = "EXLT,2".to_string();
+		for sheet in &self.sheets {
+			list.push_str(&format!("\r\n{sheet},-1"));
+		}
+		self.files.insert("exd/root.exl".into(), list.into_bytes());
+
+		Excel::new(Ironworks::new().with_resource(self))
+	}
+}
+
+impl Resource for Fixture {
+	fn version(&self, _path: &str) -> Result<String, ironworks::Error> {
+		Ok("1".into())
+	}
+
+	fn file(&self, path: &str) -> Result<Box<dyn FileStream>, ironworks::Error> {
+		let data = self
+			.files
+			.get(path)
+			.ok_or_else(|| ironworks::Error::NotFound(ironworks::ErrorValue::Path(path.into())))?;
+		Ok(Box::new(Cursor::new(data.clone())))
+	}
+}
+
+/// Schema backed by a fixed set of sheet schemas.
+pub struct TestSchema(HashMap<String, Sheet>);
+
+impl Schema for TestSchema {
+	fn sheet(&self, name: &str) -> Result<Sheet> {
+		self.0
+			.get(name)
+			.cloned()
+			.ok_or_else(|| Error::NotFound(ErrorValue::Sheet(name.into())))
+	}
+}
+
+fn target(sheet: &str, selector: Option<&str>, condition: Option<u32>) -> ReferenceTarget {
+	ReferenceTarget {
+		sheet: sheet.into(),
+		selector: selector.map(String::from),
+		condition: condition.map(|value| ReferenceCondition {
+			selector: "Kind".into(),
+			value,
+		}),
+	}
+}
+
+/// Schema for the sheets in [`reference_excel`].
+pub fn reference_schema() -> TestSchema {
+	let sheet = |name: &str, fields: Vec<(&str, Scalar)>| {
+		let fields = fields
+			.into_iter()
+			.enumerate()
+			.map(|(offset, (name, scalar))| StructField {
+				offset: offset as u32,
+				name: name.into(),
+				node: Node::Scalar(scalar),
+			})
+			.collect();
+		let sheet = Sheet {
+			name: name.into(),
+			order: Order::Index,
+			node: Node::Struct(fields),
+		};
+		(name.to_string(), sheet)
+	};
+
+	TestSchema(HashMap::from([
+		sheet(
+			"Item",
+			vec![
+				(
+					"Category",
+					Scalar::Reference(vec![target("ItemUICategory", None, None)]),
+				),
+				("Kind", Scalar::Default),
+				(
+					"Link",
+					Scalar::Reference(vec![
+						target("Quest", Some("Key"), Some(1)),
+						target("Action", None, Some(2)),
+					]),
+				),
+			],
+		),
+		sheet(
+			"ItemUICategory",
+			vec![("Item", Scalar::Reference(vec![target("Item", None, None)]))],
+		),
+		sheet("Quest", vec![("Key", Scalar::Default)]),
+	]))
+}
+
+/// Database of sheets referencing each other, including conditional, selected,
+/// and cyclic references. `Action` has no schema.
+pub fn reference_excel() -> Excel<Fixture> {
+	let u16 = (ColumnKind::UInt16, 0);
+	let item = |kind| vec![Field::U16(5), Field::U8(kind), Field::U16(7)];
+	Fixture::new()
+		.with_sheet(
+			"Item",
+			&[u16, (ColumnKind::UInt8, 2), (ColumnKind::UInt16, 4)],
+			[(1, item(1)), (2, item(2))],
+		)
+		.with_sheet("ItemUICategory", &[u16], [(5, vec![Field::U16(1)])])
+		.with_sheet(
+			"Quest",
+			&[u16],
+			[(1, vec![Field::U16(7)]), (2, vec![Field::U16(8)])],
+		)
+		.with_sheet("Action", &[u16], [(7, vec![Field::U16(0)])])
+		.excel()
+}
@@ -0,0 +1,306 @@
+use std::{
+	collections::{HashMap, hash_map::Entry},
+	io::{self, BufRead, Write},
+	str::FromStr,
+};
+
+use ironworks::{Resource, excel::Excel};
+
+use crate::{
+	error::{Error, Result},
+	schema::{ReferenceTarget, Schema, Sheet},
+};
+
+use super::{
+	resolve::{collect_references, condition_holds, field_key},
+	value::Value,
+};
+
+const HEADER: &str = "ironworks reference index v1";
+
+/// A row containing a reference to another row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BackLink {
+	/// Name of the sheet containing the referencing row.
+	pub sheet: String,
+	/// ID of the referencing row.
+	pub row_id: u32,
+	/// Subrow ID of the referencing row.
+	pub subrow_id: u16,
+	/// Path of the referencing field within the row.
+	pub path: String,
+}
+
+/// Index of the references between rows described by a schema, answering which
+/// rows link to any given row.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ReferenceIndex {
+	links: HashMap<String, HashMap<u32, Vec<BackLink>>>,
+}
+
+impl ReferenceIndex {
+	/// Build an index of the references from every sheet in the database. Sheets
+	/// without a schema are skipped.
+	pub fn build<R: Resource>(excel: &Excel<R>, schema: &dyn Schema) -> Result<Self> {
+		let sheets = excel
+			.list()?
+			.iter()
+			.map(|(name, _)| name.into_owned())
+			.collect::<Vec<_>>();
+
+		Self::build_sheets(excel, schema, sheets)
+	}
+
+	/// Build an index of the references from the specified sheets. Sheets
+	/// without a schema are skipped.
+	pub fn build_sheets<R: Resource>(
+		excel: &Excel<R>,
+		schema: &dyn Schema,
+		sheets: impl IntoIterator<Item = impl AsRef<str>>,
+	) -> Result<Self> {
+		let mut index = Self::default();
+		let mut keys = TargetKeys::default();
+
+		for name in sheets {
+			let name = name.as_ref();
+			let sheet_schema = match schema.sheet(name) {
+				Err(Error::NotFound(_)) => continue,
+				other => other?,
+			};
+
+			for row in excel.sheet(name)? {
+				let value = Value::read(&sheet_schema, &row)?;
+				let mut references = vec![];
+				collect_references(&sheet_schema.node, &value, String::new(), &mut references);
+
+				for (path, targets, field) in references {
+					let Some(key) = field_key(field) else {
+						continue;
+					};
+
+					for target in targets {
+						if !condition_holds(target, &value) {
+							continue;
+						}
+
+						for &target_row in keys.rows(excel, schema, target, key)? {
+							index.insert(
+								&target.sheet,
+								target_row,
+								BackLink {
+									sheet: name.to_string(),
+									row_id: row.row_id(),
+									subrow_id: row.subrow_id(),
+									path: path.clone(),
+								},
+							);
+						}
+					}
+				}
+			}
+		}
+
+		Ok(index)
+	}
+
+	/// Get the rows that link to the specified row.
+	pub fn links(&self, sheet: &str, row_id: u32) -> &[BackLink] {
+		self.links
+			.get(sheet)
+			.and_then(|rows| rows.get(&row_id))
+			.map_or(&[], Vec::as_slice)
+	}
+
+	/// Iterate over the rows in a sheet that are linked to, along with the rows
+	/// that link to them, in arbitrary order.
+	pub fn sheet_links(&self, sheet: &str) -> impl Iterator<Item = (u32, &[BackLink])> {
+		self.links
+			.get(sheet)
+			.into_iter()
+			.flatten()
+			.map(|(row_id, links)| (*row_id, links.as_slice()))
+	}
+
+	/// Write the index in a line-based text format, that can be read back with
+	/// [`ReferenceIndex::read`].
+	pub fn write(&self, mut writer: impl Write) -> io::Result<()> {
+		writeln!(writer, "{HEADER}")?;
+
+		let mut sheets = self.links.iter().collect::<Vec<_>>();
+		sheets.sort_by_key(|(sheet, _)| *sheet);
+		for (sheet, rows) in sheets {
+			let mut rows = rows.iter().collect::<Vec<_>>();
+			rows.sort_by_key(|(row_id, _)| **row_id);
+			for (row_id, links) in rows {
+				for link in links {
+					writeln!(
+						writer,
+						"{sheet}\t{row_id}\t{}\t{}\t{}\t{}",
+						link.sheet, link.row_id, link.subrow_id, link.path
+					)?;
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Read an index previously written with [`ReferenceIndex::write`].
+	pub fn read(reader: impl BufRead) -> io::Result<Self> {
+		let mut lines = reader.lines();
+		if lines.next().transpose()?.as_deref() != Some(HEADER) {
+			return Err(invalid("unrecognised reference index header"));
+		}
+
+		let mut index = Self::default();
+		for line in lines {
+			let line = line?;
+			let fields = line.split('\t').collect::<Vec<_>>();
+			let [sheet, row_id, source, source_row, source_subrow, path] = fields[..] else {
+				return Err(invalid("malformed reference index entry"));
+			};
+
+			index.insert(
+				sheet,
+				parse(row_id)?,
+				BackLink {
+					sheet: source.to_string(),
+					row_id: parse(source_row)?,
+					subrow_id: parse(source_subrow)?,
+					path: path.to_string(),
+				},
+			);
+		}
+
+		Ok(index)
+	}
+
+	fn insert(&mut self, sheet: &str, row_id: u32, link: BackLink) {
+		self.links
+			.entry(sheet.to_string())
+			.or_default()
+			.entry(row_id)
+			.or_default()
+			.push(link);
+	}
+}
+
+fn parse<T: FromStr>(value: &str) -> io::Result<T> {
+	value
+		.parse()
+		.map_err(|_| invalid("malformed reference index row ID"))
+}
+
+fn invalid(message: &str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Lookup of the rows matching reference keys, per target sheet and selector.
+#[derive(Default)]
+struct TargetKeys(HashMap<(String, Option<String>), HashMap<i64, Vec<u32>>>);
+
+impl TargetKeys {
+	fn rows<R: Resource>(
+		&mut self,
+		excel: &Excel<R>,
+		schema: &dyn Schema,
+		target: &ReferenceTarget,
+		key: i64,
+	) -> Result<&[u32]> {
+		let keys = match self
+			.0
+			.entry((target.sheet.clone(), target.selector.clone()))
+		{
+			Entry::Occupied(entry) => entry.into_mut(),
+			Entry::Vacant(entry) => entry.insert(target_keys(excel, schema, target)?),
+		};
+
+		Ok(keys.get(&key).map_or(&[], Vec::as_slice))
+	}
+}
+
+fn target_keys<R: Resource>(
+	excel: &Excel<R>,
+	schema: &dyn Schema,
+	target: &ReferenceTarget,
+) -> Result<HashMap<i64, Vec<u32>>> {
+	let mut keys = HashMap::<i64, Vec<u32>>::new();
+
+	let sheet = match excel.sheet(target.sheet.as_str()) {
+		Err(ironworks::Error::NotFound(_)) => return Ok(keys),
+		other => other?,
+	};
+
+	// Without a selector, rows are keyed by their ID.
+	let selection: Option<(&str, Sheet)> = match &target.selector {
+		None => None,
+		Some(selector) => match schema.sheet(&target.sheet) {
+			Err(Error::NotFound(_)) => return Ok(keys),
+			other => Some((selector, other?)),
+		},
+	};
+
+	for row in sheet {
+		let key = match &selection {
+			None => Some(row.row_id().into()),
+			Some((selector, sheet_schema)) => Value::read(sheet_schema, &row)?
+				.get(selector)
+				.and_then(Value::as_field)
+				.and_then(field_key),
+		};
+
+		// Subrows share their parent's ID, only record it once.
+		if let Some(key) = key {
+			let rows = keys.entry(key).or_default();
+			if rows.last() != Some(&row.row_id()) {
+				rows.push(row.row_id());
+			}
+		}
+	}
+
+	Ok(keys)
+}
+
+#[cfg(test)]
+mod test {
+	use crate::excel::fixture::{reference_excel, reference_schema};
+
+	use super::{BackLink, ReferenceIndex};
+
+	fn link(sheet: &str, row_id: u32, path: &str) -> BackLink {
+		BackLink {
+			sheet: sheet.into(),
+			row_id,
+			subrow_id: 0,
+			path: path.into(),
+		}
+	}
+
+	#[test]
+	fn indexes_back_links() {
+		let index = ReferenceIndex::build(&reference_excel(), &reference_schema()).unwrap();
+
+		assert_eq!(
+			index.links("ItemUICategory", 5),
+			[link("Item", 1, "Category"), link("Item", 2, "Category")]
+		);
+		assert_eq!(index.links("Item", 1), [link("ItemUICategory", 5, "Item")]);
+		assert!(index.links("Item", 2).is_empty());
+
+		// Only the first item's link meets the quest target's condition.
+		assert_eq!(index.links("Quest", 1), [link("Item", 1, "Link")]);
+		assert!(index.links("Quest", 2).is_empty());
+		assert_eq!(index.sheet_links("Quest").count(), 1);
+	}
+
+	#[test]
+	fn round_trips_through_persistence() {
+		let index = ReferenceIndex::build(&reference_excel(), &reference_schema()).unwrap();
+
+		let mut buffer = vec![];
+		index.write(&mut buffer).unwrap();
+		assert_eq!(ReferenceIndex::read(buffer.as_slice()).unwrap(), index);
+
+		assert!(ReferenceIndex::read(&b"not an index\n"[..]).is_err());
+	}
+}
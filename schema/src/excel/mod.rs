@@ -0,0 +1,18 @@
+//! Adapters for reading Excel data with the structure described by a schema.
+
+mod diff;
+mod export;
+mod index;
+mod resolve;
+mod value;
+
+#[cfg(test)]
+mod fixture;
+
+pub use {
+	diff::{Change, ExcelDiff, FieldChange, RowKey},
+	export::{Exporter, StringFormat},
+	index::{BackLink, ReferenceIndex},
+	resolve::{Link, Resolver},
+	value::Value,
+};
@@ -0,0 +1,396 @@
+use std::{
+	collections::{BTreeMap, HashMap},
+	fmt,
+	sync::{Arc, Mutex},
+};
+
+use ironworks::{
+	Resource,
+	excel::{self, Excel, Field, Row},
+	file::exh::ColumnKind,
+};
+
+use crate::{
+	error::{Error, Result},
+	schema::{Node, ReferenceTarget, Scalar, Schema, Sheet},
+};
+
+use super::value::{Value, column_paths};
+
+/// Row linked to by a reference, along with the rows it links to in turn.
+#[derive(Debug)]
+pub struct Link {
+	/// Name of the sheet containing the row.
+	pub sheet: String,
+	/// ID of the row.
+	pub row_id: u32,
+	/// Subrow ID of the row.
+	pub subrow_id: u16,
+	/// Value of the row, read following its sheet schema. `None` if the sheet
+	/// has no schema.
+	pub value: Option<Value>,
+	/// Rows referenced by this row, keyed by the path of the referencing field.
+	/// Links are not expanded past the resolver's depth, nor for rows that are
+	/// already being expanded further up the chain, nor for rows without a
+	/// schema.
+	pub links: BTreeMap<String, Vec<Link>>,
+}
+
+type RowKey = (String, u32, u16);
+
+/// Resolver following the references described by a schema to the rows they
+/// link to.
+pub struct Resolver<'a, R> {
+	excel: &'a Excel<R>,
+	schema: &'a dyn Schema,
+	depth: usize,
+	sheets: Mutex<HashMap<String, Option<Arc<Sheet>>>>,
+}
+
+impl<'a, R: Resource> Resolver<'a, R> {
+	/// Create a resolver over the provided Excel database and schema. By default,
+	/// only references directly from the requested row are followed.
+	pub fn new(excel: &'a Excel<R>, schema: &'a dyn Schema) -> Self {
+		Self {
+			excel,
+			schema,
+			depth: 1,
+			sheets: Default::default(),
+		}
+	}
+
+	/// Set the number of references to follow in a chain before stopping.
+	pub fn with_depth(mut self, depth: usize) -> Self {
+		self.set_depth(depth);
+		self
+	}
+
+	/// Set the number of references to follow in a chain before stopping.
+	pub fn set_depth(&mut self, depth: usize) {
+		self.depth = depth;
+	}
+
+	/// Read a row, following the references it contains.
+	pub fn row(&self, sheet: &str, row_id: u32, subrow_id: u16) -> Result<Link> {
+		let row = self.excel.sheet(sheet)?.subrow(row_id, subrow_id)?;
+		self.link(sheet, &row, self.depth, &mut vec![])
+	}
+
+	/// Resolve the rows referenced by a field. `source` is the row containing
+	/// the field, against which reference conditions are evaluated. Fields of
+	/// non-reference scalars resolve to no rows.
+	pub fn resolve(&self, scalar: &Scalar, field: &Field, source: &Value) -> Result<Vec<Link>> {
+		let Scalar::Reference(targets) = scalar else {
+			return Ok(vec![]);
+		};
+
+		self.targets(targets, field, source, self.depth, &mut vec![])
+	}
+
+	fn link(
+		&self,
+		sheet: &str,
+		row: &Row,
+		depth: usize,
+		visited: &mut Vec<RowKey>,
+	) -> Result<Link> {
+		let mut link = Link {
+			sheet: sheet.to_string(),
+			row_id: row.row_id(),
+			subrow_id: row.subrow_id(),
+			value: None,
+			links: BTreeMap::new(),
+		};
+
+		// Without a schema, there is no way to read the row or find its references.
+		let Some(schema) = self.sheet(sheet)? else {
+			return Ok(link);
+		};
+		let value = Value::read(&schema, row)?;
+
+		let key = (sheet.to_string(), row.row_id(), row.subrow_id());
+		if depth > 0 && !visited.contains(&key) {
+			visited.push(key);
+			let mut references = vec![];
+			collect_references(&schema.node, &value, String::new(), &mut references);
+			for (path, targets, field) in references {
+				let found = self.targets(targets, field, &value, depth, visited)?;
+				if !found.is_empty() {
+					link.links.insert(path, found);
+				}
+			}
+			visited.pop();
+		}
+
+		link.value = Some(value);
+		Ok(link)
+	}
+
+	fn targets(
+		&self,
+		targets: &[ReferenceTarget],
+		field: &Field,
+		source: &Value,
+		depth: usize,
+		visited: &mut Vec<RowKey>,
+	) -> Result<Vec<Link>> {
+		let Some(key) = field_key(field) else {
+			return Ok(vec![]);
+		};
+
+		let mut links = vec![];
+		for target in targets {
+			if !condition_holds(target, source) {
+				continue;
+			}
+
+			for row in self.target_rows(target, key)? {
+				links.push(self.link(&target.sheet, &row, depth.saturating_sub(1), visited)?);
+			}
+		}
+
+		Ok(links)
+	}
+
+	fn target_rows(&self, target: &ReferenceTarget, key: i64) -> Result<Vec<Row>> {
+		let sheet = match self.excel.sheet(target.sheet.as_str()) {
+			Err(ironworks::Error::NotFound(_)) => return Ok(vec![]),
+			other => other?,
+		};
+
+		// Without a selector, the key is the target row's ID.
+		let Some(selector) = &target.selector else {
+			let Ok(row_id) = u32::try_from(key) else {
+				return Ok(vec![]);
+			};
+			return subrows(&sheet, row_id);
+		};
+
+		// Selected columns are looked up through the sheet's cached index of the
+		// column the selector reads.
+		let Some(schema) = self.sheet(&target.sheet)? else {
+			return Ok(vec![]);
+		};
+		let columns = sheet.columns()?;
+		let column = column_paths(&schema, &columns)
+			.iter()
+			.position(|path| path.as_deref() == Some(selector.as_str()));
+		let Some(column) = column else {
+			return Ok(vec![]);
+		};
+		// Floating point fields are never used as keys, and cannot be indexed.
+		if columns[column].kind() == ColumnKind::Float32 {
+			return Ok(vec![]);
+		}
+
+		sheet
+			.index(column)?
+			.get(key)
+			.iter()
+			.map(|&(row_id, subrow_id)| Ok(sheet.subrow(row_id, subrow_id)?))
+			.collect()
+	}
+
+	/// The schema for a sheet. `None` if the schema does not describe it.
+	fn sheet(&self, name: &str) -> Result<Option<Arc<Sheet>>> {
+		let mut sheets = self.sheets.lock().unwrap();
+		if let Some(sheet) = sheets.get(name) {
+			return Ok(sheet.clone());
+		}
+
+		let sheet = match self.schema.sheet(name) {
+			Ok(sheet) => Some(Arc::new(sheet)),
+			Err(Error::NotFound(_)) => None,
+			Err(error) => return Err(error),
+		};
+		sheets.insert(name.to_string(), sheet.clone());
+		Ok(sheet)
+	}
+}
+
+impl<R> fmt::Debug for Resolver<'_, R> {
+	fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		formatter
+			.debug_struct("Resolver")
+			.field("depth", &self.depth)
+			.finish_non_exhaustive()
+	}
+}
+
+/// Read every subrow of a row, in subrow order. Rows of sheets without subrows
+/// are read as a single subrow.
+fn subrows<R: Resource>(sheet: &excel::Sheet<&str, R>, row_id: u32) -> Result<Vec<Row>> {
+	let mut rows = vec![];
+	for subrow_id in 0..=u16::MAX {
+		match sheet.subrow(row_id, subrow_id) {
+			Ok(row) => rows.push(row),
+			Err(ironworks::Error::NotFound(_)) => break,
+			Err(error) => return Err(error.into()),
+		}
+	}
+	Ok(rows)
+}
+
+/// Check if the condition on a reference target, if any, holds for the row
+/// containing the reference.
+pub(super) fn condition_holds(target: &ReferenceTarget, source: &Value) -> bool {
+	target.condition.as_ref().is_none_or(|condition| {
+		source
+			.get(&condition.selector)
+			.and_then(Value::as_field)
+			.and_then(field_key)
+			== Some(condition.value.into())
+	})
+}
+
+/// Collect the reference fields within a value, along with their paths.
+pub(super) fn collect_references<'a>(
+	node: &'a Node,
+	value: &'a Value,
+	path: String,
+	references: &mut Vec<(String, &'a [ReferenceTarget], &'a Field)>,
+) {
+	match (node, value) {
+		(Node::Array { node, .. }, Value::Array(values)) => {
+			for (index, value) in values.iter().enumerate() {
+				collect_references(node, value, format!("{path}[{index}]"), references);
+			}
+		}
+
+		(Node::Struct(fields), Value::Struct(values)) => {
+			for (field, (name, value)) in fields.iter().zip(values) {
+				let path = match path.is_empty() {
+					true => name.clone(),
+					false => format!("{path}.{name}"),
+				};
+				collect_references(&field.node, value, path, references);
+			}
+		}
+
+		(Node::Scalar(Scalar::Reference(targets)), Value::Scalar(field)) => {
+			references.push((path, targets, field));
+		}
+
+		_ => {}
+	}
+}
+
+/// Numeric value of a field, as used to key references.
+pub(super) fn field_key(field: &Field) -> Option<i64> {
+	let key = match *field {
+		Field::Bool(value) => value.into(),
+		Field::I8(value) => value.into(),
+		Field::I16(value) => value.into(),
+		Field::I32(value) => value.into(),
+		Field::I64(value) => value,
+		Field::U8(value) => value.into(),
+		Field::U16(value) => value.into(),
+		Field::U32(value) => value.into(),
+		Field::U64(value) => value.try_into().ok()?,
+		Field::String(_) | Field::F32(_) => return None,
+	};
+	Some(key)
+}
+
+#[cfg(test)]
+mod test {
+	use ironworks::{
+		excel::{Field, Language, SheetWriter},
+		file::exh::{ColumnDefinition, ColumnKind, SheetKind},
+	};
+
+	use crate::{
+		excel::{
+			fixture::{Fixture, reference_excel, reference_schema},
+			value::Value,
+		},
+		schema::{ReferenceTarget, Scalar},
+	};
+
+	use super::Resolver;
+
+	#[test]
+	fn follows_reference_chains() {
+		let (excel, schema) = (reference_excel(), reference_schema());
+		let item = Resolver::new(&excel, &schema)
+			.with_depth(2)
+			.row("Item", 1, 0)
+			.unwrap();
+
+		let category = &item.links["Category"];
+		assert_eq!(category.len(), 1);
+		assert_eq!(
+			(category[0].sheet.as_str(), category[0].row_id),
+			("ItemUICategory", 5)
+		);
+
+		// The category links back to the item, which is not expanded again.
+		let back = &category[0].links["Item"];
+		assert_eq!((back[0].sheet.as_str(), back[0].row_id), ("Item", 1));
+		assert!(back[0].links.is_empty());
+
+		// Only the quest target's condition holds, and it's selected by key.
+		let link = &item.links["Link"];
+		assert_eq!(link.len(), 1);
+		assert_eq!((link[0].sheet.as_str(), link[0].row_id), ("Quest", 1));
+	}
+
+	#[test]
+	fn links_rows_without_a_schema() {
+		let (excel, schema) = (reference_excel(), reference_schema());
+		let item = Resolver::new(&excel, &schema)
+			.with_depth(2)
+			.row("Item", 2, 0)
+			.unwrap();
+
+		// The action target's condition holds, but the schema doesn't describe it.
+		let link = &item.links["Link"];
+		assert_eq!(link.len(), 1);
+		assert_eq!((link[0].sheet.as_str(), link[0].row_id), ("Action", 7));
+		assert!(link[0].value.is_none());
+		assert!(link[0].links.is_empty());
+	}
+
+	#[test]
+	fn stops_at_depth() {
+		let (excel, schema) = (reference_excel(), reference_schema());
+		let item = Resolver::new(&excel, &schema).row("Item", 1, 0).unwrap();
+		assert!(item.links["Category"][0].links.is_empty());
+
+		let item = Resolver::new(&excel, &schema)
+			.with_depth(0)
+			.row("Item", 1, 0)
+			.unwrap();
+		assert!(item.links.is_empty());
+	}
+
+	#[test]
+	fn links_every_subrow() {
+		let mut recipe = SheetWriter::new(
+			"Recipe",
+			SheetKind::Subrows,
+			vec![ColumnDefinition::new(ColumnKind::UInt8, 0)],
+		);
+		for subrow_id in [0, 1, 2] {
+			recipe
+				.set_row(Language::None, 3, subrow_id, vec![Field::U8(1)])
+				.unwrap();
+		}
+		let excel = Fixture::new().with_writer("Recipe", &recipe).excel();
+
+		let scalar = Scalar::Reference(vec![ReferenceTarget {
+			sheet: "Recipe".into(),
+			selector: None,
+			condition: None,
+		}]);
+		let links = Resolver::new(&excel, &reference_schema())
+			.resolve(&scalar, &Field::U16(3), &Value::Struct(vec![]))
+			.unwrap();
+
+		let subrows = links
+			.iter()
+			.map(|link| (link.row_id, link.subrow_id))
+			.collect::<Vec<_>>();
+		assert_eq!(subrows, [(3, 0), (3, 1), (3, 2)]);
+	}
+}
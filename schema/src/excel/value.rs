@@ -0,0 +1,290 @@
+use ironworks::{
+	excel::{Field, Row},
+	file::exh,
+};
+
+use crate::{
+	error::Result,
+	schema::{Node, Order, Sheet},
+};
+
+/// A value read from an Excel row, structured following a sheet schema.
+#[derive(Debug)]
+pub enum Value {
+	/// Values read from an array node, in order.
+	Array(Vec<Value>),
+
+	/// A single field read from a column.
+	Scalar(Field),
+
+	/// Values read from a struct node, keyed by field name in schema order.
+	Struct(Vec<(String, Value)>),
+
+	/// A column described by the schema that does not exist in the sheet.
+	Missing,
+}
+
+impl Value {
+	/// Read a row following the schema for its sheet.
+	pub fn read(sheet: &Sheet, row: &Row) -> Result<Self> {
+		let columns = column_order(sheet, row.columns())
+			.into_iter()
+			.map(|index| row.columns()[index].clone())
+			.collect::<Vec<_>>();
+
+		read_node(&sheet.node, &columns, 0, row)
+	}
+
+	/// Get a value nested within this value by path, i.e. `Item[3].Amount`. An
+	/// empty path refers to this value.
+	pub fn get(&self, path: &str) -> Option<&Value> {
+		let mut value = self;
+
+		for segment in path.split('.') {
+			let mut parts = segment.split('[');
+
+			let name = parts.next().unwrap_or_default();
+			if !name.is_empty() {
+				value = value.field(name)?;
+			}
+
+			for index in parts {
+				let index = index.strip_suffix(']')?.parse::<usize>().ok()?;
+				value = value.index(index)?;
+			}
+		}
+
+		Some(value)
+	}
+
+	/// Get the field of a scalar value.
+	pub fn as_field(&self) -> Option<&Field> {
+		match self {
+			Self::Scalar(field) => Some(field),
+			_ => None,
+		}
+	}
+
+	fn field(&self, name: &str) -> Option<&Value> {
+		match self {
+			Self::Struct(fields) => fields
+				.iter()
+				.find(|(field_name, _)| field_name == name)
+				.map(|(_, value)| value),
+			_ => None,
+		}
+	}
+
+	fn index(&self, index: usize) -> Option<&Value> {
+		match self {
+			Self::Array(values) => values.get(index),
+			_ => None,
+		}
+	}
+}
+
+/// Indices of a sheet's columns, in the order they are described by the schema.
+fn column_order(sheet: &Sheet, columns: &[exh::ColumnDefinition]) -> Vec<usize> {
+	let mut order = (0..columns.len()).collect::<Vec<_>>();
+	if sheet.order == Order::Offset {
+		// Packed booleans share an offset, and are ordered by the bit they read.
+		order.sort_by_key(|&index| (columns[index].offset(), u16::from(columns[index].kind())));
+	}
+	order
+}
+
+/// Paths of the schema fields reading each of a sheet's columns, in column
+/// order. Columns the schema does not describe have no path.
+pub(super) fn column_paths(
+	sheet: &Sheet,
+	columns: &[exh::ColumnDefinition],
+) -> Vec<Option<String>> {
+	let order = column_order(sheet, columns);
+	let mut paths = vec![None; columns.len()];
+	collect_paths(&sheet.node, 0, String::new(), &order, &mut paths);
+	paths
+}
+
+fn collect_paths(
+	node: &Node,
+	offset: usize,
+	path: String,
+	order: &[usize],
+	paths: &mut [Option<String>],
+) {
+	match node {
+		Node::Array { count, node } => {
+			let size = node.size() as usize;
+			for index in 0..*count as usize {
+				let path = format!("{path}[{index}]");
+				collect_paths(node, offset + index * size, path, order, paths);
+			}
+		}
+
+		Node::Scalar(_) => {
+			if let Some(&column) = order.get(offset) {
+				paths[column] = Some(path).filter(|path| !path.is_empty());
+			}
+		}
+
+		Node::Struct(fields) => {
+			for field in fields {
+				let path = match path.is_empty() {
+					true => field.name.clone(),
+					false => format!("{path}.{}", field.name),
+				};
+				collect_paths(
+					&field.node,
+					offset + field.offset as usize,
+					path,
+					order,
+					paths,
+				);
+			}
+		}
+	}
+}
+
+fn read_node(
+	node: &Node,
+	columns: &[exh::ColumnDefinition],
+	offset: usize,
+	row: &Row,
+) -> Result<Value> {
+	let value = match node {
+		Node::Array { count, node } => {
+			let size = node.size() as usize;
+			let values = (0..*count as usize)
+				.map(|index| read_node(node, columns, offset + index * size, row))
+				.collect::<Result<_>>()?;
+			Value::Array(values)
+		}
+
+		// Schemas may describe more columns than a sheet contains.
+		Node::Scalar(_) => match columns.get(offset) {
+			Some(column) => Value::Scalar(row.field(column)?),
+			None => Value::Missing,
+		},
+
+		Node::Struct(fields) => {
+			let values = fields
+				.iter()
+				.map(|field| {
+					let value =
+						read_node(&field.node, columns, offset + field.offset as usize, row)?;
+					Ok((field.name.clone(), value))
+				})
+				.collect::<Result<_>>()?;
+			Value::Struct(values)
+		}
+	};
+
+	Ok(value)
+}
+
+#[cfg(test)]
+mod test {
+	use ironworks::{excel::Field, file::exh::ColumnKind};
+
+	use crate::{
+		excel::fixture::Fixture,
+		schema::{Node, Order, Scalar, Sheet, StructField},
+	};
+
+	use super::Value;
+
+	fn field(offset: u32, name: &str, node: Node) -> StructField {
+		StructField {
+			offset,
+			name: name.into(),
+			node,
+		}
+	}
+
+	fn sheet(order: Order) -> Sheet {
+		let scalar = || Node::Scalar(Scalar::Default);
+		let item = Node::Struct(vec![field(0, "Id", scalar()), field(1, "Amount", scalar())]);
+		Sheet {
+			name: "Recipe".into(),
+			order,
+			node: Node::Struct(vec![
+				field(0, "Flag", scalar()),
+				field(
+					1,
+					"Item",
+					Node::Array {
+						count: 2,
+						node: Box::new(item),
+					},
+				),
+				field(5, "Extra", scalar()),
+			]),
+		}
+	}
+
+	fn read(order: Order) -> Value {
+		// Columns are declared out of offset order, with the flag last.
+		let excel = Fixture::new()
+			.with_sheet(
+				"Recipe",
+				&[
+					(ColumnKind::UInt16, 1),
+					(ColumnKind::UInt8, 3),
+					(ColumnKind::UInt16, 4),
+					(ColumnKind::UInt8, 6),
+					(ColumnKind::Bool, 0),
+				],
+				[(
+					1,
+					vec![
+						Field::U16(10),
+						Field::U8(2),
+						Field::U16(20),
+						Field::U8(4),
+						Field::Bool(true),
+					],
+				)],
+			)
+			.excel();
+		let row = excel.sheet("Recipe").unwrap().row(1).unwrap();
+		Value::read(&sheet(order), &row).unwrap()
+	}
+
+	#[test]
+	fn reads_by_offset() {
+		let value = read(Order::Offset);
+		assert!(matches!(
+			value.get("Flag"),
+			Some(Value::Scalar(Field::Bool(true)))
+		));
+		assert!(matches!(
+			value.get("Item[0].Id"),
+			Some(Value::Scalar(Field::U16(10)))
+		));
+		assert!(matches!(
+			value.get("Item[1].Id"),
+			Some(Value::Scalar(Field::U16(20)))
+		));
+		assert!(matches!(
+			value.get("Item[1].Amount"),
+			Some(Value::Scalar(Field::U8(4)))
+		));
+		assert!(matches!(value.get("Extra"), Some(Value::Missing)));
+	}
+
+	#[test]
+	fn reads_by_index() {
+		let value = read(Order::Index);
+		assert!(matches!(
+			value.get("Flag"),
+			Some(Value::Scalar(Field::U16(10)))
+		));
+		assert!(matches!(
+			value.get("Item[1].Amount"),
+			Some(Value::Scalar(Field::Bool(true)))
+		));
+		assert!(value.get("Item[2]").is_none());
+		assert!(value.get("Item.Id").is_none());
+		assert!(matches!(value.get(""), Some(Value::Struct(_))));
+	}
+}
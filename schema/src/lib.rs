@@ -11,6 +11,8 @@ mod schema;
 #[cfg(feature = "git2")]
 mod git;
 
+#[cfg(feature = "excel")]
+pub mod excel;
 #[cfg(feature = "exdschema")]
 pub mod exdschema;
 